@@ -0,0 +1,2 @@
+DELETE FROM "prekeys"
+WHERE "owner"=?1;
\ No newline at end of file
@@ -0,0 +1,2 @@
+DELETE FROM "sender_keys"
+WHERE "owner"=?1;
\ No newline at end of file
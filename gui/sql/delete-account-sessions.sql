@@ -0,0 +1,2 @@
+DELETE FROM "sessions"
+WHERE "owner"=?1;
\ No newline at end of file
@@ -1,2 +1,2 @@
 DELETE FROM "contacts"
-WHERE "pk"=?1;
\ No newline at end of file
+WHERE "owner"=?1 AND "pk"=?2;
\ No newline at end of file
@@ -0,0 +1,2 @@
+DELETE FROM "messages"
+WHERE "owner"=?1 AND "hash"=?2;
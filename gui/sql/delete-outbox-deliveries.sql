@@ -0,0 +1,2 @@
+DELETE FROM "outbox_deliveries"
+WHERE "outbox"=?1;
\ No newline at end of file
@@ -0,0 +1,2 @@
+DELETE FROM "pending_group_messages"
+WHERE "owner"=?1 AND "group"=?2 AND "sender"=?3;
\ No newline at end of file
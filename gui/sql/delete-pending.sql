@@ -0,0 +1,2 @@
+DELETE FROM "pending"
+WHERE "hash"=?1;
\ No newline at end of file
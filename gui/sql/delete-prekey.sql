@@ -0,0 +1,2 @@
+DELETE FROM "prekeys"
+WHERE "owner"=?1 AND "public"=?2 AND "signed"=0;
\ No newline at end of file
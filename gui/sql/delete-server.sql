@@ -0,0 +1,2 @@
+DELETE FROM "servers"
+WHERE "url"=?1;
\ No newline at end of file
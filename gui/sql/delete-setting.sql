@@ -0,0 +1,2 @@
+DELETE FROM "settings"
+WHERE "key"=?1;
\ No newline at end of file
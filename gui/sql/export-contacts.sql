@@ -0,0 +1,2 @@
+SELECT "owner", "pk", "name", "timer", "verified"
+FROM "contacts";
\ No newline at end of file
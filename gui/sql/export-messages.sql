@@ -0,0 +1,2 @@
+SELECT "hash", "sender", "receiver", "data_hash", "timestamp", "success", "expires_at", "verified", "owner", "read", "group"
+FROM "messages";
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT "pk"
+FROM "accounts"
+WHERE "pk"=?1;
\ No newline at end of file
@@ -1,2 +1,2 @@
-SELECT "sk", "name"
-FROM "accounts";
+SELECT "sk", "name", "server", "pk"
+FROM "accounts";
\ No newline at end of file
@@ -1,2 +1,3 @@
-SELECT "pk", "name"
-FROM "contacts";
+SELECT "pk", "name", "timer", "verified"
+FROM "contacts"
+WHERE "owner"=?1;
\ No newline at end of file
@@ -1,3 +0,0 @@
-SELECT 1
-FROM "messages"
-WHERE "hash"=?1;
\ No newline at end of file
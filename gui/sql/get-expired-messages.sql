@@ -0,0 +1,3 @@
+SELECT "owner", "hash", "data_hash"
+FROM "messages"
+WHERE "expires_at"<=?1;
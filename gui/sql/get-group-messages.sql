@@ -0,0 +1,4 @@
+SELECT "hash", "sender", "receiver", "data_hash", "timestamp", "success", "expires_at", "verified", "group", "owner"
+FROM "messages"
+WHERE "owner"=?1 AND "group"=?2
+ORDER BY "timestamp" ASC;
\ No newline at end of file
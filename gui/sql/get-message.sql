@@ -1,3 +1,3 @@
 SELECT 1
 FROM "messages"
-WHERE "hash"=?1;
\ No newline at end of file
+WHERE "owner"=?1 AND "hash"=?2;
\ No newline at end of file
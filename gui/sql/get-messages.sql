@@ -1,4 +1,4 @@
-SELECT "hash", "sender", "receiver", "data_hash", "timestamp", "success"
+SELECT "hash", "sender", "receiver", "data_hash", "timestamp", "success", "expires_at", "verified", "group", "owner"
 FROM "messages"
-WHERE "sender"=?1 AND "receiver"=?2 OR "sender"=?2 AND "receiver"=?1
+WHERE "owner"=?1 AND ("sender"=?1 AND "receiver"=?2 OR "sender"=?2 AND "receiver"=?1) AND "group" IS NULL
 ORDER BY "timestamp" ASC;
\ No newline at end of file
@@ -0,0 +1,4 @@
+SELECT "device", "hash", "data_hash", "timestamp"
+FROM "outbox_deliveries"
+WHERE "outbox"=?1
+ORDER BY "rowid";
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT "id", "sender", "receiver", COALESCE("local", "data"), "ttl", "timestamp", "attempts", "next_attempt_at"
+FROM "outbox"
+ORDER BY "timestamp" ASC;
\ No newline at end of file
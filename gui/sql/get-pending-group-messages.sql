@@ -0,0 +1,4 @@
+SELECT "hash", "receiver", "data_hash", "timestamp", "success", "data"
+FROM "pending_group_messages"
+WHERE "owner"=?1 AND "group"=?2 AND "sender"=?3
+ORDER BY "timestamp" ASC;
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT "hash", "sender", "receiver", "data_hash", "timestamp", "success", "sealed", "group"
+FROM "pending"
+ORDER BY "timestamp" ASC;
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT "secret"
+FROM "prekeys"
+WHERE "owner"=?1 AND "public"=?2;
\ No newline at end of file
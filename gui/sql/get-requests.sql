@@ -0,0 +1,4 @@
+SELECT "hash", "sender", "receiver", "data_hash", "timestamp", "success", "expires_at", "verified", "group", "owner"
+FROM "messages"
+WHERE "owner"=?1 AND "receiver"=?1 AND "group" IS NULL AND "sender" NOT IN (SELECT "pk" FROM "contacts" WHERE "owner"=?1)
+ORDER BY "timestamp" ASC;
\ No newline at end of file
@@ -0,0 +1,4 @@
+SELECT "id", "state"
+FROM "sender_keys"
+WHERE "owner"=?1 AND "group"=?2 AND "sender"=?3
+ORDER BY "used_at" DESC;
\ No newline at end of file
@@ -0,0 +1,2 @@
+SELECT "url", "ca", "fingerprint"
+FROM "servers";
\ No newline at end of file
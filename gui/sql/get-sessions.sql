@@ -0,0 +1,4 @@
+SELECT "id", "state", "device"
+FROM "sessions"
+WHERE "owner"=?1 AND "peer"=?2
+ORDER BY "used_at" DESC;
\ No newline at end of file
@@ -0,0 +1,2 @@
+SELECT "key", "value"
+FROM "settings";
\ No newline at end of file
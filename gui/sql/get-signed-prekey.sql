@@ -0,0 +1,5 @@
+SELECT "public", "created_at"
+FROM "prekeys"
+WHERE "owner"=?1 AND "signed"=1
+ORDER BY "created_at" DESC
+LIMIT 1;
\ No newline at end of file
@@ -0,0 +1,4 @@
+SELECT "owner", COUNT(*)
+FROM "messages"
+WHERE "owner"="receiver" AND "read"=0
+GROUP BY "owner";
\ No newline at end of file
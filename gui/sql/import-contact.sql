@@ -0,0 +1,7 @@
+INSERT INTO "contacts" ("owner", "pk", "name", "timer", "verified")
+SELECT ?1, ?2, ?3, ?4, ?5
+WHERE NOT EXISTS (
+    SELECT 1
+    FROM "contacts"
+    WHERE "owner"=?1 AND "pk"=?2
+);
\ No newline at end of file
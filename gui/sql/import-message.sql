@@ -0,0 +1,7 @@
+INSERT INTO "messages" ("hash", "sender", "receiver", "data_hash", "timestamp", "success", "expires_at", "verified", "owner", "read", "group")
+SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
+WHERE NOT EXISTS (
+    SELECT 1
+    FROM "messages"
+    WHERE "owner"=?9 AND "hash"=?1
+);
\ No newline at end of file
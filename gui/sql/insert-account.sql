@@ -1,2 +1,2 @@
-INSERT INTO "accounts" ("sk", "pk", "name")
-VALUES (?1, ?2, ?3);
+INSERT INTO "accounts" ("sk", "pk", "name", "server")
+VALUES (?1, ?2, ?3, ?4);
\ No newline at end of file
@@ -1,2 +1,2 @@
-INSERT INTO "contacts" ("pk", "name")
-VALUES (?1, ?2);
+INSERT INTO "contacts" ("owner", "pk", "name", "timer")
+VALUES (?1, ?2, ?3, ?4);
\ No newline at end of file
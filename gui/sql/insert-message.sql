@@ -1,2 +1,2 @@
-INSERT INTO "messages" ("hash", "sender", "receiver", "data_hash", "timestamp", "success")
-VALUES (?1, ?2, ?3, ?4, ?5, ?6);
\ No newline at end of file
+INSERT OR IGNORE INTO "messages" ("hash", "sender", "receiver", "data_hash", "timestamp", "success", "expires_at", "verified", "group", "owner")
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10);
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO "outbox_deliveries" ("outbox", "device", "hash", "data_hash", "timestamp")
+VALUES (?1, ?2, ?3, ?4, ?5);
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO "outbox" ("sender", "receiver", "ttl", "timestamp", "attempts", "next_attempt_at", "local")
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7);
\ No newline at end of file
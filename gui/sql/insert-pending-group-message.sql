@@ -0,0 +1,2 @@
+INSERT OR IGNORE INTO "pending_group_messages" ("owner", "hash", "sender", "receiver", "group", "data_hash", "timestamp", "success", "data")
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9);
\ No newline at end of file
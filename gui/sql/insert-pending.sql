@@ -0,0 +1,2 @@
+INSERT OR IGNORE INTO "pending" ("hash", "sender", "receiver", "data_hash", "timestamp", "success", "sealed", "group")
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8);
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO "prekeys" ("owner", "public", "secret", "signed", "created_at")
+VALUES (?1, ?2, ?3, ?4, ?5);
\ No newline at end of file
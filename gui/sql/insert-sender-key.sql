@@ -0,0 +1,4 @@
+INSERT INTO "sender_keys" ("owner", "group", "sender", "id", "state", "used_at")
+VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+ON CONFLICT ("owner", "group", "sender", "id") DO UPDATE
+SET "state"=excluded."state", "used_at"=excluded."used_at";
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO "sessions" ("owner", "peer", "state", "used_at", "device")
+VALUES (?1, ?2, ?3, ?4, ?5);
\ No newline at end of file
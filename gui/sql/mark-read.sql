@@ -0,0 +1,3 @@
+UPDATE "messages"
+SET "read"=1
+WHERE "owner"=?1 AND "receiver"=?1 AND "sender"=?2 AND "group" IS NULL;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE "contacts" ADD COLUMN "timer" INTEGER;
+ALTER TABLE "messages" ADD COLUMN "expires_at" INTEGER;
@@ -0,0 +1,20 @@
+ALTER TABLE "contacts" ADD COLUMN "owner" VARCHAR(44);
+INSERT INTO "contacts" ("pk", "name", "timer", "owner")
+SELECT DISTINCT "contacts"."pk", "contacts"."name", "contacts"."timer", "accounts"."pk"
+FROM "contacts", "accounts"
+WHERE "contacts"."owner" IS NULL AND EXISTS (
+    SELECT 1
+    FROM "messages"
+    WHERE "messages"."sender"="accounts"."pk" AND "messages"."receiver"="contacts"."pk" OR
+        "messages"."sender"="contacts"."pk" AND "messages"."receiver"="accounts"."pk"
+);
+INSERT INTO "contacts" ("pk", "name", "timer", "owner")
+SELECT DISTINCT "contacts"."pk", "contacts"."name", "contacts"."timer", "accounts"."pk"
+FROM "contacts", "accounts"
+WHERE "contacts"."owner" IS NULL AND NOT EXISTS (
+    SELECT 1
+    FROM "messages"
+    WHERE "messages"."sender"="contacts"."pk" OR "messages"."receiver"="contacts"."pk"
+);
+DELETE FROM "contacts"
+WHERE "owner" IS NULL;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS "settings" (
+    "key" VARCHAR(64) PRIMARY KEY,
+    "value" TEXT
+);
+ALTER TABLE "accounts" ADD COLUMN "server" VARCHAR(256);
+UPDATE "accounts"
+SET "server"='http://82.97.242.232:8080/';
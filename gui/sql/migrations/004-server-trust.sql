@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS "servers" (
+    "url" VARCHAR(256) PRIMARY KEY,
+    "ca" TEXT,
+    "fingerprint" VARCHAR(64)
+);
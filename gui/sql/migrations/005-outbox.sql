@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS "outbox" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "sender" VARCHAR(44),
+    "receiver" VARCHAR(44),
+    "data" BLOB,
+    "ttl" INTEGER,
+    "timestamp" INTEGER,
+    "attempts" INTEGER,
+    "next_attempt_at" INTEGER
+);
@@ -0,0 +1,3 @@
+ALTER TABLE "messages" ADD COLUMN "read" BOOLEAN NOT NULL DEFAULT 0;
+UPDATE "messages"
+SET "read"=1;
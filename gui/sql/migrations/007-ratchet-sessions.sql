@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS "sessions" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "owner" VARCHAR(44),
+    "peer" VARCHAR(44),
+    "state" BLOB,
+    "used_at" INTEGER
+);
+ALTER TABLE "outbox" ADD COLUMN "local" BLOB;
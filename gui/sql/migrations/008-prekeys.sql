@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS "prekeys" (
+    "owner" VARCHAR(44),
+    "public" BLOB,
+    "secret" BLOB,
+    "signed" BOOLEAN,
+    "created_at" INTEGER,
+    PRIMARY KEY ("owner", "public")
+);
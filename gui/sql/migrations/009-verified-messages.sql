@@ -0,0 +1,4 @@
+ALTER TABLE "messages" ADD COLUMN "verified" BOOLEAN NOT NULL DEFAULT 0;
+UPDATE "messages"
+SET "verified"=1
+WHERE "sender" IN (SELECT "pk" FROM "accounts");
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS "pending" (
+    "hash" VARCHAR(88) PRIMARY KEY,
+    "sender" VARCHAR(44),
+    "receiver" VARCHAR(44),
+    "data_hash" VARCHAR(88),
+    "timestamp" INTEGER,
+    "success" BOOLEAN
+);
@@ -0,0 +1 @@
+ALTER TABLE "pending" ADD COLUMN "sealed" BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+ALTER TABLE "messages" ADD COLUMN "group" VARCHAR(24);
+ALTER TABLE "pending" ADD COLUMN "group" VARCHAR(24);
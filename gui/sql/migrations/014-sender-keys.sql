@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS "sender_keys" (
+    "owner" VARCHAR(44),
+    "group" VARCHAR(24),
+    "sender" VARCHAR(44),
+    "id" INTEGER,
+    "state" BLOB,
+    "used_at" INTEGER,
+    PRIMARY KEY ("owner", "group", "sender", "id")
+);
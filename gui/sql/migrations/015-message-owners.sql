@@ -0,0 +1,3 @@
+ALTER TABLE "messages" ADD COLUMN "owner" VARCHAR(44);
+UPDATE "messages"
+SET "owner"=CASE WHEN "receiver" IN (SELECT "pk" FROM "accounts") THEN "receiver" ELSE "sender" END;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS "pending_group_messages" (
+    "owner" VARCHAR(44),
+    "hash" VARCHAR(88),
+    "sender" VARCHAR(44),
+    "receiver" VARCHAR(44),
+    "group" VARCHAR(24),
+    "data_hash" VARCHAR(88),
+    "timestamp" INTEGER,
+    "success" BOOLEAN,
+    "data" BLOB,
+    PRIMARY KEY ("owner", "hash")
+);
\ No newline at end of file
@@ -0,0 +1,7 @@
+DELETE FROM "messages"
+WHERE rowid NOT IN (
+    SELECT MIN(rowid)
+    FROM "messages"
+    GROUP BY "owner", "hash"
+);
+CREATE UNIQUE INDEX IF NOT EXISTS "messages_owner_hash" ON "messages" ("owner", "hash");
\ No newline at end of file
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS "outbox_deliveries" (
+    "outbox" INTEGER,
+    "device" BLOB,
+    "hash" BLOB,
+    "data_hash" BLOB,
+    "timestamp" INTEGER
+);
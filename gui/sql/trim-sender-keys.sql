@@ -0,0 +1,8 @@
+DELETE FROM "sender_keys"
+WHERE "owner"=?1 AND "group"=?2 AND "sender"=?3 AND "id" NOT IN (
+    SELECT "id"
+    FROM "sender_keys"
+    WHERE "owner"=?1 AND "group"=?2 AND "sender"=?3
+    ORDER BY "used_at" DESC
+    LIMIT ?4
+);
\ No newline at end of file
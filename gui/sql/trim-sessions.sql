@@ -0,0 +1,8 @@
+DELETE FROM "sessions"
+WHERE "owner"=?1 AND "peer"=?2 AND "device" IS ?4 AND "id" NOT IN (
+    SELECT "id"
+    FROM "sessions"
+    WHERE "owner"=?1 AND "peer"=?2 AND "device" IS ?4
+    ORDER BY "used_at" DESC
+    LIMIT ?3
+);
\ No newline at end of file
@@ -0,0 +1,2 @@
+DELETE FROM "prekeys"
+WHERE "owner"=?1 AND "signed"=1 AND "created_at"<?2;
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE "accounts"
+SET "sk"=?2
+WHERE "pk"=?1;
\ No newline at end of file
@@ -1,3 +1,3 @@
 UPDATE "accounts"
-SET "name"=?2
-WHERE "pk"=?1;
+SET "name"=?2, "server"=?3
+WHERE "pk"=?1;
\ No newline at end of file
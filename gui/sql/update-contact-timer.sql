@@ -0,0 +1,3 @@
+UPDATE "contacts"
+SET "timer"=?3
+WHERE "owner"=?1 AND "pk"=?2;
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE "contacts"
+SET "pk"=?3, "name"=?4
+WHERE "owner"=?1 AND "pk"=?2;
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE "outbox"
+SET "attempts"=?2, "next_attempt_at"=?3
+WHERE "id"=?1;
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO "servers" ("url", "ca", "fingerprint")
+VALUES (?1, ?2, ?3)
+ON CONFLICT ("url") DO UPDATE SET "ca"=?2, "fingerprint"=?3;
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE "sessions"
+SET "state"=?2, "used_at"=?3
+WHERE "id"=?1;
\ No newline at end of file
@@ -0,0 +1,84 @@
+use {
+	crate::{tasks, utils, vault},
+	std::{
+		io::{BufRead, Write},
+		path::PathBuf,
+	},
+	tokio_rusqlite::Connection,
+};
+
+fn prompt(label: &str) -> String {
+	eprint!("{}: ", label);
+	let _ = std::io::stderr().flush();
+
+	let mut line = String::new();
+	let _ = std::io::stdin().lock().read_line(&mut line);
+	line.trim_end_matches(['\r', '\n']).to_owned()
+}
+
+async fn profile(command: &str, path: PathBuf) -> Result<(), String> {
+	let basedir = utils::basedir();
+	let database = Connection::open(basedir.join("db.sqlite3"))
+		.await
+		.map_err(|err| format!("{:?}", err))?;
+	tasks::init_tables::run(database.clone())
+		.await
+		.map_err(|err| format!("{:?}", err))?;
+
+	let settings = tasks::get_settings::run(database.clone())
+		.await
+		.map_err(|err| format!("{:?}", err))?;
+	let key = match settings.passphrase {
+		Some(lock) => Some(
+			vault::unlock(&lock, &prompt("Local passphrase"))
+				.ok_or_else(|| String::from("Wrong passphrase"))?,
+		),
+		None => None,
+	};
+	let passphrase = prompt("Profile passphrase");
+	if passphrase.is_empty() {
+		return Err(String::from("The profile passphrase can't be empty"))
+	}
+
+	match command {
+		"export" => {
+			let accounts = tasks::get_accounts::run(database.clone(), key)
+				.await
+				.map_err(|err| format!("{:?}", err))?;
+			let count = accounts.len();
+			tasks::export_profile::run(basedir, database, key, accounts, path, passphrase)
+				.await
+				.map_err(|err| format!("{:?}", err))?;
+			eprintln!("Exported {} accounts", count);
+		},
+		_ => {
+			let accounts = tasks::import_profile::run(basedir, database, key, path, passphrase)
+				.await
+				.map_err(|err| format!("{:?}", err))?;
+			eprintln!("Imported {} new accounts", accounts.len());
+		},
+	}
+
+	Ok(())
+}
+
+/// Handles `export <file>` and `import <file>`, returning `None` when the GUI should start instead.
+pub(crate) fn run(args: &[String]) -> Option<i32> {
+	let [command, path] = args else { return None };
+	if command != "export" && command != "import" {
+		return None
+	}
+
+	let runtime = tokio::runtime::Builder::new_current_thread()
+		.enable_all()
+		.build()
+		.expect("Failed to start runtime");
+
+	match runtime.block_on(profile(command, PathBuf::from(path))) {
+		Ok(()) => Some(0),
+		Err(err) => {
+			eprintln!("{}", err);
+			Some(1)
+		},
+	}
+}
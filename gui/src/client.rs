@@ -1,13 +1,34 @@
 use {
 	borsh::io,
+	ed25519_dalek::VerifyingKey,
 	project_toot_sdk::{
+		DeviceId,
+		IBlockPayload,
+		ICaller,
+		ICountOneTimePrekeysPayload,
+		ICreateGroupPayload,
 		IDeleteMessagesPayload,
+		IFetchPrekeyBundlePayload,
 		IGetMessagesPayload,
+		IGroup,
+		IInviteMemberPayload,
+		ILeaveGroupPayload,
+		IListBlockedPayload,
+		IListDevicesPayload,
 		IMessage,
+		IPrekeyBundle,
+		IPublishOneTimePrekeysPayload,
+		IPublishSignedPrekeyPayload,
 		IRPCRequest,
 		IRPCResponse,
+		IRegisterDevicePayload,
+		IRemoveMemberPayload,
+		ISendGroupMessagePayload,
 		ISendMessagePayload,
+		ISendSealedMessagePayload,
+		ISetMemberRolePayload,
 		ISigner,
+		IUnblockPayload,
 	},
 	reqwest::{header::CONTENT_TYPE, Client, StatusCode, Url},
 };
@@ -46,11 +67,8 @@ pub(crate) struct ProjectTootClient {
 }
 
 impl ProjectTootClient {
-	pub fn new(baseurl: Url) -> Self {
-		Self {
-			baseurl,
-			http: Client::new(),
-		}
+	pub fn new(baseurl: Url, http: Client) -> Self {
+		Self { baseurl, http }
 	}
 
 	pub fn baseurl(&self) -> Url {
@@ -91,6 +109,15 @@ impl ProjectTootClient {
 		Ok(())
 	}
 
+	pub async fn send_sealed_message(
+		self,
+		payload: ISendSealedMessagePayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::SendSealedMessage(payload))
+			.await?;
+		Ok(())
+	}
+
 	pub async fn get_messages(
 		self,
 		signer: ISigner,
@@ -120,4 +147,180 @@ impl ProjectTootClient {
 			_ => panic!("Unexpected response"),
 		}
 	}
+
+	pub async fn block(self, signer: ISigner, payload: IBlockPayload) -> Result<(), ClientError> {
+		self.request(IRPCRequest::Block(signer, payload)).await?;
+		Ok(())
+	}
+
+	pub async fn unblock(
+		self,
+		signer: ISigner,
+		payload: IUnblockPayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::Unblock(signer, payload)).await?;
+		Ok(())
+	}
+
+	pub async fn list_blocked(
+		self,
+		signer: ISigner,
+		payload: IListBlockedPayload,
+	) -> Result<Vec<VerifyingKey>, ClientError> {
+		match self
+			.request(IRPCRequest::ListBlocked(signer, payload))
+			.await?
+			.1
+		{
+			IRPCResponse::ListBlocked(inner) => Ok(inner),
+			_ => panic!("Unexpected response"),
+		}
+	}
+
+	pub async fn publish_signed_prekey(
+		self,
+		signer: ISigner,
+		payload: IPublishSignedPrekeyPayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::PublishSignedPrekey(signer, payload))
+			.await?;
+		Ok(())
+	}
+
+	pub async fn publish_one_time_prekeys(
+		self,
+		signer: ISigner,
+		payload: IPublishOneTimePrekeysPayload,
+	) -> Result<u32, ClientError> {
+		match self
+			.request(IRPCRequest::PublishOneTimePrekeys(signer, payload))
+			.await?
+			.1
+		{
+			IRPCResponse::PublishOneTimePrekeys(inner) => Ok(inner),
+			_ => panic!("Unexpected response"),
+		}
+	}
+
+	pub async fn count_one_time_prekeys(
+		self,
+		signer: ISigner,
+		payload: ICountOneTimePrekeysPayload,
+	) -> Result<u32, ClientError> {
+		match self
+			.request(IRPCRequest::CountOneTimePrekeys(signer, payload))
+			.await?
+			.1
+		{
+			IRPCResponse::CountOneTimePrekeys(inner) => Ok(inner),
+			_ => panic!("Unexpected response"),
+		}
+	}
+
+	pub async fn fetch_prekey_bundle(
+		self,
+		caller: ICaller,
+		payload: IFetchPrekeyBundlePayload,
+	) -> Result<Option<IPrekeyBundle>, ClientError> {
+		match self
+			.request(IRPCRequest::FetchPrekeyBundle(caller, payload))
+			.await?
+			.1
+		{
+			IRPCResponse::FetchPrekeyBundle(inner) => Ok(inner),
+			_ => panic!("Unexpected response"),
+		}
+	}
+
+	pub async fn list_devices(
+		self,
+		caller: ICaller,
+		payload: IListDevicesPayload,
+	) -> Result<Vec<Option<DeviceId>>, ClientError> {
+		match self
+			.request(IRPCRequest::ListDevices(caller, payload))
+			.await?
+			.1
+		{
+			IRPCResponse::ListDevices(inner) => Ok(inner),
+			_ => panic!("Unexpected response"),
+		}
+	}
+
+	pub async fn register_device(
+		self,
+		signer: ISigner,
+		payload: IRegisterDevicePayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::RegisterDevice(signer, payload))
+			.await?;
+		Ok(())
+	}
+
+	pub async fn create_group(
+		self,
+		signer: ISigner,
+		payload: ICreateGroupPayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::CreateGroup(signer, payload))
+			.await?;
+		Ok(())
+	}
+
+	pub async fn invite_member(
+		self,
+		signer: ISigner,
+		payload: IInviteMemberPayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::InviteMember(signer, payload))
+			.await?;
+		Ok(())
+	}
+
+	pub async fn set_member_role(
+		self,
+		signer: ISigner,
+		payload: ISetMemberRolePayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::SetMemberRole(signer, payload))
+			.await?;
+		Ok(())
+	}
+
+	pub async fn remove_member(
+		self,
+		signer: ISigner,
+		payload: IRemoveMemberPayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::RemoveMember(signer, payload))
+			.await?;
+		Ok(())
+	}
+
+	pub async fn leave_group(
+		self,
+		signer: ISigner,
+		payload: ILeaveGroupPayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::LeaveGroup(signer, payload))
+			.await?;
+		Ok(())
+	}
+
+	pub async fn list_groups(self, signer: ISigner) -> Result<Vec<IGroup>, ClientError> {
+		match self.request(IRPCRequest::ListGroups(signer)).await?.1 {
+			IRPCResponse::ListGroups(inner) => Ok(inner),
+			_ => panic!("Unexpected response"),
+		}
+	}
+
+	pub async fn send_group_message(
+		self,
+		signer: ISigner,
+		payload: ISendGroupMessagePayload,
+	) -> Result<(), ClientError> {
+		self.request(IRPCRequest::SendGroupMessage(signer, payload))
+			.await?;
+		Ok(())
+	}
 }
@@ -0,0 +1,324 @@
+use {
+	crate::{group, ratchet::Header},
+	aes_siv::{
+		aead::{Aead, KeyInit, Payload},
+		Aes128SivAead,
+	},
+	borsh::{BorshDeserialize, BorshSerialize},
+	chacha20poly1305::XChaCha20Poly1305,
+	ed25519_dalek::{Signature, SigningKey, VerifyingKey},
+	sha2::{Digest, Sha512},
+	std::fmt,
+};
+
+pub(crate) const VERSION: u8 = 3;
+
+pub(crate) const DEFAULT_ALGORITHM: Algorithm = Algorithm::XChaCha20Poly1305;
+
+/// Length prefix in front of the plaintext of padded envelopes.
+const LENGTH_PREFIX: usize = 4;
+
+const MINIMUM_PADDED_LEN: usize = 64;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Algorithm {
+	Aes128Siv,
+	XChaCha20Poly1305,
+}
+
+impl Algorithm {
+	fn nonce_len(&self) -> usize {
+		match self {
+			Self::Aes128Siv => 16,
+			Self::XChaCha20Poly1305 => 24,
+		}
+	}
+}
+
+/// Bucket policy applied to plaintexts before encryption, hiding their exact length.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Padding {
+	None,
+	PowerOfTwo,
+	#[default]
+	Padme,
+}
+
+impl Padding {
+	pub(crate) const ALL: [Padding; 3] = [Self::None, Self::PowerOfTwo, Self::Padme];
+
+	pub(crate) fn name(&self) -> &'static str {
+		match self {
+			Self::None => "none",
+			Self::PowerOfTwo => "pow2",
+			Self::Padme => "padme",
+		}
+	}
+
+	pub(crate) fn parse(value: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|x| x.name() == value)
+	}
+
+	fn bucket(&self, len: usize) -> usize {
+		match self {
+			Self::None => len,
+			Self::PowerOfTwo => len.max(MINIMUM_PADDED_LEN).next_power_of_two(),
+			Self::Padme => {
+				let len = len.max(MINIMUM_PADDED_LEN);
+				let exponent = len.ilog2();
+				let mask = (1usize << (exponent - exponent.ilog2() - 1)) - 1;
+				(len + mask) & !mask
+			},
+		}
+	}
+
+	fn pad(&self, plaintext: &[u8]) -> Vec<u8> {
+		let mut ret = Vec::<u8>::new();
+		ret.extend((plaintext.len() as u32).to_le_bytes());
+		ret.extend(plaintext);
+		ret.resize(self.bucket(ret.len()), 0);
+		ret
+	}
+}
+
+fn unpad(buf: Vec<u8>) -> Result<Vec<u8>, DecryptError> {
+	let Some(prefix) = buf.get(..LENGTH_PREFIX) else {
+		return Err(DecryptError::Malformed)
+	};
+	let len = u32::from_le_bytes(prefix.try_into().unwrap()) as usize;
+
+	buf.get(LENGTH_PREFIX..LENGTH_PREFIX + len)
+		.map(|x| x.to_vec())
+		.ok_or(DecryptError::Malformed)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub(crate) enum Scheme {
+	Sealed,
+	Ratchet(Header),
+	SealedSender([u8; 32]),
+	Group(group::Header),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub(crate) struct Envelope {
+	pub version: u8,
+	pub algorithm: Algorithm,
+	pub scheme: Scheme,
+	pub nonce: Vec<u8>,
+	pub ciphertext: Vec<u8>,
+	pub signature: Option<[u8; 64]>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DecryptError {
+	Missing,
+	Malformed,
+	UnsupportedVersion(u8),
+	UnsupportedFormat,
+	Authentication,
+	NoSession,
+	InvalidPayload,
+}
+
+impl fmt::Display for DecryptError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Missing => write!(f, "Message data is missing"),
+			Self::Malformed => write!(f, "Malformed envelope"),
+			Self::UnsupportedVersion(version) => {
+				write!(f, "Unsupported envelope version {}", version)
+			},
+			Self::UnsupportedFormat => write!(f, "Unsupported message format"),
+			Self::Authentication => write!(f, "Message authentication failed"),
+			Self::NoSession => write!(f, "No session can decrypt this message"),
+			Self::InvalidPayload => write!(f, "Invalid message payload"),
+		}
+	}
+}
+
+pub(crate) fn binding(sender: &VerifyingKey, receiver: &VerifyingKey, timestamp: i64) -> Vec<u8> {
+	let mut ret = Vec::<u8>::new();
+	ret.extend(sender.as_bytes());
+	ret.extend(receiver.as_bytes());
+	ret.extend(timestamp.to_le_bytes());
+	ret
+}
+
+pub(crate) fn decode(data: &[u8]) -> Result<Envelope, DecryptError> {
+	match data.first() {
+		Some(1) => borsh::from_slice::<(u8, Algorithm, Scheme, Vec<u8>, Vec<u8>)>(data)
+			.map(|(version, algorithm, scheme, nonce, ciphertext)| Envelope {
+				version,
+				algorithm,
+				scheme,
+				nonce,
+				ciphertext,
+				signature: None,
+			})
+			.or(Err(DecryptError::Malformed)),
+		Some(2) | Some(&VERSION) => {
+			borsh::from_slice::<Envelope>(data).or(Err(DecryptError::Malformed))
+		},
+		Some(version) => Err(DecryptError::UnsupportedVersion(*version)),
+		None => Err(DecryptError::Malformed),
+	}
+}
+
+pub(crate) fn seal(
+	algorithm: Algorithm,
+	padding: Padding,
+	key: &[u8; 32],
+	scheme: Scheme,
+	signer: Option<&SigningKey>,
+	binding: &[u8],
+	plaintext: &[u8],
+) -> Vec<u8> {
+	let mut envelope = Envelope {
+		version: VERSION,
+		algorithm,
+		scheme,
+		nonce: (0..algorithm.nonce_len())
+			.map(|_| rand::random::<u8>())
+			.collect::<Vec<_>>(),
+		ciphertext: Vec::new(),
+		signature: None,
+	};
+	let plaintext = padding.pad(plaintext);
+	let payload = Payload {
+		msg: &plaintext,
+		aad: &envelope.associated_data(binding),
+	};
+
+	envelope.ciphertext = match algorithm {
+		Algorithm::Aes128Siv => Aes128SivAead::new_from_slice(key)
+			.unwrap()
+			.encrypt(aes_siv::Nonce::from_slice(&envelope.nonce), payload),
+		Algorithm::XChaCha20Poly1305 => XChaCha20Poly1305::new_from_slice(key).unwrap().encrypt(
+			chacha20poly1305::XNonce::from_slice(&envelope.nonce),
+			payload,
+		),
+	}
+	.unwrap();
+	envelope.signature = signer.map(|signer| {
+		signer
+			.sign_prehashed(envelope.digest(binding), None)
+			.unwrap()
+			.to_bytes()
+	});
+
+	borsh::to_vec(&envelope).unwrap()
+}
+
+impl Envelope {
+	fn associated_data(&self, binding: &[u8]) -> Vec<u8> {
+		let mut ret = borsh::to_vec(&(self.version, self.algorithm, &self.scheme)).unwrap();
+		ret.extend(binding);
+		ret
+	}
+
+	fn digest(&self, binding: &[u8]) -> Sha512 {
+		let mut hasher: Sha512 = Digest::new();
+		hasher.update(b"message-envelope");
+		hasher.update(
+			borsh::to_vec(&(
+				self.version,
+				self.algorithm,
+				&self.scheme,
+				&self.nonce,
+				&self.ciphertext,
+			))
+			.unwrap(),
+		);
+		hasher.update(binding);
+		hasher
+	}
+
+	pub(crate) fn verify(&self, sender: &VerifyingKey, binding: &[u8]) -> bool {
+		self.signature.is_some_and(|signature| {
+			sender
+				.verify_prehashed_strict(
+					self.digest(binding),
+					None,
+					&Signature::from_bytes(&signature),
+				)
+				.is_ok()
+		})
+	}
+
+	pub(crate) fn open(&self, key: &[u8; 32], binding: &[u8]) -> Result<Vec<u8>, DecryptError> {
+		if self.nonce.len() != self.algorithm.nonce_len() {
+			return Err(DecryptError::Malformed)
+		}
+
+		let payload = Payload {
+			msg: &self.ciphertext,
+			aad: &self.associated_data(binding),
+		};
+
+		match self.algorithm {
+			Algorithm::Aes128Siv => Aes128SivAead::new_from_slice(key)
+				.unwrap()
+				.decrypt(aes_siv::Nonce::from_slice(&self.nonce), payload),
+			Algorithm::XChaCha20Poly1305 => XChaCha20Poly1305::new_from_slice(key)
+				.unwrap()
+				.decrypt(chacha20poly1305::XNonce::from_slice(&self.nonce), payload),
+		}
+		.or(Err(DecryptError::Authentication))
+		.and_then(|plaintext| {
+			if self.version >= 3 {
+				unpad(plaintext)
+			} else {
+				Ok(plaintext)
+			}
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn padding_bucket() {
+		for len in [0, 1, 63, 64, 65, 100, 1_000, 1_025, 100_000] {
+			assert_eq!(Padding::None.bucket(len), len);
+			assert!(Padding::PowerOfTwo.bucket(len) >= len.max(MINIMUM_PADDED_LEN));
+			assert!(Padding::PowerOfTwo.bucket(len).is_power_of_two());
+			assert!(Padding::Padme.bucket(len) >= len.max(MINIMUM_PADDED_LEN));
+			// Padmé never adds more than 12% to the length.
+			assert!(Padding::Padme.bucket(len) <= len.max(MINIMUM_PADDED_LEN) * 112 / 100);
+		}
+
+		assert_eq!(Padding::PowerOfTwo.bucket(1), 64);
+		assert_eq!(Padding::PowerOfTwo.bucket(65), 128);
+		assert_eq!(Padding::PowerOfTwo.bucket(128), 128);
+		assert_eq!(Padding::Padme.bucket(1), 64);
+		assert_eq!(Padding::Padme.bucket(65), 72);
+		assert_eq!(Padding::Padme.bucket(100), 104);
+		assert_eq!(Padding::Padme.bucket(1_000), 1_024);
+		assert_eq!(Padding::Padme.bucket(1_025), 1_088);
+	}
+
+	#[test]
+	fn padding_unpad() {
+		for padding in Padding::ALL {
+			for len in [0, 1, 59, 60, 61, 1_000] {
+				let plaintext = (0..len).map(|x| x as u8).collect::<Vec<_>>();
+				let padded = padding.pad(&plaintext);
+
+				assert_eq!(padded.len(), padding.bucket(len + LENGTH_PREFIX));
+				assert_eq!(super::unpad(padded).unwrap(), plaintext);
+			}
+		}
+
+		assert!(matches!(
+			super::unpad(vec![1, 0]),
+			Err(DecryptError::Malformed)
+		));
+		assert!(matches!(
+			super::unpad(vec![9, 0, 0, 0, 1, 2]),
+			Err(DecryptError::Malformed)
+		));
+	}
+}
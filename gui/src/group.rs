@@ -0,0 +1,387 @@
+use {
+	crate::{
+		envelope::{self, DecryptError, Envelope, Padding, Scheme},
+		ratchet,
+		states::{Account, Payload},
+		utils,
+	},
+	borsh::{BorshDeserialize, BorshSerialize},
+	ed25519_dalek::VerifyingKey,
+	project_toot_sdk::{GroupId, IGroup},
+	sha2::{Digest, Sha512},
+	std::collections::BTreeMap,
+};
+
+const GROUP_TAG: &[u8] = b"ptd-group";
+const MAXIMUM_SKIP: u32 = 1_000;
+const MAXIMUM_SENDER_KEYS: usize = 4;
+
+type Key = [u8; 32];
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub(crate) struct Header {
+	group: GroupId,
+	id: u32,
+	iteration: u32,
+}
+
+/// Every member encrypts its group messages with a chain of its own and hands the chain to the
+/// others over the pairwise sessions, so a message is encrypted and uploaded only once.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+struct SenderKey {
+	chain: Key,
+	iteration: u32,
+	/// Only set for our own chains, the membership the chain was handed to.
+	members: Option<Key>,
+	skipped: BTreeMap<u32, Key>,
+}
+
+impl SenderKey {
+	fn new(chain: Key, iteration: u32, members: Option<Key>) -> Self {
+		Self {
+			chain,
+			iteration,
+			members,
+			skipped: BTreeMap::new(),
+		}
+	}
+
+	fn advance(&mut self) -> (u32, Key) {
+		let (chain, key) = ratchet::kdf_chain(&self.chain);
+		let iteration = self.iteration;
+		self.chain = chain;
+		self.iteration += 1;
+
+		(iteration, key)
+	}
+
+	fn key(&mut self, iteration: u32) -> Option<Key> {
+		if let Some(key) = self.skipped.remove(&iteration) {
+			return Some(key)
+		}
+
+		if iteration < self.iteration || iteration > self.iteration.saturating_add(MAXIMUM_SKIP) {
+			return None
+		}
+
+		while self.iteration < iteration {
+			let (skipped, key) = self.advance();
+			self.skipped.insert(skipped, key);
+		}
+		while self.skipped.len() > MAXIMUM_SKIP as usize {
+			self.skipped.pop_first();
+		}
+
+		Some(self.advance().1)
+	}
+}
+
+fn members(group: &IGroup) -> Key {
+	let mut members = group
+		.members
+		.iter()
+		.map(|x| x.address.to_bytes())
+		.collect::<Vec<_>>();
+	members.sort();
+
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(GROUP_TAG);
+	hasher.update(group.id);
+	for member in members {
+		hasher.update(member);
+	}
+	hasher.finalize()[..32].try_into().unwrap()
+}
+
+pub(crate) fn binding(sender: &VerifyingKey, group: &GroupId, timestamp: i64) -> Vec<u8> {
+	let mut ret = Vec::<u8>::new();
+	ret.extend(sender.as_bytes());
+	ret.extend(group);
+	ret.extend(timestamp.to_le_bytes());
+	ret
+}
+
+fn key_binding(group: &str, sender: &str, id: u32) -> Vec<u8> {
+	[
+		b"sender-key".as_slice(),
+		group.as_bytes(),
+		sender.as_bytes(),
+		&id.to_le_bytes(),
+	]
+	.concat()
+}
+
+fn load_keys(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	group: &str,
+	sender: &str,
+) -> rusqlite::Result<Vec<(u32, SenderKey)>> {
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+
+	Ok(conn
+		.prepare(include_str!("../sql/get-sender-keys.sql"))?
+		.query_map([&owner, group, sender], |row| {
+			Ok((row.get::<_, u32>(0)?, row.get::<_, Vec<u8>>(1)?))
+		})?
+		.filter_map(|x| x.ok())
+		.filter_map(|(id, state)| {
+			let state = utils::open_state(account, &key_binding(group, sender, id), state);
+			Some((id, borsh::from_slice::<SenderKey>(&state).ok()?))
+		})
+		.collect::<Vec<_>>())
+}
+
+fn store_key(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	group: &str,
+	sender: &str,
+	id: u32,
+	key: &SenderKey,
+) -> rusqlite::Result<()> {
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+	let state = utils::seal_state(
+		account,
+		&key_binding(group, sender, id),
+		&borsh::to_vec(key).unwrap(),
+	);
+	let now = chrono::Utc::now().timestamp_micros();
+
+	conn.prepare(include_str!("../sql/insert-sender-key.sql"))?
+		.execute((&owner, group, sender, id, state, now))?;
+	conn.prepare(include_str!("../sql/trim-sender-keys.sql"))?
+		.execute((&owner, group, sender, MAXIMUM_SENDER_KEYS))?;
+
+	Ok(())
+}
+
+/// Starts a new chain unless the current one was handed to exactly these members, so anyone who
+/// left can't read what follows and anyone who joined can. Returns the payload to distribute, the
+/// chain is only kept once `commit` is called after every member got it.
+pub(crate) fn rotate(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	group: &IGroup,
+) -> rusqlite::Result<Option<Payload>> {
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+	let id = bs58::encode(group.id).into_string();
+	let members = members(group);
+
+	if load_keys(conn, account, &id, &owner)?
+		.first()
+		.is_some_and(|(_, key)| key.members == Some(members))
+	{
+		return Ok(None)
+	}
+
+	Ok(Some(Payload::SenderKey {
+		group: group.id,
+		id: rand::random::<u32>(),
+		chain: rand::random::<Key>(),
+		iteration: 0,
+	}))
+}
+
+pub(crate) fn commit(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	group: &IGroup,
+	distribution: &Payload,
+) -> rusqlite::Result<()> {
+	let Payload::SenderKey {
+		id,
+		chain,
+		iteration,
+		..
+	} = distribution
+	else {
+		return Ok(())
+	};
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+
+	store_key(
+		conn,
+		account,
+		&bs58::encode(group.id).into_string(),
+		&owner,
+		*id,
+		&SenderKey::new(*chain, *iteration, Some(members(group))),
+	)
+}
+
+pub(crate) fn store(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	sender: &VerifyingKey,
+	group: &GroupId,
+	id: u32,
+	chain: Key,
+	iteration: u32,
+) -> rusqlite::Result<()> {
+	store_key(
+		conn,
+		account,
+		&bs58::encode(group).into_string(),
+		&bs58::encode(sender.as_bytes()).into_string(),
+		id,
+		&SenderKey::new(chain, iteration, None),
+	)
+}
+
+pub(crate) fn encrypt(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	group: &GroupId,
+	timestamp: i64,
+	padding: Padding,
+	plaintext: &[u8],
+) -> rusqlite::Result<Vec<u8>> {
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+	let id = bs58::encode(group).into_string();
+
+	let Some((key_id, mut key)) = load_keys(conn, account, &id, &owner)?.into_iter().next() else {
+		return Err(rusqlite::Error::QueryReturnedNoRows)
+	};
+	let (iteration, message_key) = key.advance();
+	store_key(conn, account, &id, &owner, key_id, &key)?;
+
+	Ok(envelope::seal(
+		envelope::DEFAULT_ALGORITHM,
+		padding,
+		&message_key,
+		Scheme::Group(Header {
+			group: *group,
+			id: key_id,
+			iteration,
+		}),
+		Some(&account.secret),
+		&binding(&account.address, group, timestamp),
+		plaintext,
+	))
+}
+
+pub(crate) fn decrypt(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	sender: &VerifyingKey,
+	group: &GroupId,
+	timestamp: i64,
+	envelope: &Envelope,
+) -> rusqlite::Result<Result<Vec<u8>, DecryptError>> {
+	let Scheme::Group(header) = &envelope.scheme else {
+		return Ok(Err(DecryptError::UnsupportedFormat))
+	};
+	if header.group != *group {
+		return Ok(Err(DecryptError::UnsupportedFormat))
+	}
+
+	let id = bs58::encode(group).into_string();
+	let opposite = bs58::encode(sender.as_bytes()).into_string();
+
+	let Some((_, mut key)) = load_keys(conn, account, &id, &opposite)?
+		.into_iter()
+		.find(|(x, _)| *x == header.id)
+	else {
+		return Ok(Err(DecryptError::NoSession))
+	};
+	let Some(message_key) = key.key(header.iteration) else {
+		return Ok(Err(DecryptError::NoSession))
+	};
+
+	match envelope.open(&message_key, &binding(sender, group, timestamp)) {
+		Ok(plaintext) => {
+			store_key(conn, account, &id, &opposite, header.id, &key)?;
+			Ok(Ok(plaintext))
+		},
+		Err(err) => Ok(Err(err)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::tasks::init_tables::MIGRATIONS,
+		ed25519_dalek::SigningKey,
+		project_toot_sdk::IGroupMember,
+	};
+
+	fn database() -> rusqlite::Connection {
+		let conn = rusqlite::Connection::open_in_memory().unwrap();
+		conn.execute_batch(include_str!("../sql/tables.sql"))
+			.unwrap();
+		for migration in MIGRATIONS {
+			conn.execute_batch(migration).unwrap();
+		}
+		conn
+	}
+
+	fn account() -> Account {
+		let secret = SigningKey::from_bytes(&rand::random());
+		Account {
+			address: secret.verifying_key(),
+			secret,
+			name: String::new(),
+			server: "https://localhost".parse().unwrap(),
+		}
+	}
+
+	fn member(account: &Account, admin: bool) -> IGroupMember {
+		IGroupMember {
+			address: account.address,
+			admin,
+		}
+	}
+
+	#[test]
+	fn rotation_follows_members() {
+		let conn = database();
+		let (alice, bob, carol) = (account(), account(), account());
+		let mut group = IGroup {
+			id: rand::random(),
+			name: String::new(),
+			members: vec![member(&alice, true), member(&bob, false)],
+		};
+
+		// Nothing is kept until the distribution was delivered.
+		let distribution = rotate(&conn, &alice, &group).unwrap().unwrap();
+		assert!(rotate(&conn, &alice, &group).unwrap().is_some());
+		commit(&conn, &alice, &group, &distribution).unwrap();
+		assert!(rotate(&conn, &alice, &group).unwrap().is_none());
+
+		// Roles don't change who can read, members joining or leaving do.
+		group.members[1].admin = true;
+		assert!(rotate(&conn, &alice, &group).unwrap().is_none());
+		group.members.push(member(&carol, false));
+		let distribution = rotate(&conn, &alice, &group).unwrap().unwrap();
+		commit(&conn, &alice, &group, &distribution).unwrap();
+		group.members.pop();
+		assert!(rotate(&conn, &alice, &group).unwrap().is_some());
+
+		// Bob reads with the chain alice handed out.
+		let Payload::SenderKey {
+			id,
+			chain,
+			iteration,
+			..
+		} = distribution
+		else {
+			unreachable!()
+		};
+		store(&conn, &bob, &alice.address, &group.id, id, chain, iteration).unwrap();
+		let data = encrypt(&conn, &alice, &group.id, 0, Padding::None, b"hello").unwrap();
+		let envelope = envelope::decode(&data).unwrap();
+		assert_eq!(
+			decrypt(&conn, &bob, &alice.address, &group.id, 0, &envelope)
+				.unwrap()
+				.unwrap(),
+			b"hello"
+		);
+		assert!(
+			decrypt(&conn, &carol, &alice.address, &group.id, 0, &envelope)
+				.unwrap()
+				.is_err()
+		);
+	}
+}
@@ -1,28 +1,64 @@
 use {
 	crate::{
 		client,
+		envelope::Padding,
+		hd::{self, Seed},
+		link,
 		messages::{
 			AccountEditingMessage,
+			AccountImportMessage,
 			AccountSpecifyMessage,
 			ChatMessage,
 			ContactCreationMessage,
+			ContactDetailsMessage,
+			GroupMessage,
+			LinkMessage,
+			ProfileMessage,
 			ProjectTootMessage,
+			RequestsMessage,
 			RusqliteMessage,
+			SettingsMessage,
+			UnlockMessage,
 			WebSocketMessage,
 		},
-		states::{Account, Contact, Data, Message},
+		safety::{self, Verification},
+		states::{
+			Account,
+			ConnectionState,
+			Contact,
+			Data,
+			Message,
+			Outgoing,
+			Payload,
+			Request,
+			Server,
+			Settings,
+		},
 		subscriptions,
 		tasks,
 		theme::Theme,
+		tls,
 		utils,
+		vault::{self, VaultKey},
 		views,
 	},
 	ed25519_dalek::{SigningKey, VerifyingKey},
-	iced::{clipboard, Application, Command, Element, Renderer, Subscription},
-	project_toot_sdk::{IMessage, ISendMessagePayload, ISigner, IWebSocketRequest, Salt},
-	reqwest::Url,
-	sha2::{Digest, Sha512},
-	std::path::PathBuf,
+	iced::{clipboard, widget::qr_code, Application, Command, Element, Renderer, Subscription},
+	project_toot_sdk::{
+		IBlockPayload,
+		ICreateGroupPayload,
+		IGroup,
+		IInviteMemberPayload,
+		ILeaveGroupPayload,
+		IListBlockedPayload,
+		IMessage,
+		IRemoveMemberPayload,
+		ISetMemberRolePayload,
+		IUnblockPayload,
+		IWebSocketRequest,
+	},
+	reqwest::{Client, Url},
+	std::{collections::HashMap, path::PathBuf, time::Duration},
 	tokio::sync::mpsc::Sender,
 	tokio_rusqlite::Connection,
 };
@@ -32,50 +68,295 @@ enum Scene {
 	#[default]
 	AccountSpecify,
 	AccountEditing,
+	AccountImport,
 	ContactCreation,
+	ContactDetails,
+	GroupDetails,
 	Chat,
+	Requests,
+	Settings,
+	Profile,
+	Link,
+	Unlock,
 }
 
+const PURGE_INTERVAL: i64 = 60 * 1_000_000;
+const OUTBOX_BACKOFF_BASE: Duration = Duration::from_secs(2);
+const OUTBOX_BACKOFF_MAX: Duration = Duration::from_secs(5 * 60);
+const OUTBOX_MAXIMUM_ATTEMPTS: u32 = 10;
+
 pub(crate) struct ProjectToot {
 	basedir: PathBuf,
-	client: client::ProjectTootClient,
+	http: Client,
 	database: Option<Connection>,
-	sender: Option<Sender<IWebSocketRequest>>,
+	senders: HashMap<VerifyingKey, Sender<IWebSocketRequest>>,
+	connections: HashMap<VerifyingKey, ConnectionState>,
+	settings: Settings,
+	vault: Option<VaultKey>,
+	unlock_failed: bool,
+	import_failed: bool,
+	recovery: Option<String>,
+	profile_status: Option<Result<String, String>>,
+	profile_busy: bool,
+	active_at: i64,
 	data: Data,
 	scene: Scene,
 	account_index: Option<usize>,
 	contact_index: Option<usize>,
+	group_index: Option<usize>,
+	safety: Option<String>,
+	safety_code: Option<qr_code::State>,
+	link: Option<(SigningKey, String)>,
+	link_code: Option<qr_code::State>,
+	link_status: Option<Result<String, String>>,
+	link_polling: bool,
 	inputs: (String, String),
+	trust_inputs: (String, String),
+	purged_at: i64,
 }
 
 impl ProjectToot {
+	fn server(&self, url: &Url) -> Option<&Server> {
+		self.data.servers.iter().find(|x| &x.url == url)
+	}
+
+	fn http(&self, url: &Url) -> Client {
+		if let Some(server) = self.server(url) {
+			match tls::client_config(Some(server)).and_then(|config| {
+				Client::builder()
+					.use_preconfigured_tls((*config).clone())
+					.build()
+					.map_err(std::io::Error::other)
+			}) {
+				Ok(http) => http,
+				Err(err) => {
+					eprintln!("{:#?}", err);
+					self.http.clone()
+				},
+			}
+		} else {
+			self.http.clone()
+		}
+	}
+
+	fn client(&self, account: &Account) -> client::ProjectTootClient {
+		client::ProjectTootClient::new(account.server.clone(), self.http(&account.server))
+	}
+
+	fn fill_trust_inputs(&mut self, url: &Url) {
+		self.trust_inputs = if let Some(server) = self.server(url) {
+			(
+				server
+					.ca
+					.as_ref()
+					.map(|x| x.to_string_lossy().into_owned())
+					.unwrap_or_default(),
+				server.fingerprint.map(hex::encode).unwrap_or_default(),
+			)
+		} else {
+			(String::new(), String::new())
+		};
+	}
+
+	fn update_server(&mut self, url: &Url) -> Option<Command<<Self as Application>::Message>> {
+		let fingerprint = if self.trust_inputs.1.trim().is_empty() {
+			None
+		} else {
+			Some(tls::fingerprint(&self.trust_inputs.1)?)
+		};
+		let server = Server {
+			url: url.clone(),
+			ca: Some(self.trust_inputs.0.trim())
+				.filter(|x| !x.is_empty())
+				.map(PathBuf::from),
+			fingerprint,
+		};
+
+		self.data.servers.retain(|x| x.url != server.url);
+		if server.ca.is_some() || server.fingerprint.is_some() {
+			self.data.servers.push(server.clone());
+		}
+
+		Some(
+			if let Some(database) = self.database.clone() {
+				Command::perform(tasks::update_server::run(database, server), |res| {
+					if let Err(err) = res {
+						eprintln!("{:#?}", err);
+					}
+
+					ProjectTootMessage::None
+				})
+			} else {
+				Command::none()
+			},
+		)
+	}
+
 	fn account_specify_create(&mut self) -> Command<<Self as Application>::Message> {
-		let secret = SigningKey::from_bytes(&rand::random());
+		let (secret, command) = if let Some(seed) = self.seed() {
+			let secret = loop {
+				let secret = hd::derive(&seed, self.settings.seed_index);
+				self.settings.seed_index += 1;
+
+				if !self
+					.data
+					.accounts
+					.iter()
+					.any(|x| x.address == secret.verifying_key())
+				{
+					break secret
+				}
+			};
+
+			(secret, self.save_settings())
+		} else {
+			(SigningKey::from_bytes(&rand::random()), Command::none())
+		};
 		let address = secret.verifying_key();
 		let account = Account {
 			secret,
 			address,
 			name: format!("Account {}", utils::small_public_key(&address)),
+			server: self.settings.server.clone(),
 		};
 
 		self.data.accounts.push(account.clone());
 
 		if let Some(database) = self.database.clone() {
-			Command::perform(tasks::insert_account::run(database, account), |res| {
-				if let Err(err) = res {
-					eprintln!("{:#?}", err);
-				}
+			Command::batch([
+				command,
+				Command::perform(
+					tasks::insert_account::run(database, account, self.vault),
+					|res| {
+						if let Err(err) = res {
+							eprintln!("{:#?}", err);
+						}
 
-				ProjectTootMessage::None
-			})
+						ProjectTootMessage::None
+					},
+				),
+			])
 		} else {
-			Command::none()
+			command
 		}
 	}
 
+	fn account_specify_import(&mut self) -> Command<<Self as Application>::Message> {
+		self.import_failed = false;
+		self.scene = Scene::AccountImport;
+
+		Command::none()
+	}
+
+	fn account_import_end(&mut self, confirmed: bool) -> Command<<Self as Application>::Message> {
+		let command = if confirmed {
+			let Some(secret) =
+				utils::parse_phrase(&self.inputs.0).map(|x| SigningKey::from_bytes(&x))
+			else {
+				self.import_failed = true;
+				return Command::none()
+			};
+			let address = secret.verifying_key();
+
+			if self.data.accounts.iter().any(|x| x.address == address) {
+				Command::none()
+			} else {
+				let account = Account {
+					secret,
+					address,
+					name: if self.inputs.1.trim().is_empty() {
+						format!("Account {}", utils::small_public_key(&address))
+					} else {
+						self.inputs.1.clone()
+					},
+					server: self.settings.server.clone(),
+				};
+
+				self.data.accounts.push(account.clone());
+
+				if let Some(database) = self.database.clone() {
+					Command::perform(
+						tasks::insert_account::run(database, account, self.vault),
+						|res| {
+							if let Err(err) = res {
+								eprintln!("{:#?}", err);
+							}
+
+							ProjectTootMessage::None
+						},
+					)
+				} else {
+					Command::none()
+				}
+			}
+		} else {
+			Command::none()
+		};
+
+		self.import_failed = false;
+		self.inputs = (String::new(), String::new());
+		self.scene = Scene::AccountSpecify;
+
+		command
+	}
+
+	fn account_import_restore_seed(&mut self) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+		let Some(seed) = utils::parse_phrase(&self.inputs.0) else {
+			self.import_failed = true;
+			return Command::none()
+		};
+
+		Command::perform(
+			tasks::restore_seed::run(
+				client::ProjectTootClient::new(
+					self.settings.server.clone(),
+					self.http(&self.settings.server),
+				),
+				database,
+				self.vault,
+				seed,
+				self.data.accounts.iter().map(|x| x.address).collect(),
+			),
+			move |res| match res {
+				Ok((accounts, next)) => {
+					ProjectTootMessage::SeedRestored(Some((seed, accounts, next)))
+				},
+				Err(err) => {
+					eprintln!("{:#?}", err);
+					ProjectTootMessage::SeedRestored(None)
+				},
+			},
+		)
+	}
+
+	fn seed_restored(
+		&mut self,
+		restored: Option<(Seed, Vec<Account>, u32)>,
+	) -> Command<<Self as Application>::Message> {
+		let Some((seed, accounts, next)) = restored else {
+			self.import_failed = true;
+			return Command::none()
+		};
+
+		self.settings.seed = Some(vault::encode_seed(self.vault.as_ref(), &seed));
+		self.settings.seed_index = next;
+		self.data.accounts.extend(accounts);
+
+		self.import_failed = false;
+		self.inputs = (String::new(), String::new());
+		self.scene = Scene::AccountSpecify;
+
+		self.save_settings()
+	}
+
 	fn account_specify_edit(&mut self, index: usize) -> Command<<Self as Application>::Message> {
 		self.account_index = Some(index);
 		self.inputs.0 = self.data.accounts[index].name.clone();
+		self.inputs.1 = self.data.accounts[index].server.to_string();
+		self.fill_trust_inputs(&self.data.accounts[index].server.clone());
 		self.scene = Scene::AccountEditing;
 
 		Command::none()
@@ -83,6 +364,7 @@ impl ProjectToot {
 
 	fn account_specify_delete(&mut self, index: usize) -> Command<<Self as Application>::Message> {
 		let account = self.data.accounts.remove(index);
+		self.data.outbox.retain(|x| x.sender != account.address);
 
 		if let Some(database) = self.database.clone() {
 			Command::perform(
@@ -102,58 +384,57 @@ impl ProjectToot {
 
 	fn account_specify_choose(&mut self, index: usize) -> Command<<Self as Application>::Message> {
 		self.account_index = Some(index);
+		self.contact_index = None;
+		self.group_index = None;
 		self.scene = Scene::Chat;
+		let address = self.data.accounts[index].address;
 
 		if let Some(database) = self.database.clone() {
 			Command::batch([
 				Command::perform(
-					tasks::load_messages::run(
+					tasks::get_contacts::run(database.clone(), self.data.accounts[index].address),
+					|res| {
+						if let Ok(contacts) = res {
+							RusqliteMessage::LoadedContacts(contacts).into()
+						} else {
+							eprintln!("{:#?}", res.unwrap_err());
+							ProjectTootMessage::None
+						}
+					},
+				),
+				Command::perform(
+					tasks::get_requests::run(
 						self.basedir.clone(),
-						self.client.clone(),
-						database,
 						self.data.accounts[index].clone(),
+						database,
 					),
 					|res| {
-						if let Err(err) = res {
-							eprintln!("{:#?}", err);
+						if let Ok(requests) = res {
+							RusqliteMessage::LoadedRequests(requests).into()
+						} else {
+							eprintln!("{:#?}", res.unwrap_err());
+							ProjectTootMessage::None
 						}
-
-						ProjectTootMessage::None
 					},
 				),
-				if let Some(sender) = self.sender.clone() {
-					let account = self.data.accounts[index].clone();
-					let salt = rand::random::<Salt>();
-					let timestamp = chrono::Utc::now().timestamp_micros();
-
-					Command::perform(
-						async move {
-							sender
-								.send(IWebSocketRequest::Authorize(ISigner {
-									address: account.address,
-									salt,
-									timestamp,
-									signature: {
-										let mut hasher: Sha512 = Digest::new();
-										hasher.update(b"authorize");
-										hasher.update(salt);
-										hasher.update(timestamp.to_le_bytes());
-										account.secret.sign_prehashed(hasher, None).unwrap()
-									},
-								}))
-								.await
+				Command::perform(
+					self.client(&self.data.accounts[index]).list_blocked(
+						utils::signer(&self.data.accounts[index], b"list-blocked"),
+						IListBlockedPayload {
+							offset: 0,
+							limit: 10_000,
 						},
-						|res| {
-							if let Err(err) = res {
-								eprintln!("{:#?}", err);
-							}
-
+					),
+					move |res| {
+						if let Ok(blocked) = res {
+							ProjectTootMessage::LoadedBlocked(address, blocked)
+						} else {
+							eprintln!("{:#?}", res.unwrap_err());
 							ProjectTootMessage::None
-						},
-					)
-				} else {
-					Command::none()
-				},
+						}
+					},
+				),
+				self.load_groups(index),
 			])
 		} else {
 			Command::none()
@@ -162,27 +443,38 @@ impl ProjectToot {
 
 	fn account_editing_end(&mut self, confirmed: bool) -> Command<<Self as Application>::Message> {
 		let command = if confirmed {
-			if let Some(index) = self.account_index.clone() {
+			if let Some(index) = self.account_index {
+				let Ok(server) = Url::parse(&self.inputs.1) else {
+					return Command::none()
+				};
+				let Some(command) = self.update_server(&server) else {
+					return Command::none()
+				};
 				let account = &mut self.data.accounts[index];
 				account.name = self.inputs.0.clone();
+				account.server = server.clone();
 
 				if let Some(database) = self.database.clone() {
-					Command::perform(
-						tasks::update_account::run(
-							database,
-							account.address.clone(),
-							self.inputs.0.clone(),
-						),
-						|res| {
-							if let Err(err) = res {
-								eprintln!("{:#?}", err);
-							}
+					Command::batch([
+						command,
+						Command::perform(
+							tasks::update_account::run(
+								database,
+								account.address,
+								self.inputs.0.clone(),
+								server,
+							),
+							|res| {
+								if let Err(err) = res {
+									eprintln!("{:#?}", err);
+								}
 
-							ProjectTootMessage::None
-						},
-					)
+								ProjectTootMessage::None
+							},
+						),
+					])
 				} else {
-					Command::none()
+					command
 				}
 			} else {
 				Command::none()
@@ -192,131 +484,1186 @@ impl ProjectToot {
 		};
 
 		self.account_index = None;
+		self.recovery = None;
 		self.inputs.0 = String::new();
+		self.inputs.1 = String::new();
+		self.trust_inputs = (String::new(), String::new());
 		self.scene = Scene::AccountSpecify;
 
 		command
 	}
 
-	fn chat_create_contact(&mut self) -> Command<<Self as Application>::Message> {
-		self.scene = Scene::ContactCreation;
+	fn account_specify_settings(&mut self) -> Command<<Self as Application>::Message> {
+		self.inputs.0 = self.settings.server.to_string();
+		self.fill_trust_inputs(&self.settings.server.clone());
+		self.scene = Scene::Settings;
 
 		Command::none()
 	}
 
-	fn chat_delete_contact(&mut self, index: usize) -> Command<<Self as Application>::Message> {
-		let contact = self.data.contacts.remove(index);
-		self.contact_index = None;
+	fn account_specify_backup(&mut self) -> Command<<Self as Application>::Message> {
+		self.profile_status = None;
+		self.scene = Scene::Profile;
 
-		if let Some(database) = self.database.clone() {
-			Command::perform(
-				tasks::delete_contact::run(database, contact.address),
-				|res| {
-					if let Err(err) = res {
-						eprintln!("{:#?}", err);
-					}
+		Command::none()
+	}
 
-					ProjectTootMessage::None
-				},
-			)
-		} else {
-			Command::none()
-		}
+	fn account_specify_link(&mut self) -> Command<<Self as Application>::Message> {
+		let secret = SigningKey::from_bytes(&rand::random());
+		let code = link::encode_code(&secret.verifying_key(), &self.settings.server);
+
+		self.link_code = match qr_code::State::new(code.as_bytes()) {
+			Ok(state) => Some(state),
+			Err(err) => {
+				eprintln!("{:#?}", err);
+				None
+			},
+		};
+		self.link = Some((secret, code));
+		self.link_status = None;
+		self.scene = Scene::Link;
+
+		Command::none()
 	}
 
-	fn chat_choose_contact(&mut self, index: usize) -> Command<<Self as Application>::Message> {
-		self.contact_index = Some(index);
+	fn account_editing_link_device(&mut self) -> Command<<Self as Application>::Message> {
+		self.inputs = (String::new(), String::new());
+		self.trust_inputs = (String::new(), String::new());
+		self.recovery = None;
+		self.link_status = None;
+		self.scene = Scene::Link;
 
-		if let Some(database) = self.database.as_ref() {
-			Command::perform(
-				tasks::get_messages::run(
-					self.basedir.clone(),
-					self.data.accounts[*self.account_index.as_ref().unwrap()].clone(),
-					self.data.contacts[index].clone(),
-					database.clone(),
-				),
-				|res| {
-					if let Ok(messages) = res {
-						RusqliteMessage::LoadedMessages(messages).into()
-					} else {
-						eprintln!("{:#?}", res.unwrap_err());
-						ProjectTootMessage::None
-					}
-				},
-			)
-		} else {
-			Command::none()
-		}
+		Command::none()
 	}
 
-	fn chat_send_message(&mut self) -> Command<<Self as Application>::Message> {
-		if self.inputs.0.is_empty() {
+	fn link_send(&mut self) -> Command<<Self as Application>::Message> {
+		let (Some(database), Some(index)) = (self.database.clone(), self.account_index) else {
 			return Command::none()
-		}
-
-		let account = self.data.accounts[self.account_index.unwrap()].clone();
-		let contact = self.data.contacts[self.contact_index.unwrap()].clone();
+		};
+		let Some((temporary, server)) = link::decode_code(&self.inputs.0) else {
+			self.link_status = Some(Err(String::from("Invalid link code")));
+			return Command::none()
+		};
+		self.link_status = None;
 
-		let data = utils::encrypt(
-			&account,
-			&contact.address,
-			self.inputs.0.as_bytes().to_vec(),
-		);
-		let salt = rand::random::<Salt>();
-		let timestamp = chrono::Utc::now().timestamp_micros();
-		let mut hasher: Sha512 = Digest::new();
-		hasher.update(b"send-message");
-		hasher.update(salt);
-		hasher.update(timestamp.to_le_bytes());
+		Command::perform(
+			tasks::send_link::run(
+				client::ProjectTootClient::new(server.clone(), self.http(&server)),
+				database,
+				self.data.accounts[index].clone(),
+				temporary,
+			),
+			|res| ProjectTootMessage::LinkSent(res.map_err(|err| format!("{:?}", err))),
+		)
+	}
 
-		self.inputs.0 = String::new();
+	fn link_poll(&mut self) -> Command<<Self as Application>::Message> {
+		let (Some(database), Some((secret, _))) = (self.database.clone(), self.link.as_ref())
+		else {
+			return Command::none()
+		};
+		if self.link_polling {
+			return Command::none()
+		}
+		self.link_polling = true;
 
 		Command::perform(
-			self.client.clone().send_message(
-				ISigner {
-					address: account.address,
-					salt,
-					timestamp,
-					signature: account.secret.sign_prehashed(hasher.clone(), None).unwrap(),
-				},
-				ISendMessagePayload {
-					receiver: contact.address,
-					data: data.clone(),
-				},
+			tasks::receive_link::run(
+				self.basedir.clone(),
+				client::ProjectTootClient::new(
+					self.settings.server.clone(),
+					self.http(&self.settings.server),
+				),
+				database,
+				self.vault,
+				secret.clone(),
 			),
-			move |res| {
-				if let Err(err) = res {
-					eprintln!("{:#?}", err);
-					ProjectTootMessage::None
-				} else {
-					ProjectTootMessage::NewMessage(
-						IMessage {
-							hash: hasher.finalize().into(),
-							sender: account.address,
-							receiver: contact.address,
-							timestamp,
-							data_hash: {
-								let mut hasher: Sha512 = Digest::new();
-								hasher.update(&data);
-								hasher.finalize().into()
-							},
-						},
-						data,
-						res.is_ok(),
-					)
-				}
-			},
+			|res| ProjectTootMessage::LinkReceived(res.map_err(|err| format!("{:?}", err))),
 		)
 	}
 
-	fn contact_creation_end(&mut self, confirmed: bool) -> Command<<Self as Application>::Message> {
-		let command = if confirmed {
-			let address = if let Ok(ret) = VerifyingKey::try_from(
-				if let Ok(ret) = bs58::decode(self.inputs.0.as_str()).into_vec() {
-					ret
-				} else {
-					return Command::none()
-				}
+	fn link_received(
+		&mut self,
+		res: Result<Option<Vec<Account>>, String>,
+	) -> Command<<Self as Application>::Message> {
+		self.link_polling = false;
+
+		match res {
+			Ok(Some(accounts)) => {
+				self.data.accounts.extend(accounts);
+				self.link_back()
+			},
+			Ok(None) => Command::none(),
+			Err(err) => {
+				self.link_status = Some(Err(err));
+				Command::none()
+			},
+		}
+	}
+
+	fn link_back(&mut self) -> Command<<Self as Application>::Message> {
+		self.link = None;
+		self.link_code = None;
+		self.link_status = None;
+		self.account_index = None;
+		self.inputs = (String::new(), String::new());
+		self.scene = Scene::AccountSpecify;
+
+		Command::none()
+	}
+
+	fn profile_export(&mut self) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+		if self.inputs.0.is_empty() || self.inputs.1.is_empty() {
+			return Command::none()
+		}
+		self.profile_busy = true;
+
+		Command::perform(
+			tasks::export_profile::run(
+				self.basedir.clone(),
+				database,
+				self.vault,
+				self.data.accounts.clone(),
+				PathBuf::from(&self.inputs.0),
+				self.inputs.1.clone(),
+			),
+			|res| ProjectTootMessage::ProfileExported(res.map_err(|err| format!("{:?}", err))),
+		)
+	}
+
+	fn profile_import(&mut self) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+		if self.inputs.0.is_empty() || self.inputs.1.is_empty() {
+			return Command::none()
+		}
+		self.profile_busy = true;
+
+		Command::perform(
+			tasks::import_profile::run(
+				self.basedir.clone(),
+				database,
+				self.vault,
+				PathBuf::from(&self.inputs.0),
+				self.inputs.1.clone(),
+			),
+			|res| ProjectTootMessage::ProfileImported(res.map_err(|err| format!("{:?}", err))),
+		)
+	}
+
+	fn profile_imported(
+		&mut self,
+		res: Result<Vec<Account>, String>,
+	) -> Command<<Self as Application>::Message> {
+		self.profile_busy = false;
+		let imported = res.is_ok();
+		self.profile_status = Some(res.map(|accounts| {
+			let count = accounts.len();
+			self.data.accounts.extend(accounts);
+			format!("Imported {} new accounts", count)
+		}));
+
+		let (true, Some(database)) = (imported, self.database.clone()) else {
+			return Command::none()
+		};
+
+		Command::perform(tasks::get_settings::run(database), |res| {
+			if let Ok(settings) = res {
+				RusqliteMessage::ReloadedSettings(settings).into()
+			} else {
+				eprintln!("{:#?}", res.unwrap_err());
+				ProjectTootMessage::None
+			}
+		})
+	}
+
+	fn settings_end(&mut self, confirmed: bool) -> Command<<Self as Application>::Message> {
+		let command = if confirmed {
+			let Ok(server) = Url::parse(&self.inputs.0) else {
+				return Command::none()
+			};
+			let Some(command) = self.update_server(&server) else {
+				return Command::none()
+			};
+			self.settings.server = server;
+
+			if let Some(database) = self.database.clone() {
+				Command::batch([
+					command,
+					Command::perform(
+						tasks::update_settings::run(database, self.settings.clone()),
+						|res| {
+							if let Err(err) = res {
+								eprintln!("{:#?}", err);
+							}
+
+							ProjectTootMessage::None
+						},
+					),
+				])
+			} else {
+				command
+			}
+		} else {
+			Command::none()
+		};
+
+		self.inputs = (String::new(), String::new());
+		self.trust_inputs = (String::new(), String::new());
+		self.recovery = None;
+		self.scene = Scene::AccountSpecify;
+
+		command
+	}
+
+	fn settings_change_passphrase(
+		&mut self,
+		passphrase: Option<String>,
+	) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+		if passphrase.as_ref().is_some_and(|x| x.is_empty()) {
+			return Command::none()
+		}
+		self.inputs.1 = String::new();
+
+		Command::perform(
+			tasks::change_passphrase::run(
+				database,
+				self.data.accounts.clone(),
+				self.seed(),
+				passphrase,
+			),
+			|res| match res {
+				Ok(vault) => ProjectTootMessage::PassphraseChanged(vault),
+				Err(err) => {
+					eprintln!("{:#?}", err);
+					ProjectTootMessage::None
+				},
+			},
+		)
+	}
+
+	fn passphrase_changed(
+		&mut self,
+		vault: Option<(String, VaultKey)>,
+	) -> Command<<Self as Application>::Message> {
+		let seed = self.seed();
+
+		if let Some((lock, key)) = vault {
+			self.settings.passphrase = Some(lock);
+			self.vault = Some(key);
+		} else {
+			self.settings.passphrase = None;
+			self.vault = None;
+		}
+		self.settings.seed = seed.map(|seed| vault::encode_seed(self.vault.as_ref(), &seed));
+
+		Command::none()
+	}
+
+	fn seed(&self) -> Option<Seed> {
+		vault::decode_seed(self.vault.as_ref(), self.settings.seed.as_deref()?)
+	}
+
+	fn save_settings(&self) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+
+		Command::perform(
+			tasks::update_settings::run(database, self.settings.clone()),
+			|res| {
+				if let Err(err) = res {
+					eprintln!("{:#?}", err);
+				}
+
+				ProjectTootMessage::None
+			},
+		)
+	}
+
+	fn settings_create_seed(&mut self) -> Command<<Self as Application>::Message> {
+		let seed = rand::random::<Seed>();
+		self.settings.seed = Some(vault::encode_seed(self.vault.as_ref(), &seed));
+		self.settings.seed_index = 0;
+		self.recovery = Some(utils::mnemonic(&seed));
+
+		self.save_settings()
+	}
+
+	fn settings_show_seed(&mut self) -> Command<<Self as Application>::Message> {
+		self.recovery = self.seed().map(|seed| utils::mnemonic(&seed));
+
+		Command::none()
+	}
+
+	fn settings_cycle_lock_timeout(&mut self) -> Command<<Self as Application>::Message> {
+		self.settings.lock_after = utils::LOCK_TIMEOUTS[utils::LOCK_TIMEOUTS
+			.iter()
+			.position(|x| x == &self.settings.lock_after)
+			.map_or(0, |x| x + 1) %
+			utils::LOCK_TIMEOUTS.len()];
+
+		self.save_settings()
+	}
+
+	fn settings_cycle_padding(&mut self) -> Command<<Self as Application>::Message> {
+		self.settings.padding = Padding::ALL[Padding::ALL
+			.iter()
+			.position(|x| x == &self.settings.padding)
+			.map_or(0, |x| x + 1) %
+			Padding::ALL.len()];
+
+		self.save_settings()
+	}
+
+	fn lock(&mut self) -> Command<<Self as Application>::Message> {
+		self.data.locked.extend(
+			self.data
+				.accounts
+				.drain(..)
+				.map(|account| (account.address, account.server)),
+		);
+		self.data.contacts.clear();
+		self.data.groups.clear();
+		self.data.messages.clear();
+		self.data.outbox.clear();
+		self.data.requests.clear();
+
+		self.vault = None;
+		self.recovery = None;
+		self.link = None;
+		self.link_code = None;
+		self.account_index = None;
+		self.contact_index = None;
+		self.group_index = None;
+		self.inputs = (String::new(), String::new());
+		self.trust_inputs = (String::new(), String::new());
+		self.scene = Scene::Unlock;
+
+		Command::none()
+	}
+
+	fn unlock_submit(&mut self) -> Command<<Self as Application>::Message> {
+		let Some(lock) = self.settings.passphrase.clone() else {
+			return Command::none()
+		};
+
+		Command::perform(
+			tasks::unlock_vault::run(lock, self.inputs.0.clone()),
+			|res| match res {
+				Ok(key) => ProjectTootMessage::Unlocked(key),
+				Err(err) => {
+					eprintln!("{:#?}", err);
+					ProjectTootMessage::None
+				},
+			},
+		)
+	}
+
+	fn unlocked(&mut self, key: Option<VaultKey>) -> Command<<Self as Application>::Message> {
+		let Some(key) = key else {
+			self.unlock_failed = true;
+			return Command::none()
+		};
+
+		self.vault = Some(key);
+		self.unlock_failed = false;
+		self.active_at = chrono::Utc::now().timestamp_micros();
+		self.inputs.0 = String::new();
+		self.scene = Scene::AccountSpecify;
+
+		self.load_accounts()
+	}
+
+	fn load_accounts(&mut self) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+
+		Command::perform(tasks::get_accounts::run(database, self.vault), |res| {
+			if let Ok(accounts) = res {
+				RusqliteMessage::LoadedAccounts(accounts).into()
+			} else {
+				eprintln!("{:#?}", res.unwrap_err());
+				ProjectTootMessage::None
+			}
+		})
+	}
+
+	fn chat_switch_account(&mut self) -> Command<<Self as Application>::Message> {
+		self.account_index = None;
+		self.contact_index = None;
+		self.group_index = None;
+		self.inputs.0 = String::new();
+		self.data.contacts.clear();
+		self.data.groups.clear();
+		self.data.requests.clear();
+		self.scene = Scene::AccountSpecify;
+
+		Command::none()
+	}
+
+	fn chat_create_contact(&mut self) -> Command<<Self as Application>::Message> {
+		self.scene = Scene::ContactCreation;
+
+		Command::none()
+	}
+
+	fn chat_open_requests(&mut self) -> Command<<Self as Application>::Message> {
+		self.scene = Scene::Requests;
+
+		Command::none()
+	}
+
+	fn chat_delete_contact(&mut self, index: usize) -> Command<<Self as Application>::Message> {
+		let account = &self.data.accounts[self.account_index.unwrap()];
+		let contact = self.data.contacts.remove(index);
+		self.contact_index = None;
+
+		if let Some(database) = self.database.clone() {
+			Command::perform(
+				tasks::delete_contact::run(database, account.address, contact.address),
+				|res| {
+					if let Err(err) = res {
+						eprintln!("{:#?}", err);
+					}
+
+					ProjectTootMessage::None
+				},
+			)
+		} else {
+			Command::none()
+		}
+	}
+
+	fn chat_block_contact(&mut self, index: usize) -> Command<<Self as Application>::Message> {
+		let account = &self.data.accounts[self.account_index.unwrap()];
+		let address = self.data.contacts[index].address;
+		let blocked = self.data.blocked.entry(account.address).or_default();
+
+		if let Some(position) = blocked.iter().position(|x| x == &address) {
+			blocked.remove(position);
+
+			Command::perform(
+				self.client(account).unblock(
+					utils::signer(account, b"unblock"),
+					IUnblockPayload { address },
+				),
+				|res| {
+					if let Err(err) = res {
+						eprintln!("{:#?}", err);
+					}
+
+					ProjectTootMessage::None
+				},
+			)
+		} else {
+			blocked.push(address);
+
+			Command::perform(
+				self.client(account)
+					.block(utils::signer(account, b"block"), IBlockPayload { address }),
+				|res| {
+					if let Err(err) = res {
+						eprintln!("{:#?}", err);
+					}
+
+					ProjectTootMessage::None
+				},
+			)
+		}
+	}
+
+	fn chat_choose_contact(&mut self, index: usize) -> Command<<Self as Application>::Message> {
+		self.contact_index = Some(index);
+		self.group_index = None;
+		self.safety = Some(safety::number(
+			&self.data.accounts[self.account_index.unwrap()].address,
+			&self.data.contacts[index].address,
+		));
+
+		if let Some(database) = self.database.as_ref() {
+			Command::batch([
+				Command::perform(
+					tasks::get_messages::run(
+						self.basedir.clone(),
+						self.data.accounts[*self.account_index.as_ref().unwrap()].clone(),
+						self.data.contacts[index].clone(),
+						database.clone(),
+					),
+					|res| {
+						if let Ok(messages) = res {
+							RusqliteMessage::LoadedMessages(messages).into()
+						} else {
+							eprintln!("{:#?}", res.unwrap_err());
+							ProjectTootMessage::None
+						}
+					},
+				),
+				Command::perform(
+					tasks::mark_read::run(
+						database.clone(),
+						self.data.accounts[*self.account_index.as_ref().unwrap()].address,
+						self.data.contacts[index].address,
+					),
+					|res| {
+						if let Ok(unread) = res {
+							RusqliteMessage::LoadedUnread(unread).into()
+						} else {
+							eprintln!("{:#?}", res.unwrap_err());
+							ProjectTootMessage::None
+						}
+					},
+				),
+			])
+		} else {
+			Command::none()
+		}
+	}
+
+	fn chat_send_message(&mut self) -> Command<<Self as Application>::Message> {
+		if self.inputs.0.is_empty() {
+			return Command::none()
+		}
+
+		if let Some(index) = self.group_index {
+			let payload = Payload::Text {
+				content: self.inputs.0.clone(),
+				timer: None,
+			};
+
+			self.inputs.0 = String::new();
+
+			return self.send_group_payload(index, payload)
+		}
+
+		let payload = Payload::Text {
+			content: self.inputs.0.clone(),
+			timer: self.data.contacts[self.contact_index.unwrap()].timer,
+		};
+
+		self.inputs.0 = String::new();
+
+		self.send_payload(payload)
+	}
+
+	fn load_groups(&self, index: usize) -> Command<<Self as Application>::Message> {
+		let account = &self.data.accounts[index];
+		let address = account.address;
+
+		Command::perform(
+			self.client(account)
+				.list_groups(utils::signer(account, b"list-groups")),
+			move |res| {
+				if let Ok(groups) = res {
+					ProjectTootMessage::LoadedGroups(address, groups)
+				} else {
+					eprintln!("{:#?}", res.unwrap_err());
+					ProjectTootMessage::None
+				}
+			},
+		)
+	}
+
+	fn loaded_groups(
+		&mut self,
+		address: VerifyingKey,
+		groups: Vec<IGroup>,
+	) -> Command<<Self as Application>::Message> {
+		if !self
+			.account_index
+			.is_some_and(|index| self.data.accounts[index].address == address)
+		{
+			return Command::none()
+		}
+
+		let selected = self.group_index.map(|index| self.data.groups[index].id);
+		self.data.groups = groups;
+		self.group_index = selected.and_then(|id| self.data.groups.iter().position(|x| x.id == id));
+
+		if selected.is_some() && self.group_index.is_none() && self.scene == Scene::GroupDetails {
+			self.scene = Scene::Chat;
+		}
+
+		Command::none()
+	}
+
+	fn group_updated(&mut self, address: VerifyingKey) -> Command<<Self as Application>::Message> {
+		match self.account_index {
+			Some(index) if self.data.accounts[index].address == address => self.load_groups(index),
+			_ => Command::none(),
+		}
+	}
+
+	fn chat_create_group(&mut self) -> Command<<Self as Application>::Message> {
+		self.contact_index = None;
+		self.group_index = None;
+		self.inputs = (String::new(), String::new());
+		self.scene = Scene::GroupDetails;
+
+		Command::none()
+	}
+
+	fn chat_choose_group(&mut self, index: usize) -> Command<<Self as Application>::Message> {
+		self.contact_index = None;
+		self.group_index = Some(index);
+
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+		let account = self.data.accounts[self.account_index.unwrap()].clone();
+		let group = self.data.groups[index].id;
+
+		Command::batch([
+			Command::perform(
+				tasks::get_group_messages::run(
+					self.basedir.clone(),
+					account.clone(),
+					group,
+					database.clone(),
+				),
+				|res| {
+					if let Ok(messages) = res {
+						RusqliteMessage::LoadedMessages(messages).into()
+					} else {
+						eprintln!("{:#?}", res.unwrap_err());
+						ProjectTootMessage::None
+					}
+				},
+			),
+			Command::perform(
+				tasks::mark_group_read::run(database, account.address, group),
+				|res| {
+					if let Ok(unread) = res {
+						RusqliteMessage::LoadedUnread(unread).into()
+					} else {
+						eprintln!("{:#?}", res.unwrap_err());
+						ProjectTootMessage::None
+					}
+				},
+			),
+		])
+	}
+
+	fn chat_open_group_details(&mut self) -> Command<<Self as Application>::Message> {
+		self.inputs = (String::new(), String::new());
+		self.scene = Scene::GroupDetails;
+
+		Command::none()
+	}
+
+	fn send_group_payload(
+		&mut self,
+		index: usize,
+		payload: Payload,
+	) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+		let account = &self.data.accounts[self.account_index.unwrap()];
+
+		Command::perform(
+			tasks::send_group_message::run(
+				self.client(account),
+				database,
+				account.clone(),
+				self.data.groups[index].id,
+				payload,
+				self.settings.device,
+				self.settings.padding,
+				self.settings.sealed_sender,
+			),
+			|res| match res {
+				Ok((message, data)) => ProjectTootMessage::GroupMessageSent(message, data),
+				Err(err) => {
+					eprintln!("{:#?}", err);
+					ProjectTootMessage::None
+				},
+			},
+		)
+	}
+
+	fn group_message_sent(
+		&mut self,
+		message: IMessage,
+		data: Vec<u8>,
+	) -> Command<<Self as Application>::Message> {
+		self.new_message(message.sender, message, data, true)
+	}
+
+	fn group_create(&mut self) -> Command<<Self as Application>::Message> {
+		let name = self.inputs.0.trim().to_string();
+		if name.is_empty() {
+			return Command::none()
+		}
+		let account = &self.data.accounts[self.account_index.unwrap()];
+		let address = account.address;
+
+		self.inputs = (String::new(), String::new());
+		self.scene = Scene::Chat;
+
+		let payload = ICreateGroupPayload {
+			group: rand::random(),
+			name,
+			members: Vec::new(),
+		};
+
+		Command::perform(
+			self.client(account).create_group(
+				utils::payload_signer(account, b"create-group", &payload),
+				payload,
+			),
+			move |res| {
+				if let Err(err) = res {
+					eprintln!("{:#?}", err);
+				}
+
+				ProjectTootMessage::GroupUpdated(address)
+			},
+		)
+	}
+
+	fn group_invite(&mut self) -> Command<<Self as Application>::Message> {
+		let Some(member) = bs58::decode(self.inputs.1.trim())
+			.into_vec()
+			.ok()
+			.and_then(|x| VerifyingKey::try_from(x.as_slice()).ok())
+		else {
+			return Command::none()
+		};
+		let account = &self.data.accounts[self.account_index.unwrap()];
+		let address = account.address;
+		let group = self.data.groups[self.group_index.unwrap()].id;
+
+		self.inputs.1 = String::new();
+
+		let payload = IInviteMemberPayload {
+			group,
+			member,
+			admin: false,
+		};
+
+		Command::perform(
+			self.client(account).invite_member(
+				utils::payload_signer(account, b"invite-member", &payload),
+				payload,
+			),
+			move |res| {
+				if let Err(err) = res {
+					eprintln!("{:#?}", err);
+				}
+
+				ProjectTootMessage::GroupUpdated(address)
+			},
+		)
+	}
+
+	fn group_remove(&mut self, index: usize) -> Command<<Self as Application>::Message> {
+		let account = &self.data.accounts[self.account_index.unwrap()];
+		let address = account.address;
+		let group = &self.data.groups[self.group_index.unwrap()];
+		let payload = IRemoveMemberPayload {
+			group: group.id,
+			member: group.members[index].address,
+		};
+
+		Command::perform(
+			self.client(account).remove_member(
+				utils::payload_signer(account, b"remove-member", &payload),
+				payload,
+			),
+			move |res| {
+				if let Err(err) = res {
+					eprintln!("{:#?}", err);
+				}
+
+				ProjectTootMessage::GroupUpdated(address)
+			},
+		)
+	}
+
+	fn group_toggle_admin(&mut self, index: usize) -> Command<<Self as Application>::Message> {
+		let account = &self.data.accounts[self.account_index.unwrap()];
+		let address = account.address;
+		let group = &self.data.groups[self.group_index.unwrap()];
+		let payload = ISetMemberRolePayload {
+			group: group.id,
+			member: group.members[index].address,
+			admin: !group.members[index].admin,
+		};
+
+		Command::perform(
+			self.client(account).set_member_role(
+				utils::payload_signer(account, b"set-member-role", &payload),
+				payload,
+			),
+			move |res| {
+				if let Err(err) = res {
+					eprintln!("{:#?}", err);
+				}
+
+				ProjectTootMessage::GroupUpdated(address)
+			},
+		)
+	}
+
+	fn group_leave(&mut self) -> Command<<Self as Application>::Message> {
+		let account = &self.data.accounts[self.account_index.unwrap()];
+		let address = account.address;
+		let group = self.data.groups.remove(self.group_index.unwrap());
+
+		self.group_index = None;
+		self.scene = Scene::Chat;
+
+		let payload = ILeaveGroupPayload { group: group.id };
+		Command::perform(
+			self.client(account).leave_group(
+				utils::payload_signer(account, b"leave-group", &payload),
+				payload,
+			),
+			move |res| {
+				if let Err(err) = res {
+					eprintln!("{:#?}", err);
+				}
+
+				ProjectTootMessage::GroupUpdated(address)
+			},
+		)
+	}
+
+	fn group_back(&mut self) -> Command<<Self as Application>::Message> {
+		self.inputs = (String::new(), String::new());
+		self.scene = Scene::Chat;
+
+		Command::none()
+	}
+
+	fn chat_cycle_timer(&mut self) -> Command<<Self as Application>::Message> {
+		let contact = &mut self.data.contacts[self.contact_index.unwrap()];
+		contact.timer = utils::TIMERS[utils::TIMERS
+			.iter()
+			.position(|x| x == &contact.timer)
+			.map_or(0, |x| x + 1) %
+			utils::TIMERS.len()];
+		let contact = contact.clone();
+
+		Command::batch([
+			if let Some(database) = self.database.clone() {
+				Command::perform(
+					tasks::update_contact_timer::run(
+						database,
+						self.data.accounts[self.account_index.unwrap()].address,
+						contact.address,
+						contact.timer,
+					),
+					|res| {
+						if let Err(err) = res {
+							eprintln!("{:#?}", err);
+						}
+
+						ProjectTootMessage::None
+					},
+				)
+			} else {
+				Command::none()
+			},
+			self.send_payload(Payload::Timer(contact.timer)),
+		])
+	}
+
+	fn send_payload(&mut self, payload: Payload) -> Command<<Self as Application>::Message> {
+		let account = &self.data.accounts[self.account_index.unwrap()];
+		let contact = &self.data.contacts[self.contact_index.unwrap()];
+
+		let ttl = match &payload {
+			Payload::Text { timer, .. } => *timer,
+			Payload::Timer(_) | Payload::SenderKey { .. } => None,
+		};
+		let timestamp = chrono::Utc::now().timestamp_micros();
+		let outgoing = Outgoing {
+			id: 0,
+			sender: account.address,
+			receiver: contact.address,
+			local: Vec::new(),
+			ttl,
+			timestamp,
+			attempts: 0,
+			next_attempt_at: timestamp,
+			content: utils::content(&payload),
+			sending: false,
+		};
+
+		if let Some(database) = self.database.clone() {
+			Command::perform(
+				tasks::queue_message::run(database, account.clone(), outgoing, payload),
+				|res| {
+					if let Ok(outgoing) = res {
+						ProjectTootMessage::Queued(outgoing)
+					} else {
+						eprintln!("{:#?}", res.unwrap_err());
+						ProjectTootMessage::None
+					}
+				},
+			)
+		} else {
+			Command::none()
+		}
+	}
+
+	fn queued(&mut self, outgoing: Outgoing) -> Command<<Self as Application>::Message> {
+		let id = outgoing.id;
+		self.data.outbox.push(outgoing);
+
+		self.deliver(id)
+	}
+
+	fn deliver(&mut self, id: i64) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+		let Some(outgoing) = self.data.outbox.iter_mut().find(|x| x.id == id) else {
+			return Command::none()
+		};
+		let Some(account) = self
+			.data
+			.accounts
+			.iter()
+			.find(|x| x.address == outgoing.sender)
+		else {
+			return Command::none()
+		};
+
+		outgoing.sending = true;
+		let outgoing = outgoing.clone();
+		let data = outgoing.local.clone();
+
+		Command::perform(
+			tasks::send_outgoing::run(
+				self.client(account),
+				database,
+				account.clone(),
+				outgoing,
+				self.settings.device,
+				self.settings.padding,
+				self.settings.sealed_sender,
+			),
+			move |res| match res {
+				Ok(message) => ProjectTootMessage::Delivered(id, message, data),
+				Err(err) => {
+					eprintln!("{:#?}", err);
+					ProjectTootMessage::Undelivered(id)
+				},
+			},
+		)
+	}
+
+	fn delivered(
+		&mut self,
+		id: i64,
+		message: IMessage,
+		data: Vec<u8>,
+	) -> Command<<Self as Application>::Message> {
+		self.data.outbox.retain(|x| x.id != id);
+
+		self.new_message(message.sender, message, data, true)
+	}
+
+	fn undelivered(&mut self, id: i64) -> Command<<Self as Application>::Message> {
+		let Some(outgoing) = self.data.outbox.iter_mut().find(|x| x.id == id) else {
+			return Command::none()
+		};
+
+		outgoing.sending = false;
+		outgoing.attempts += 1;
+		outgoing.next_attempt_at = chrono::Utc::now().timestamp_micros() +
+			utils::backoff(outgoing.attempts, OUTBOX_BACKOFF_BASE, OUTBOX_BACKOFF_MAX).as_micros()
+				as i64;
+
+		if let Some(database) = self.database.clone() {
+			Command::perform(
+				tasks::update_outgoing::run(
+					database,
+					id,
+					outgoing.attempts,
+					outgoing.next_attempt_at,
+				),
+				|res| {
+					if let Err(err) = res {
+						eprintln!("{:#?}", err);
+					}
+
+					ProjectTootMessage::None
+				},
+			)
+		} else {
+			Command::none()
+		}
+	}
+
+	fn flush_outbox(&mut self, now: i64) -> Command<<Self as Application>::Message> {
+		let ids = self
+			.data
+			.outbox
+			.iter()
+			.filter(|x| {
+				!x.sending &&
+					x.attempts < OUTBOX_MAXIMUM_ATTEMPTS &&
+					x.next_attempt_at <= now &&
+					self.connections.get(&x.sender) == Some(&ConnectionState::Online)
+			})
+			.map(|x| x.id)
+			.collect::<Vec<_>>();
+
+		Command::batch(
+			ids.into_iter()
+				.map(|id| self.deliver(id))
+				.collect::<Vec<_>>(),
+		)
+	}
+
+	fn chat_retry_message(&mut self, id: i64) -> Command<<Self as Application>::Message> {
+		if self.data.outbox.iter().any(|x| x.id == id && !x.sending) {
+			self.deliver(id)
+		} else {
+			Command::none()
+		}
+	}
+
+	fn chat_cancel_message(&mut self, id: i64) -> Command<<Self as Application>::Message> {
+		let count = self.data.outbox.len();
+		self.data.outbox.retain(|x| x.id != id || x.sending);
+
+		if count == self.data.outbox.len() {
+			return Command::none()
+		}
+
+		if let Some(database) = self.database.clone() {
+			Command::perform(tasks::delete_outgoing::run(database, id), |res| {
+				if let Err(err) = res {
+					eprintln!("{:#?}", err);
+				}
+
+				ProjectTootMessage::None
+			})
+		} else {
+			Command::none()
+		}
+	}
+
+	fn chat_open_details(&mut self) -> Command<<Self as Application>::Message> {
+		let contact = &self.data.contacts[self.contact_index.unwrap()];
+		self.inputs.0 = contact.name.clone();
+		self.inputs.1 = bs58::encode(contact.address).into_string();
+		self.safety_code = match qr_code::State::new(safety::code(
+			&self.data.accounts[self.account_index.unwrap()].address,
+			&contact.address,
+		)) {
+			Ok(state) => Some(state),
+			Err(err) => {
+				eprintln!("{:#?}", err);
+				None
+			},
+		};
+		self.scene = Scene::ContactDetails;
+
+		Command::none()
+	}
+
+	fn contact_details_toggle_verified(&mut self) -> Command<<Self as Application>::Message> {
+		let owner = self.data.accounts[self.account_index.unwrap()].address;
+		let Some(number) = self.safety.clone() else {
+			return Command::none()
+		};
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+		let contact = &mut self.data.contacts[self.contact_index.unwrap()];
+		contact.verified = if safety::verification(&number, contact) == Verification::Verified {
+			None
+		} else {
+			Some(number)
+		};
+
+		Command::perform(
+			tasks::update_contact_verified::run(
+				database,
+				owner,
+				contact.address,
+				contact.verified.clone(),
+			),
+			|res| {
+				if let Err(err) = res {
+					eprintln!("{:#?}", err);
+				}
+
+				ProjectTootMessage::None
+			},
+		)
+	}
+
+	fn contact_details_end(&mut self, confirmed: bool) -> Command<<Self as Application>::Message> {
+		let command = if confirmed {
+			let Some(address) = bs58::decode(self.inputs.1.trim())
+				.into_vec()
+				.ok()
+				.and_then(|x| VerifyingKey::try_from(x.as_slice()).ok())
+			else {
+				return Command::none()
+			};
+			let owner = self.data.accounts[self.account_index.unwrap()].address;
+			let contact = &mut self.data.contacts[self.contact_index.unwrap()];
+			let previous = contact.address;
+			contact.address = address;
+			contact.name = self.inputs.0.clone();
+			self.safety = Some(safety::number(&owner, &address));
+
+			if let Some(database) = self.database.clone() {
+				Command::perform(
+					tasks::update_contact::run(
+						database,
+						owner,
+						previous,
+						address,
+						self.inputs.0.clone(),
+					),
+					|res| {
+						if let Err(err) = res {
+							eprintln!("{:#?}", err);
+						}
+
+						ProjectTootMessage::None
+					},
+				)
+			} else {
+				Command::none()
+			}
+		} else {
+			Command::none()
+		};
+
+		self.inputs.0 = String::new();
+		self.inputs.1 = String::new();
+		self.safety_code = None;
+		self.scene = Scene::Chat;
+
+		command
+	}
+
+	fn contact_creation_end(&mut self, confirmed: bool) -> Command<<Self as Application>::Message> {
+		let command = if confirmed {
+			let address = if let Ok(ret) = VerifyingKey::try_from(
+				if let Ok(ret) = bs58::decode(self.inputs.0.as_str()).into_vec() {
+					ret
+				} else {
+					return Command::none()
+				}
 				.as_slice(),
 			) {
 				ret
@@ -326,13 +1673,19 @@ impl ProjectToot {
 			let contact = Contact {
 				address,
 				name: self.inputs.1.clone(),
+				timer: None,
+				verified: None,
 			};
 
 			self.data.contacts.push(contact.clone());
 
 			if let Some(database) = self.database.as_ref() {
 				Command::perform(
-					tasks::insert_contact::run(database.clone(), contact),
+					tasks::insert_contact::run(
+						database.clone(),
+						self.data.accounts[self.account_index.unwrap()].address,
+						contact,
+					),
 					|res| {
 						if let Err(err) = res {
 							eprintln!("{:#?}", err);
@@ -355,30 +1708,131 @@ impl ProjectToot {
 		command
 	}
 
+	fn requests_accept(&mut self, index: usize) -> Command<<Self as Application>::Message> {
+		let request = self.data.requests.remove(index);
+		let contact = Contact {
+			address: request.address,
+			name: format!("Contact {}", utils::small_public_key(&request.address)),
+			timer: None,
+			verified: None,
+		};
+
+		self.data.contacts.push(contact.clone());
+		self.scene = Scene::Chat;
+
+		Command::batch([
+			if let Some(database) = self.database.as_ref() {
+				Command::perform(
+					tasks::insert_contact::run(
+						database.clone(),
+						self.data.accounts[self.account_index.unwrap()].address,
+						contact,
+					),
+					|res| {
+						if let Err(err) = res {
+							eprintln!("{:#?}", err);
+						}
+
+						ProjectTootMessage::None
+					},
+				)
+			} else {
+				Command::none()
+			},
+			self.chat_choose_contact(self.data.contacts.len() - 1),
+		])
+	}
+
+	fn requests_ignore(&mut self, index: usize) -> Command<<Self as Application>::Message> {
+		let request = self.data.requests.remove(index);
+		let account = &self.data.accounts[self.account_index.unwrap()];
+
+		if let Some(database) = self.database.clone() {
+			Command::perform(
+				tasks::delete_conversation::run(
+					self.basedir.clone(),
+					database,
+					account.address,
+					request.address,
+				),
+				|res| {
+					if let Err(err) = res {
+						eprintln!("{:#?}", err);
+					}
+
+					ProjectTootMessage::None
+				},
+			)
+		} else {
+			Command::none()
+		}
+	}
+
+	fn requests_block(&mut self, index: usize) -> Command<<Self as Application>::Message> {
+		let account = &self.data.accounts[self.account_index.unwrap()];
+		let address = self.data.requests[index].address;
+
+		let blocked = self.data.blocked.entry(account.address).or_default();
+		if !blocked.contains(&address) {
+			blocked.push(address);
+		}
+
+		Command::batch([
+			Command::perform(
+				self.client(account)
+					.block(utils::signer(account, b"block"), IBlockPayload { address }),
+				|res| {
+					if let Err(err) = res {
+						eprintln!("{:#?}", err);
+					}
+
+					ProjectTootMessage::None
+				},
+			),
+			self.requests_ignore(index),
+		])
+	}
+
 	fn database_connected(
 		&mut self,
 		database: Connection,
 	) -> Command<<Self as Application>::Message> {
 		self.database = Some(database.clone());
+		Command::perform(tasks::init_tables::run(database), |res| {
+			if let Err(err) = res {
+				eprintln!("{:#?}", err);
+				ProjectTootMessage::None
+			} else {
+				RusqliteMessage::Initialized.into()
+			}
+		})
+	}
+
+	fn database_initialized(&mut self) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+
 		Command::batch([
-			Command::perform(tasks::init_tables::run(database.clone()), |res| {
-				if let Err(err) = res {
-					eprintln!("{:#?}", err);
+			Command::perform(tasks::get_unread::run(database.clone()), |res| {
+				if let Ok(unread) = res {
+					RusqliteMessage::LoadedUnread(unread).into()
+				} else {
+					eprintln!("{:#?}", res.unwrap_err());
+					ProjectTootMessage::None
 				}
-
-				ProjectTootMessage::None
 			}),
-			Command::perform(tasks::get_accounts::run(database.clone()), |res| {
-				if let Ok(accounts) = res {
-					RusqliteMessage::LoadedAccounts(accounts).into()
+			Command::perform(tasks::get_servers::run(database.clone()), |res| {
+				if let Ok(servers) = res {
+					RusqliteMessage::LoadedServers(servers).into()
 				} else {
 					eprintln!("{:#?}", res.unwrap_err());
 					ProjectTootMessage::None
 				}
 			}),
-			Command::perform(tasks::get_contacts::run(database.clone()), |res| {
-				if let Ok(contacts) = res {
-					RusqliteMessage::LoadedContacts(contacts).into()
+			Command::perform(tasks::get_settings::run(database), |res| {
+				if let Ok(settings) = res {
+					RusqliteMessage::LoadedSettings(settings).into()
 				} else {
 					eprintln!("{:#?}", res.unwrap_err());
 					ProjectTootMessage::None
@@ -387,15 +1841,116 @@ impl ProjectToot {
 		])
 	}
 
+	fn websocket_connected(
+		&mut self,
+		address: VerifyingKey,
+		sender: Sender<IWebSocketRequest>,
+	) -> Command<<Self as Application>::Message> {
+		self.senders.insert(address, sender);
+		self.connections.insert(address, ConnectionState::Online);
+
+		let now = chrono::Utc::now().timestamp_micros();
+		for outgoing in self.data.outbox.iter_mut().filter(|x| x.sender == address) {
+			outgoing.next_attempt_at = outgoing.next_attempt_at.min(now);
+		}
+
+		self.authorize(address)
+	}
+
+	fn authorize(&self, address: VerifyingKey) -> Command<<Self as Application>::Message> {
+		let Some(sender) = self.senders.get(&address).cloned() else {
+			return Command::none()
+		};
+		let Some(account) = self.data.accounts.iter().find(|x| x.address == address) else {
+			return Command::none()
+		};
+		let signer = utils::signer(account, b"authorize");
+
+		Command::batch([
+			Command::perform(
+				async move { sender.send(IWebSocketRequest::Authorize(signer)).await },
+				|res| {
+					if let Err(err) = res {
+						eprintln!("{:#?}", err);
+					}
+
+					ProjectTootMessage::None
+				},
+			),
+			if let Some(database) = self.database.clone() {
+				Command::perform(
+					tasks::load_messages::run(
+						self.basedir.clone(),
+						self.client(account),
+						database,
+						account.clone(),
+						self.settings.device,
+					),
+					|res| match res {
+						Ok(messages) => ProjectTootMessage::Synced(messages),
+						Err(err) => {
+							eprintln!("{:#?}", err);
+							ProjectTootMessage::None
+						},
+					},
+				)
+			} else {
+				Command::none()
+			},
+			if let Some(database) = self.database.clone() {
+				Command::perform(
+					tasks::replenish_prekeys::run(
+						self.client(account),
+						database,
+						account.clone(),
+						self.settings.device,
+					),
+					|res| {
+						if let Err(err) = res {
+							eprintln!("{:#?}", err);
+						}
+
+						ProjectTootMessage::None
+					},
+				)
+			} else {
+				Command::none()
+			},
+		])
+	}
+
 	fn websocket_new_message(
 		&mut self,
 		message: IMessage,
 	) -> Command<<Self as Application>::Message> {
+		let Some(baseurl) = self
+			.data
+			.accounts
+			.iter()
+			.map(|x| (x.address, &x.server))
+			.chain(
+				self.data
+					.locked
+					.iter()
+					.map(|(address, server)| (*address, server)),
+			)
+			.find(|(address, _)| address == &message.receiver)
+			.map(|(_, server)| server.clone())
+		else {
+			return Command::none()
+		};
+		// Copies encrypted for our other devices can't be opened here.
+		if message
+			.device
+			.is_some_and(|device| device != self.settings.device)
+		{
+			return Command::none()
+		}
+
 		Command::perform(
 			tasks::download::run(
-				self.client.http(),
-				self.client
-					.baseurl()
+				self.http(&baseurl),
+				baseurl
 					.join("cdn/")
 					.unwrap()
 					.join((hex::encode(message.data_hash) + ".dat").as_str())
@@ -411,66 +1966,265 @@ impl ProjectToot {
 		)
 	}
 
+	/// Messages from the server belong to their receiver, our own sent copies to their sender.
 	fn new_message(
 		&mut self,
+		owner: VerifyingKey,
 		message: IMessage,
 		data: Vec<u8>,
 		success: bool,
 	) -> Command<<Self as Application>::Message> {
-		let content = if let Some(account) = self
-			.data
-			.accounts
-			.iter()
-			.find(|x| x.address == message.sender || x.address == message.receiver)
-		{
-			utils::decrypt(
-				account,
-				&if account.address == message.sender {
-					message.receiver
-				} else {
-					message.sender
+		let Some(account) = self.data.accounts.iter().find(|x| x.address == owner) else {
+			let Some(database) = self.database.clone() else {
+				return Command::none()
+			};
+			if !self
+				.data
+				.locked
+				.iter()
+				.any(|(address, _)| address == &owner)
+			{
+				return Command::none()
+			}
+
+			return Command::perform(
+				tasks::store_pending::run(self.basedir.clone(), database, message, data, success),
+				|res| {
+					if let Err(err) = res {
+						eprintln!("{:#?}", err);
+					}
+
+					ProjectTootMessage::None
 				},
-				&data,
 			)
-		} else {
-			String::from("No account to decrypt this message")
 		};
 
-		let msg = Message {
-			hash: message.hash,
-			sender: message.sender,
-			receiver: message.receiver,
-			data_hash: message.data_hash,
-			timestamp: message.timestamp,
-			success,
-			content,
-		};
+		// Blocks are loaded once an account is opened, the others are asked for sealed messages.
+		let blocked = self.data.blocked.get(&owner).cloned();
+		let client = self.client(account);
+		let signer = utils::signer(account, b"list-blocked");
 
-		self.data.messages.push(msg.clone());
+		if let Some(database) = self.database.clone() {
+			let basedir = self.basedir.clone();
+			let account = account.clone();
 
-		Command::batch([
-			if let Some(database) = self.database.as_ref() {
-				Command::perform(tasks::insert_message::run(database.clone(), msg), |res| {
-					if let Err(err) = res {
+			Command::perform(
+				async move {
+					let blocked = match blocked {
+						Some(blocked) => blocked,
+						None if message.sealed => client
+							.list_blocked(
+								signer,
+								IListBlockedPayload {
+									offset: 0,
+									limit: 10_000,
+								},
+							)
+							.await
+							.unwrap_or_else(|err| {
+								eprintln!("{:#?}", err);
+								Vec::new()
+							}),
+						None => Vec::new(),
+					};
+
+					tasks::receive_message::run(
+						basedir, database, account, message, data, success, blocked,
+					)
+					.await
+				},
+				|res| match res {
+					Ok(messages) => ProjectTootMessage::Synced(messages),
+					Err(err) => {
 						eprintln!("{:#?}", err);
-					}
+						ProjectTootMessage::None
+					},
+				},
+			)
+		} else {
+			Command::none()
+		}
+	}
 
-					ProjectTootMessage::None
-				})
+	fn received(
+		&mut self,
+		message: Message,
+		payload: Option<Payload>,
+	) -> Command<<Self as Application>::Message> {
+		let command = if let (Some(Payload::Timer(timer)), None) = (payload, message.group) {
+			let owner = message.owner;
+			let opposite = if owner == message.sender {
+				message.receiver
+			} else {
+				message.sender
+			};
+
+			if self
+				.account_index
+				.is_some_and(|index| self.data.accounts[index].address == owner)
+			{
+				if let Some(contact) = self
+					.data
+					.contacts
+					.iter_mut()
+					.find(|x| x.address == opposite)
+				{
+					contact.timer = timer;
+				}
+			}
+
+			if let Some(database) = self.database.clone() {
+				Command::perform(
+					tasks::update_contact_timer::run(database, owner, opposite, timer),
+					|res| {
+						if let Err(err) = res {
+							eprintln!("{:#?}", err);
+						}
+
+						ProjectTootMessage::None
+					},
+				)
 			} else {
 				Command::none()
+			}
+		} else {
+			Command::none()
+		};
+
+		if message.group.is_none() &&
+			message.owner == message.receiver &&
+			self.account_index
+				.is_some_and(|index| self.data.accounts[index].address == message.receiver) &&
+			!self
+				.data
+				.contacts
+				.iter()
+				.any(|x| x.address == message.sender)
+		{
+			if let Some(request) = self
+				.data
+				.requests
+				.iter_mut()
+				.find(|x| x.address == message.sender)
+			{
+				if !request.messages.iter().any(|x| x.hash == message.hash) {
+					request.messages.push(message.clone());
+				}
+			} else {
+				self.data.requests.push(Request {
+					address: message.sender,
+					messages: vec![message.clone()],
+				});
+			}
+		}
+
+		let refresh = match self.account_index {
+			Some(index)
+				if self.data.accounts[index].address == message.owner &&
+					message.group.is_some_and(|group| {
+						!self.data.groups.iter().any(|x| x.id == group)
+					}) =>
+			{
+				self.load_groups(index)
 			},
-			Command::perform(
-				tasks::write_data::run(self.basedir.clone(), message.data_hash, data),
-				|res| {
-					if let Err(err) = res {
-						eprintln!("{:#?}", err);
-					}
+			_ => Command::none(),
+		};
 
-					ProjectTootMessage::None
-				},
+		let command = Command::batch([command, refresh, self.refresh_unread(&message)]);
+
+		if self.data.locked.is_empty() &&
+			!self
+				.data
+				.messages
+				.iter()
+				.any(|x| x.owner == message.owner && x.hash == message.hash)
+		{
+			self.data.messages.push(message);
+		}
+
+		command
+	}
+
+	fn refresh_unread(&self, message: &Message) -> Command<<Self as Application>::Message> {
+		let Some(database) = self.database.clone() else {
+			return Command::none()
+		};
+		if message.sender == message.receiver || message.owner != message.receiver {
+			return Command::none()
+		}
+
+		let opened = self.scene == Scene::Chat &&
+			self.account_index
+				.is_some_and(|index| self.data.accounts[index].address == message.receiver) &&
+			match message.group {
+				Some(group) => self
+					.group_index
+					.is_some_and(|index| self.data.groups[index].id == group),
+				None => self
+					.contact_index
+					.is_some_and(|index| self.data.contacts[index].address == message.sender),
+			};
+
+		let callback = |res: Result<HashMap<VerifyingKey, usize>, tokio_rusqlite::Error>| {
+			if let Ok(unread) = res {
+				RusqliteMessage::LoadedUnread(unread).into()
+			} else {
+				eprintln!("{:#?}", res.unwrap_err());
+				ProjectTootMessage::None
+			}
+		};
+
+		match (opened, message.group) {
+			(true, Some(group)) => Command::perform(
+				tasks::mark_group_read::run(database, message.receiver, group),
+				callback,
 			),
-		])
+			(true, None) => Command::perform(
+				tasks::mark_read::run(database, message.receiver, message.sender),
+				callback,
+			),
+			_ => Command::perform(tasks::get_unread::run(database), callback),
+		}
+	}
+
+	fn tick(&mut self) -> Command<<Self as Application>::Message> {
+		let now = chrono::Utc::now().timestamp_micros();
+		if self.vault.is_some() &&
+			self.settings
+				.lock_after
+				.is_some_and(|secs| now - self.active_at > i64::from(secs) * 1_000_000)
+		{
+			return self.lock()
+		}
+		let flush = Command::batch([self.flush_outbox(now), self.link_poll()]);
+
+		let count = self.data.messages.len();
+		self.data
+			.messages
+			.retain(|x| x.expires_at.is_none_or(|expires_at| expires_at > now));
+
+		if count == self.data.messages.len() && now - self.purged_at < PURGE_INTERVAL {
+			return flush
+		}
+		self.purged_at = now;
+
+		if let Some(database) = self.database.clone() {
+			Command::batch([
+				flush,
+				Command::perform(
+					tasks::purge_messages::run(self.basedir.clone(), database, now),
+					|res| {
+						if let Err(err) = res {
+							eprintln!("{:#?}", err);
+						}
+
+						ProjectTootMessage::None
+					},
+				),
+			])
+		} else {
+			flush
+		}
 	}
 }
 
@@ -481,24 +2235,37 @@ impl Application for ProjectToot {
 	type Flags = ();
 
 	fn new(_: Self::Flags) -> (Self, Command<Self::Message>) {
-		let basedir = home::home_dir()
-			.expect("Failed to get home dir")
-			.join(".local/share/ptd/");
-		std::fs::create_dir_all(basedir.clone()).expect("Failed to create program data dir");
+		let basedir = utils::basedir();
 
 		(
 			Self {
 				basedir: basedir.clone(),
-				client: client::ProjectTootClient::new(
-					Url::parse("http://82.97.242.232:8080/").unwrap(),
-				),
+				http: Client::new(),
 				database: None,
-				sender: None,
+				senders: HashMap::new(),
+				connections: HashMap::new(),
+				settings: Settings::default(),
+				vault: None,
+				unlock_failed: false,
+				import_failed: false,
+				recovery: None,
+				profile_status: None,
+				profile_busy: false,
+				active_at: 0,
 				data: Data::default(),
 				scene: Scene::AccountSpecify,
 				account_index: None,
 				contact_index: None,
+				group_index: None,
+				safety: None,
+				safety_code: None,
+				link: None,
+				link_code: None,
+				link_status: None,
+				link_polling: false,
 				inputs: (String::new(), String::new()),
+				trust_inputs: (String::new(), String::new()),
+				purged_at: 0,
 			},
 			Command::perform(Connection::open(basedir.join("db.sqlite3")), |res| {
 				if let Ok(database) = res {
@@ -519,29 +2286,76 @@ impl Application for ProjectToot {
 			ProjectTootMessage::None => Command::none(),
 			ProjectTootMessage::AccountSpecify(inner) => match inner {
 				AccountSpecifyMessage::Create => self.account_specify_create(),
+				AccountSpecifyMessage::Import => self.account_specify_import(),
 				AccountSpecifyMessage::Edit(index) => self.account_specify_edit(index),
 				AccountSpecifyMessage::Delete(index) => self.account_specify_delete(index),
 				AccountSpecifyMessage::Choose(index) => self.account_specify_choose(index),
+				AccountSpecifyMessage::Settings => self.account_specify_settings(),
+				AccountSpecifyMessage::Backup => self.account_specify_backup(),
+				AccountSpecifyMessage::Link => self.account_specify_link(),
 			},
 			ProjectTootMessage::AccountEditing(inner) => match inner {
 				AccountEditingMessage::UpdateName(new_value) => {
 					self.inputs.0 = new_value;
 					Command::none()
 				},
+				AccountEditingMessage::UpdateServer(new_value) => {
+					self.inputs.1 = new_value;
+					Command::none()
+				},
+				AccountEditingMessage::UpdateCa(new_value) => {
+					self.trust_inputs.0 = new_value;
+					Command::none()
+				},
+				AccountEditingMessage::UpdateFingerprint(new_value) => {
+					self.trust_inputs.1 = new_value;
+					Command::none()
+				},
 				AccountEditingMessage::End(confirmed) => self.account_editing_end(confirmed),
 				AccountEditingMessage::Copy => clipboard::write(utils::public_key(
 					&self.data.accounts[*self.account_index.as_ref().unwrap()].address,
 				)),
+				AccountEditingMessage::ShowRecovery => {
+					self.recovery = Some(utils::mnemonic(
+						self.data.accounts[self.account_index.unwrap()]
+							.secret
+							.as_bytes(),
+					));
+					Command::none()
+				},
+				AccountEditingMessage::LinkDevice => self.account_editing_link_device(),
+			},
+			ProjectTootMessage::AccountImport(inner) => match inner {
+				AccountImportMessage::UpdateSecret(new_value) => {
+					self.inputs.0 = new_value;
+					Command::none()
+				},
+				AccountImportMessage::UpdateName(new_value) => {
+					self.inputs.1 = new_value;
+					Command::none()
+				},
+				AccountImportMessage::RestoreSeed => self.account_import_restore_seed(),
+				AccountImportMessage::End(confirmed) => self.account_import_end(confirmed),
 			},
 			ProjectTootMessage::Chat(inner) => match inner {
+				ChatMessage::SwitchAccount => self.chat_switch_account(),
 				ChatMessage::CreateContact => self.chat_create_contact(),
+				ChatMessage::CreateGroup => self.chat_create_group(),
+				ChatMessage::OpenRequests => self.chat_open_requests(),
 				ChatMessage::DeleteContact(index) => self.chat_delete_contact(index),
+				ChatMessage::BlockContact(index) => self.chat_block_contact(index),
 				ChatMessage::ChooseContact(index) => self.chat_choose_contact(index),
+				ChatMessage::ChooseGroup(index) => self.chat_choose_group(index),
 				ChatMessage::UpdateMessage(new_value) => {
 					self.inputs.0 = new_value;
 					Command::none()
 				},
 				ChatMessage::SendMessage => self.chat_send_message(),
+				ChatMessage::RetryMessage(id) => self.chat_retry_message(id),
+				ChatMessage::CancelMessage(id) => self.chat_cancel_message(id),
+				ChatMessage::CycleTimer => self.chat_cycle_timer(),
+				ChatMessage::OpenDetails => self.chat_open_details(),
+				ChatMessage::OpenGroupDetails => self.chat_open_group_details(),
 			},
 			ProjectTootMessage::ContactCreation(inner) => match inner {
 				ContactCreationMessage::UpdateAddress(new_value) => {
@@ -554,62 +2368,382 @@ impl Application for ProjectToot {
 				},
 				ContactCreationMessage::End(confirmed) => self.contact_creation_end(confirmed),
 			},
+			ProjectTootMessage::ContactDetails(inner) => match inner {
+				ContactDetailsMessage::UpdateName(new_value) => {
+					self.inputs.0 = new_value;
+					Command::none()
+				},
+				ContactDetailsMessage::UpdateAddress(new_value) => {
+					self.inputs.1 = new_value;
+					Command::none()
+				},
+				ContactDetailsMessage::ToggleVerified => self.contact_details_toggle_verified(),
+				ContactDetailsMessage::End(confirmed) => self.contact_details_end(confirmed),
+				ContactDetailsMessage::Copy => clipboard::write(utils::public_key(
+					&self.data.contacts[self.contact_index.unwrap()].address,
+				)),
+			},
+			ProjectTootMessage::Group(inner) => match inner {
+				GroupMessage::UpdateName(new_value) => {
+					self.inputs.0 = new_value;
+					Command::none()
+				},
+				GroupMessage::UpdateMember(new_value) => {
+					self.inputs.1 = new_value;
+					Command::none()
+				},
+				GroupMessage::Create => self.group_create(),
+				GroupMessage::Invite => self.group_invite(),
+				GroupMessage::Remove(index) => self.group_remove(index),
+				GroupMessage::ToggleAdmin(index) => self.group_toggle_admin(index),
+				GroupMessage::Leave => self.group_leave(),
+				GroupMessage::Back => self.group_back(),
+			},
+			ProjectTootMessage::Requests(inner) => match inner {
+				RequestsMessage::Accept(index) => self.requests_accept(index),
+				RequestsMessage::Ignore(index) => self.requests_ignore(index),
+				RequestsMessage::Block(index) => self.requests_block(index),
+				RequestsMessage::Back => {
+					self.scene = Scene::Chat;
+					Command::none()
+				},
+			},
+			ProjectTootMessage::Settings(inner) => match inner {
+				SettingsMessage::UpdateServer(new_value) => {
+					self.inputs.0 = new_value;
+					Command::none()
+				},
+				SettingsMessage::UpdateCa(new_value) => {
+					self.trust_inputs.0 = new_value;
+					Command::none()
+				},
+				SettingsMessage::UpdateFingerprint(new_value) => {
+					self.trust_inputs.1 = new_value;
+					Command::none()
+				},
+				SettingsMessage::UpdatePassphrase(new_value) => {
+					self.inputs.1 = new_value;
+					Command::none()
+				},
+				SettingsMessage::SetPassphrase => {
+					self.settings_change_passphrase(Some(self.inputs.1.clone()))
+				},
+				SettingsMessage::RemovePassphrase => self.settings_change_passphrase(None),
+				SettingsMessage::CycleLockTimeout => self.settings_cycle_lock_timeout(),
+				SettingsMessage::CyclePadding => self.settings_cycle_padding(),
+				SettingsMessage::ToggleSealedSender => {
+					self.settings.sealed_sender = !self.settings.sealed_sender;
+					self.save_settings()
+				},
+				SettingsMessage::CreateSeed => self.settings_create_seed(),
+				SettingsMessage::ShowSeed => self.settings_show_seed(),
+				SettingsMessage::End(confirmed) => self.settings_end(confirmed),
+			},
+			ProjectTootMessage::Profile(inner) => match inner {
+				ProfileMessage::UpdatePath(new_value) => {
+					self.inputs.0 = new_value;
+					Command::none()
+				},
+				ProfileMessage::UpdatePassphrase(new_value) => {
+					self.inputs.1 = new_value;
+					Command::none()
+				},
+				ProfileMessage::Export => self.profile_export(),
+				ProfileMessage::Import => self.profile_import(),
+				ProfileMessage::Back => {
+					self.inputs = (String::new(), String::new());
+					self.scene = Scene::AccountSpecify;
+					Command::none()
+				},
+			},
+			ProjectTootMessage::Link(inner) => match inner {
+				LinkMessage::UpdateCode(new_value) => {
+					self.inputs.0 = new_value;
+					Command::none()
+				},
+				LinkMessage::Copy => {
+					clipboard::write(self.link.as_ref().map_or(String::new(), |x| x.1.clone()))
+				},
+				LinkMessage::Send => self.link_send(),
+				LinkMessage::Back => self.link_back(),
+			},
+			ProjectTootMessage::Unlock(inner) => match inner {
+				UnlockMessage::UpdatePassphrase(new_value) => {
+					self.inputs.0 = new_value;
+					Command::none()
+				},
+				UnlockMessage::Submit => self.unlock_submit(),
+			},
 			ProjectTootMessage::Database(inner) => match inner {
 				RusqliteMessage::Connected(database) => self.database_connected(database),
+				RusqliteMessage::Initialized => self.database_initialized(),
+				RusqliteMessage::LoadedServers(servers) => {
+					self.data.servers = servers;
+					Command::none()
+				},
+				RusqliteMessage::ReloadedSettings(settings) => {
+					self.settings = settings;
+					Command::none()
+				},
+				RusqliteMessage::LoadedSettings(settings) => {
+					self.settings = settings;
+
+					if self.settings.passphrase.is_some() {
+						self.scene = Scene::Unlock;
+						Command::none()
+					} else {
+						self.load_accounts()
+					}
+				},
 				RusqliteMessage::LoadedAccounts(accounts) => {
-					self.data.accounts.extend(accounts.into_iter());
+					self.data.accounts.extend(accounts);
+					self.data.locked.clear();
+
+					let Some(database) = self.database.clone() else {
+						return Command::none()
+					};
+
+					Command::batch(
+						[
+							Command::perform(
+								tasks::get_outbox::run(
+									database.clone(),
+									self.data.accounts.clone(),
+								),
+								|res| {
+									if let Ok(outbox) = res {
+										RusqliteMessage::LoadedOutbox(outbox).into()
+									} else {
+										eprintln!("{:#?}", res.unwrap_err());
+										ProjectTootMessage::None
+									}
+								},
+							),
+							Command::perform(
+								tasks::receive_pending::run(
+									self.basedir.clone(),
+									database,
+									self.data.accounts.clone(),
+								),
+								|res| match res {
+									Ok(messages) => ProjectTootMessage::Synced(messages),
+									Err(err) => {
+										eprintln!("{:#?}", err);
+										ProjectTootMessage::None
+									},
+								},
+							),
+						]
+						.into_iter()
+						.chain(self.data.accounts.iter().map(|x| self.authorize(x.address)))
+						.collect::<Vec<_>>(),
+					)
+				},
+				RusqliteMessage::LoadedUnread(unread) => {
+					self.data.unread = unread;
+					Command::none()
+				},
+				RusqliteMessage::LoadedOutbox(outbox) => {
+					self.data.outbox = outbox;
 					Command::none()
 				},
 				RusqliteMessage::LoadedContacts(contacts) => {
-					self.data.contacts.extend(contacts.into_iter());
+					self.data.contacts = contacts;
 					Command::none()
 				},
 				RusqliteMessage::LoadedMessages(messages) => {
 					self.data.messages = messages;
 					Command::none()
 				},
+				RusqliteMessage::LoadedRequests(requests) => {
+					self.data.requests = requests;
+					Command::none()
+				},
 			},
 			ProjectTootMessage::WebSocket(inner) => match inner {
-				WebSocketMessage::Connected(sender) => {
-					self.sender = Some(sender);
+				WebSocketMessage::Connected(address, sender) => {
+					self.websocket_connected(address, sender)
+				},
+				WebSocketMessage::Connecting(address) => {
+					self.connections
+						.insert(address, ConnectionState::Connecting);
 					Command::none()
 				},
-				WebSocketMessage::Disconnected => {
-					self.sender = None;
+				WebSocketMessage::Disconnected(address) => {
+					self.senders.remove(&address);
+					self.connections.insert(address, ConnectionState::Offline);
 					Command::none()
 				},
-				WebSocketMessage::Authorized => {
+				WebSocketMessage::Authorized(_) => {
 					println!("Successfully authorized with websocket!");
 					Command::none()
 				},
 				WebSocketMessage::NewMessage(msg) => self.websocket_new_message(msg),
 			},
 			ProjectTootMessage::NewMessage(msg, data, success) => {
-				self.new_message(msg, data, success)
+				self.new_message(msg.receiver, msg, data, success)
+			},
+			ProjectTootMessage::GroupMessageSent(msg, data) => self.group_message_sent(msg, data),
+			ProjectTootMessage::Queued(outgoing) => self.queued(outgoing),
+			ProjectTootMessage::Delivered(id, msg, data) => self.delivered(id, msg, data),
+			ProjectTootMessage::Undelivered(id) => self.undelivered(id),
+			ProjectTootMessage::Synced(messages) => Command::batch(
+				messages
+					.into_iter()
+					.map(|(msg, payload)| self.received(msg, payload))
+					.collect::<Vec<_>>(),
+			),
+			ProjectTootMessage::Tick => self.tick(),
+			ProjectTootMessage::Activity => {
+				self.active_at = chrono::Utc::now().timestamp_micros();
+				Command::none()
+			},
+			ProjectTootMessage::LoadedBlocked(address, blocked) => {
+				self.data.blocked.insert(address, blocked);
+				Command::none()
 			},
+			ProjectTootMessage::LoadedGroups(address, groups) => {
+				self.loaded_groups(address, groups)
+			},
+			ProjectTootMessage::GroupUpdated(address) => self.group_updated(address),
+			ProjectTootMessage::Unlocked(key) => self.unlocked(key),
+			ProjectTootMessage::PassphraseChanged(vault) => self.passphrase_changed(vault),
+			ProjectTootMessage::SeedRestored(restored) => self.seed_restored(restored),
+			ProjectTootMessage::ProfileExported(res) => {
+				self.profile_busy = false;
+				self.profile_status = Some(res.map(|_| String::from("Profile exported")));
+				Command::none()
+			},
+			ProjectTootMessage::ProfileImported(res) => self.profile_imported(res),
+			ProjectTootMessage::LinkSent(res) => {
+				self.link_status =
+					Some(res.map(|_| String::from("Account sent, finish on the new device")));
+				Command::none()
+			},
+			ProjectTootMessage::LinkReceived(res) => self.link_received(res),
 		}
 	}
 
 	fn view(&self) -> Element<'_, Self::Message, Renderer<Self::Theme>> {
 		match self.scene {
-			Scene::AccountSpecify => views::account_specify::view(&self.data.accounts),
+			Scene::AccountSpecify => {
+				views::account_specify::view(&self.data.accounts, &self.data.unread)
+			},
 			Scene::AccountEditing => views::account_editing::view(
 				&self.data.accounts[self.account_index.unwrap()],
 				&self.inputs.0,
+				&self.inputs.1,
+				&self.trust_inputs.0,
+				&self.trust_inputs.1,
+				self.recovery.as_deref(),
 			),
+			Scene::AccountImport => {
+				views::account_import::view(&self.inputs.0, &self.inputs.1, self.import_failed)
+			},
 			Scene::ContactCreation => views::contact_creation::view(&self.inputs.0, &self.inputs.1),
+			Scene::ContactDetails => {
+				let contact = &self.data.contacts[self.contact_index.unwrap()];
+				let number = self.safety.as_deref().unwrap_or_default();
+
+				views::contact_details::view(
+					contact,
+					&self.inputs.0,
+					&self.inputs.1,
+					number,
+					self.safety_code.as_ref(),
+					safety::verification(number, contact),
+				)
+			},
+			Scene::GroupDetails => views::group::view(
+				&self.data.accounts[self.account_index.unwrap()].address,
+				self.group_index.map(|index| &self.data.groups[index]),
+				&self.data.contacts,
+				&self.inputs.0,
+				&self.inputs.1,
+			),
 			Scene::Chat => views::chat::view(
 				&self.data.accounts,
 				&self.data.contacts,
+				&self.data.groups,
 				&self.data.messages,
+				&self.data.outbox,
+				self.data
+					.blocked
+					.get(&self.data.accounts[self.account_index.unwrap()].address)
+					.map_or(&[], Vec::as_slice),
+				self.data.requests.len(),
 				self.account_index.unwrap(),
+				self.connections
+					.get(&self.data.accounts[self.account_index.unwrap()].address)
+					.copied()
+					.unwrap_or_default(),
 				self.contact_index,
+				self.group_index,
+				self.contact_index
+					.zip(self.safety.as_deref())
+					.map(|(index, number)| {
+						safety::verification(number, &self.data.contacts[index])
+					}),
+				&self.inputs.0,
+				chrono::Utc::now().timestamp_micros(),
+			),
+			Scene::Requests => views::requests::view(&self.data.requests),
+			Scene::Settings => views::settings::view(
+				&self.inputs.0,
+				&self.trust_inputs.0,
+				&self.trust_inputs.1,
+				&self.inputs.1,
+				self.settings.passphrase.is_some(),
+				self.settings.lock_after,
+				self.settings.padding,
+				self.settings.sealed_sender,
+				self.settings.seed.is_some(),
+				self.recovery.as_deref(),
+			),
+			Scene::Profile => views::profile::view(
+				&self.inputs.0,
+				&self.inputs.1,
+				self.profile_status.as_ref(),
+				self.profile_busy,
+			),
+			Scene::Link => views::link::view(
+				self.link.as_ref().map(|x| x.1.as_str()),
+				self.link_code.as_ref(),
 				&self.inputs.0,
+				self.link_status.as_ref(),
 			),
+			Scene::Unlock => views::unlock::view(&self.inputs.0, self.unlock_failed),
 		}
 	}
 
 	fn subscription(&self) -> Subscription<Self::Message> {
-		subscriptions::websocket::subscribe(self.client.baseurl().clone())
+		Subscription::batch(
+			self.data
+				.accounts
+				.iter()
+				.map(|account| (account.address, &account.server))
+				.chain(
+					self.data
+						.locked
+						.iter()
+						.map(|(address, server)| (*address, server)),
+				)
+				.map(|(address, server)| {
+					subscriptions::websocket::subscribe(
+						address,
+						server.clone(),
+						self.server(server).cloned(),
+					)
+				})
+				.chain([
+					iced::time::every(Duration::from_secs(1)).map(|_| ProjectTootMessage::Tick),
+					iced::subscription::events_with(|event, _| match event {
+						iced::Event::Keyboard(_) | iced::Event::Mouse(_) => {
+							Some(ProjectTootMessage::Activity)
+						},
+						_ => None,
+					}),
+				]),
+		)
 	}
 }
@@ -0,0 +1,100 @@
+use {
+	ed25519_dalek::SigningKey,
+	hmac::{Hmac, Mac},
+	sha2::Sha512,
+};
+
+pub(crate) type Seed = [u8; 32];
+
+const CURVE: &[u8] = b"ed25519 seed";
+const HARDENED: u32 = 0x8000_0000;
+const PURPOSE: u32 = 44;
+const COIN_TYPE: u32 = 7007;
+
+fn hmac(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+	let mut mac = <Hmac<Sha512> as Mac>::new_from_slice(key).unwrap();
+	for part in data {
+		mac.update(part);
+	}
+	let output = mac.finalize().into_bytes();
+
+	(
+		output[..32].try_into().unwrap(),
+		output[32..].try_into().unwrap(),
+	)
+}
+
+/// Returns the private key and chain code at the end of the path, every level hardened.
+fn node(seed: &[u8], path: &[u32]) -> ([u8; 32], [u8; 32]) {
+	let (mut key, mut chain) = hmac(CURVE, &[seed]);
+	for level in path {
+		(key, chain) = hmac(&chain, &[&[0], &key, &(level | HARDENED).to_be_bytes()]);
+	}
+
+	(key, chain)
+}
+
+/// SLIP-0010 derivation along `m/44'/7007'/index'`, every level hardened as ed25519 requires.
+pub(crate) fn derive(seed: &Seed, index: u32) -> SigningKey {
+	SigningKey::from_bytes(&node(seed, &[PURPOSE, COIN_TYPE, index]).0)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Test vector 1 for ed25519 from SLIP-0010.
+	const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+	const VECTORS: &[(&[u32], &str, &str)] = &[
+		(
+			&[],
+			"2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+			"90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+		),
+		(
+			&[0],
+			"68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+			"8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+		),
+		(
+			&[0, 1],
+			"b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2",
+			"a320425f77d1b5c2505a6b1b27382b37368ee640e3557c315416801243552f14",
+		),
+		(
+			&[0, 1, 2],
+			"92a5b23c0b8a99e37d07df3fb9966917f5d06e02ddbd909c7e184371463e9fc9",
+			"2e69929e00b5ab250f49c3fb1c12f252de4fed2c1db88387094a0f8c4c9ccd6c",
+		),
+		(
+			&[0, 1, 2, 2],
+			"30d1dc7e5fc04c31219ab25a27ae00b50f6fd66622f6e9c913253d6511d1e662",
+			"8f6d87f93d750e0efccda017d662a1b31a266e4a6f5993b15f5c1f07f74dd5cc",
+		),
+		(
+			&[0, 1, 2, 2, 1_000_000_000],
+			"8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+			"68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+		),
+	];
+
+	#[test]
+	fn slip10_vectors() {
+		let seed = hex::decode(SEED).unwrap();
+		for (path, key, chain) in VECTORS {
+			let (derived_key, derived_chain) = node(&seed, path);
+			assert_eq!(hex::encode(derived_key), *key, "{:?}", path);
+			assert_eq!(hex::encode(derived_chain), *chain, "{:?}", path);
+		}
+	}
+
+	#[test]
+	fn derive_follows_path() {
+		let seed = [7u8; 32];
+		assert_eq!(
+			derive(&seed, 3).to_bytes(),
+			node(&seed, &[PURPOSE, COIN_TYPE, 3]).0
+		);
+		assert_ne!(derive(&seed, 0).to_bytes(), derive(&seed, 1).to_bytes());
+	}
+}
@@ -0,0 +1,96 @@
+use {
+	crate::{
+		envelope::{self, Padding, Scheme},
+		profile::Profile,
+		states::Account,
+	},
+	borsh::{BorshDeserialize, BorshSerialize},
+	ed25519_dalek::{SigningKey, VerifyingKey},
+	project_toot_sdk::IMessage,
+	sha2::{Digest, Sha512},
+	url::Url,
+};
+
+const LINK_TAG: &[u8] = b"ptd-link";
+
+/// How long the account bundle waits on the server for the new device.
+pub(crate) const LINK_TTL: u32 = 10 * 60;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+struct Code {
+	address: [u8; 32],
+	server: String,
+}
+
+/// The code shown by the new device: its temporary address and the server it polls.
+pub(crate) fn encode_code(address: &VerifyingKey, server: &Url) -> String {
+	bs58::encode(
+		borsh::to_vec(&Code {
+			address: address.to_bytes(),
+			server: server.to_string(),
+		})
+		.unwrap(),
+	)
+	.into_string()
+}
+
+pub(crate) fn decode_code(value: &str) -> Option<(VerifyingKey, Url)> {
+	let code = borsh::from_slice::<Code>(&bs58::decode(value.trim()).into_vec().ok()?).ok()?;
+
+	Some((
+		VerifyingKey::from_bytes(&code.address).ok()?,
+		Url::parse(&code.server).ok()?,
+	))
+}
+
+fn key(secret: &SigningKey, opposite: &VerifyingKey) -> [u8; 32] {
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(LINK_TAG);
+	hasher.update(x25519_dalek::x25519(
+		secret.to_scalar_bytes(),
+		opposite.to_montgomery().0,
+	));
+	hasher.finalize()[..32].try_into().unwrap()
+}
+
+pub(crate) fn seal(
+	account: &Account,
+	temporary: &VerifyingKey,
+	timestamp: i64,
+	profile: &Profile,
+) -> Vec<u8> {
+	envelope::seal(
+		envelope::DEFAULT_ALGORITHM,
+		Padding::default(),
+		&key(&account.secret, temporary),
+		Scheme::Sealed,
+		Some(&account.secret),
+		&envelope::binding(&account.address, temporary, timestamp),
+		&[LINK_TAG, &borsh::to_vec(profile).unwrap()].concat(),
+	)
+}
+
+/// Accepts only a bundle signed by the account it carries.
+pub(crate) fn open(temporary: &SigningKey, message: &IMessage, data: &[u8]) -> Option<Profile> {
+	let binding = envelope::binding(&message.sender, &message.receiver, message.timestamp);
+	let envelope = envelope::decode(data).ok()?;
+	if !envelope.verify(&message.sender, &binding) {
+		return None
+	}
+
+	let plaintext = envelope
+		.open(&key(temporary, &message.sender), &binding)
+		.ok()?;
+	let profile = borsh::from_slice::<Profile>(plaintext.strip_prefix(LINK_TAG)?).ok()?;
+	let owner = bs58::encode(message.sender.as_bytes()).into_string();
+
+	match profile.accounts.as_slice() {
+		[account]
+			if SigningKey::from_bytes(&account.secret).verifying_key() == message.sender &&
+				profile.contacts.iter().all(|x| x.owner == owner) =>
+		{
+			Some(profile)
+		},
+		_ => None,
+	}
+}
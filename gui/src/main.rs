@@ -1,15 +1,39 @@
+// Error enums are only read through Debug when they are logged, and views take every piece of
+// state they render as its own argument.
+#![allow(
+	dead_code,
+	clippy::enum_variant_names,
+	clippy::large_enum_variant,
+	clippy::too_many_arguments
+)]
+
+mod cli;
 mod client;
+mod envelope;
+mod group;
 mod gui;
+mod hd;
+mod link;
 mod messages;
+mod profile;
+mod ratchet;
+mod safety;
+mod sealed;
 mod states;
 mod subscriptions;
 mod tasks;
 mod theme;
+mod tls;
 mod utils;
+mod vault;
 mod views;
 use iced::{window::Position, Application};
 
 fn main() -> iced::Result {
+	if let Some(code) = cli::run(&std::env::args().skip(1).collect::<Vec<_>>()) {
+		std::process::exit(code)
+	}
+
 	gui::ProjectToot::run(iced::Settings {
 		window: iced::window::Settings {
 			position: Position::Centered,
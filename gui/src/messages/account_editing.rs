@@ -3,8 +3,13 @@ use super::ProjectTootMessage;
 #[derive(Clone, Debug)]
 pub(crate) enum AccountEditingMessage {
 	UpdateName(String),
+	UpdateServer(String),
+	UpdateCa(String),
+	UpdateFingerprint(String),
 	End(bool),
 	Copy,
+	ShowRecovery,
+	LinkDevice,
 }
 
 impl From<AccountEditingMessage> for ProjectTootMessage {
@@ -0,0 +1,15 @@
+use super::ProjectTootMessage;
+
+#[derive(Clone, Debug)]
+pub(crate) enum AccountImportMessage {
+	UpdateSecret(String),
+	UpdateName(String),
+	RestoreSeed,
+	End(bool),
+}
+
+impl From<AccountImportMessage> for ProjectTootMessage {
+	fn from(value: AccountImportMessage) -> Self {
+		Self::AccountImport(value)
+	}
+}
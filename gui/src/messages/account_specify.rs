@@ -3,9 +3,13 @@ use super::ProjectTootMessage;
 #[derive(Clone, Debug)]
 pub(crate) enum AccountSpecifyMessage {
 	Create,
+	Import,
 	Edit(usize),
 	Delete(usize),
 	Choose(usize),
+	Settings,
+	Backup,
+	Link,
 }
 
 impl From<AccountSpecifyMessage> for ProjectTootMessage {
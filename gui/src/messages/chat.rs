@@ -2,11 +2,21 @@ use super::ProjectTootMessage;
 
 #[derive(Clone, Debug)]
 pub(crate) enum ChatMessage {
+	SwitchAccount,
 	CreateContact,
+	CreateGroup,
+	OpenRequests,
 	DeleteContact(usize),
+	BlockContact(usize),
 	ChooseContact(usize),
+	ChooseGroup(usize),
 	UpdateMessage(String),
 	SendMessage,
+	RetryMessage(i64),
+	CancelMessage(i64),
+	CycleTimer,
+	OpenDetails,
+	OpenGroupDetails,
 }
 
 impl From<ChatMessage> for ProjectTootMessage {
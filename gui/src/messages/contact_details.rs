@@ -0,0 +1,16 @@
+use super::ProjectTootMessage;
+
+#[derive(Clone, Debug)]
+pub(crate) enum ContactDetailsMessage {
+	UpdateName(String),
+	UpdateAddress(String),
+	ToggleVerified,
+	End(bool),
+	Copy,
+}
+
+impl From<ContactDetailsMessage> for ProjectTootMessage {
+	fn from(value: ContactDetailsMessage) -> Self {
+		Self::ContactDetails(value)
+	}
+}
@@ -1,15 +1,25 @@
 use {
 	super::ProjectTootMessage,
-	crate::states::{Account, Contact, Message},
+	crate::states::{Account, Contact, Message, Outgoing, Request, Server, Settings},
+	ed25519_dalek::VerifyingKey,
+	std::collections::HashMap,
 	tokio_rusqlite::Connection,
 };
 
 #[derive(Clone, Debug)]
 pub(crate) enum RusqliteMessage {
 	Connected(Connection),
+	Initialized,
+	LoadedServers(Vec<Server>),
+	LoadedSettings(Settings),
+	/// Settings read again after an import, without leaving the current scene.
+	ReloadedSettings(Settings),
 	LoadedAccounts(Vec<Account>),
 	LoadedContacts(Vec<Contact>),
 	LoadedMessages(Vec<Message>),
+	LoadedOutbox(Vec<Outgoing>),
+	LoadedUnread(HashMap<VerifyingKey, usize>),
+	LoadedRequests(Vec<Request>),
 }
 
 impl From<RusqliteMessage> for ProjectTootMessage {
@@ -0,0 +1,19 @@
+use super::ProjectTootMessage;
+
+#[derive(Clone, Debug)]
+pub(crate) enum GroupMessage {
+	UpdateName(String),
+	UpdateMember(String),
+	Create,
+	Invite,
+	Remove(usize),
+	ToggleAdmin(usize),
+	Leave,
+	Back,
+}
+
+impl From<GroupMessage> for ProjectTootMessage {
+	fn from(value: GroupMessage) -> Self {
+		Self::Group(value)
+	}
+}
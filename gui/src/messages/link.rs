@@ -0,0 +1,15 @@
+use super::ProjectTootMessage;
+
+#[derive(Clone, Debug)]
+pub(crate) enum LinkMessage {
+	UpdateCode(String),
+	Copy,
+	Send,
+	Back,
+}
+
+impl From<LinkMessage> for ProjectTootMessage {
+	fn from(value: LinkMessage) -> Self {
+		Self::Link(value)
+	}
+}
@@ -1,15 +1,31 @@
 mod account_editing;
+mod account_import;
 mod account_specify;
 mod chat;
 mod contact_creation;
+mod contact_details;
 mod database;
+mod group;
+mod link;
+mod profile;
+mod requests;
+mod settings;
+mod unlock;
 mod websocket;
 pub(crate) use {
 	account_editing::AccountEditingMessage,
+	account_import::AccountImportMessage,
 	account_specify::AccountSpecifyMessage,
 	chat::ChatMessage,
 	contact_creation::ContactCreationMessage,
+	contact_details::ContactDetailsMessage,
 	database::RusqliteMessage,
+	group::GroupMessage,
+	link::LinkMessage,
+	profile::ProfileMessage,
+	requests::RequestsMessage,
+	settings::SettingsMessage,
+	unlock::UnlockMessage,
 	websocket::WebSocketMessage,
 };
 
@@ -19,9 +35,37 @@ pub(crate) enum ProjectTootMessage {
 	None,
 	AccountSpecify(AccountSpecifyMessage),
 	AccountEditing(AccountEditingMessage),
+	AccountImport(AccountImportMessage),
 	Chat(ChatMessage),
 	ContactCreation(ContactCreationMessage),
+	ContactDetails(ContactDetailsMessage),
+	Group(GroupMessage),
+	Requests(RequestsMessage),
+	Settings(SettingsMessage),
+	Profile(ProfileMessage),
+	Link(LinkMessage),
+	Unlock(UnlockMessage),
 	Database(RusqliteMessage),
 	WebSocket(WebSocketMessage),
 	NewMessage(project_toot_sdk::IMessage, Vec<u8>, bool),
+	GroupMessageSent(project_toot_sdk::IMessage, Vec<u8>),
+	Queued(crate::states::Outgoing),
+	Delivered(i64, project_toot_sdk::IMessage, Vec<u8>),
+	Undelivered(i64),
+	Synced(Vec<(crate::states::Message, Option<crate::states::Payload>)>),
+	Tick,
+	Activity,
+	LoadedBlocked(
+		ed25519_dalek::VerifyingKey,
+		Vec<ed25519_dalek::VerifyingKey>,
+	),
+	LoadedGroups(ed25519_dalek::VerifyingKey, Vec<project_toot_sdk::IGroup>),
+	GroupUpdated(ed25519_dalek::VerifyingKey),
+	Unlocked(Option<crate::vault::VaultKey>),
+	PassphraseChanged(Option<(String, crate::vault::VaultKey)>),
+	SeedRestored(Option<(crate::hd::Seed, Vec<crate::states::Account>, u32)>),
+	ProfileExported(Result<(), String>),
+	ProfileImported(Result<Vec<crate::states::Account>, String>),
+	LinkSent(Result<(), String>),
+	LinkReceived(Result<Option<Vec<crate::states::Account>>, String>),
 }
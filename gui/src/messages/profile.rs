@@ -0,0 +1,16 @@
+use super::ProjectTootMessage;
+
+#[derive(Clone, Debug)]
+pub(crate) enum ProfileMessage {
+	UpdatePath(String),
+	UpdatePassphrase(String),
+	Export,
+	Import,
+	Back,
+}
+
+impl From<ProfileMessage> for ProjectTootMessage {
+	fn from(value: ProfileMessage) -> Self {
+		Self::Profile(value)
+	}
+}
@@ -0,0 +1,15 @@
+use super::ProjectTootMessage;
+
+#[derive(Clone, Debug)]
+pub(crate) enum RequestsMessage {
+	Accept(usize),
+	Ignore(usize),
+	Block(usize),
+	Back,
+}
+
+impl From<RequestsMessage> for ProjectTootMessage {
+	fn from(value: RequestsMessage) -> Self {
+		Self::Requests(value)
+	}
+}
@@ -0,0 +1,23 @@
+use super::ProjectTootMessage;
+
+#[derive(Clone, Debug)]
+pub(crate) enum SettingsMessage {
+	UpdateServer(String),
+	UpdateCa(String),
+	UpdateFingerprint(String),
+	UpdatePassphrase(String),
+	SetPassphrase,
+	RemovePassphrase,
+	CycleLockTimeout,
+	CyclePadding,
+	ToggleSealedSender,
+	CreateSeed,
+	ShowSeed,
+	End(bool),
+}
+
+impl From<SettingsMessage> for ProjectTootMessage {
+	fn from(value: SettingsMessage) -> Self {
+		Self::Settings(value)
+	}
+}
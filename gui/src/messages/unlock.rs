@@ -0,0 +1,13 @@
+use super::ProjectTootMessage;
+
+#[derive(Clone, Debug)]
+pub(crate) enum UnlockMessage {
+	UpdatePassphrase(String),
+	Submit,
+}
+
+impl From<UnlockMessage> for ProjectTootMessage {
+	fn from(value: UnlockMessage) -> Self {
+		Self::Unlock(value)
+	}
+}
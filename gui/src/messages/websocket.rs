@@ -1,14 +1,16 @@
 use {
 	super::ProjectTootMessage,
+	ed25519_dalek::VerifyingKey,
 	project_toot_sdk::{IMessage, IWebSocketRequest},
 	tokio::sync::mpsc::Sender,
 };
 
 #[derive(Clone, Debug)]
 pub(crate) enum WebSocketMessage {
-	Connected(Sender<IWebSocketRequest>),
-	Disconnected,
-	Authorized,
+	Connecting(VerifyingKey),
+	Connected(VerifyingKey, Sender<IWebSocketRequest>),
+	Disconnected(VerifyingKey),
+	Authorized(VerifyingKey),
 	NewMessage(IMessage),
 }
 
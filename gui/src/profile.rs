@@ -0,0 +1,99 @@
+use {
+	crate::{
+		envelope::{self, Padding, Scheme},
+		hd::Seed,
+		vault,
+	},
+	borsh::{BorshDeserialize, BorshSerialize},
+	project_toot_sdk::Sha512Data,
+};
+
+const PROFILE_TAG: &[u8] = b"ptd-profile";
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub(crate) struct ProfileAccount {
+	pub secret: [u8; 32],
+	pub name: String,
+	pub server: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub(crate) struct ProfileContact {
+	pub owner: String,
+	pub address: String,
+	pub name: String,
+	pub timer: Option<u32>,
+	pub verified: Option<String>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub(crate) struct ProfileMessage {
+	pub hash: String,
+	pub sender: String,
+	pub receiver: String,
+	pub data_hash: String,
+	pub timestamp: i64,
+	pub success: bool,
+	pub expires_at: Option<i64>,
+	pub verified: bool,
+	pub owner: String,
+	pub read: bool,
+	pub group: Option<String>,
+}
+
+/// Settings that mean the same on any device, unlike the passphrase and the device id.
+pub(crate) const PORTABLE_SETTINGS: [&str; 4] =
+	["server", "lock_after", "padding", "sealed_sender"];
+
+/// Ratchet sessions, prekey secrets and sender keys are left out: they belong to the device that
+/// made them, and a copy used on two devices would fork them. The importing device starts without
+/// any and is a new device to peers, who start fresh sessions with it, while group members hand
+/// it their sender keys with their next rotation.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default)]
+pub(crate) struct Profile {
+	pub accounts: Vec<ProfileAccount>,
+	pub contacts: Vec<ProfileContact>,
+	pub messages: Vec<ProfileMessage>,
+	/// Local copies keyed by `utils::data_key` of their owner and data hash.
+	pub blobs: Vec<(Sha512Data, Vec<u8>)>,
+	pub settings: Vec<(String, String)>,
+	/// The recovery seed and how many accounts were derived from it.
+	pub seed: Option<(Seed, u32)>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+struct Archive {
+	salt: [u8; 16],
+	sealed: Vec<u8>,
+}
+
+pub(crate) fn seal(passphrase: &str, profile: &Profile) -> Vec<u8> {
+	let salt = rand::random::<[u8; 16]>();
+	let key = vault::derive(passphrase, &salt);
+	let sealed = envelope::seal(
+		envelope::DEFAULT_ALGORITHM,
+		Padding::None,
+		&key,
+		Scheme::Sealed,
+		None,
+		PROFILE_TAG,
+		&borsh::to_vec(profile).unwrap(),
+	);
+
+	[
+		PROFILE_TAG,
+		&borsh::to_vec(&Archive { salt, sealed }).unwrap(),
+	]
+	.concat()
+}
+
+pub(crate) fn open(passphrase: &str, data: &[u8]) -> Option<Profile> {
+	let archive = borsh::from_slice::<Archive>(data.strip_prefix(PROFILE_TAG)?).ok()?;
+	let key = vault::derive(passphrase, &archive.salt);
+	let plaintext = envelope::decode(&archive.sealed)
+		.ok()?
+		.open(&key, PROFILE_TAG)
+		.ok()?;
+
+	borsh::from_slice(&plaintext).ok()
+}
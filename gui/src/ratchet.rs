@@ -0,0 +1,572 @@
+use {
+	crate::{
+		envelope::{self, DecryptError, Envelope, Padding, Scheme},
+		states::Account,
+		utils,
+	},
+	borsh::{BorshDeserialize, BorshSerialize},
+	ed25519_dalek::VerifyingKey,
+	hkdf::Hkdf,
+	hmac::{Hmac, Mac},
+	project_toot_sdk::{DeviceId, IPrekeyBundle},
+	sha2::{Digest, Sha256, Sha512},
+	std::collections::BTreeMap,
+	x25519_dalek::X25519_BASEPOINT_BYTES,
+};
+
+const MAXIMUM_SKIP: u32 = 1_000;
+const MAXIMUM_SESSIONS: usize = 4;
+
+type Key = [u8; 32];
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+struct Init {
+	signed_prekey: Key,
+	one_time_prekey: Option<Key>,
+	/// The device of the initiator, so the responder answers on the same session.
+	device: Option<DeviceId>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub(crate) struct Header {
+	dh: Key,
+	previous: u32,
+	n: u32,
+	init: Option<Init>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+struct Session {
+	root: Key,
+	dh_secret: Key,
+	dh_public: Key,
+	remote: Option<Key>,
+	send_chain: Option<Key>,
+	recv_chain: Option<Key>,
+	sent: u32,
+	received: u32,
+	previous: u32,
+	skipped: BTreeMap<(Key, u32), Key>,
+	pending: Option<Init>,
+}
+
+pub(crate) fn keypair() -> (Key, Key) {
+	let secret = rand::random::<Key>();
+	(secret, x25519_dalek::x25519(secret, X25519_BASEPOINT_BYTES))
+}
+
+fn kdf_root(root: &Key, dh: &Key) -> (Key, Key) {
+	let mut okm = [0u8; 64];
+	Hkdf::<Sha256>::new(Some(&root[..]), dh)
+		.expand(b"ptd-ratchet-root", &mut okm)
+		.unwrap();
+	(okm[..32].try_into().unwrap(), okm[32..].try_into().unwrap())
+}
+
+pub(crate) fn kdf_chain(chain: &Key) -> (Key, Key) {
+	let step = |byte: u8| -> Key {
+		let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(chain).unwrap();
+		mac.update(&[byte]);
+		mac.finalize().into_bytes().into()
+	};
+	(step(2), step(1))
+}
+
+fn agreement(dh: &[Key]) -> Key {
+	let mut ikm = vec![0xFF; 32];
+	for inner in dh {
+		ikm.extend(inner);
+	}
+
+	let mut okm = [0u8; 32];
+	Hkdf::<Sha256>::new(None, &ikm)
+		.expand(b"ptd-x3dh", &mut okm)
+		.unwrap();
+	okm
+}
+
+impl Session {
+	fn initiate(secret: Key, dh_secret: Key, dh_public: Key, init: Init) -> Self {
+		let remote = init.signed_prekey;
+		let (root, send_chain) = kdf_root(&secret, &x25519_dalek::x25519(dh_secret, remote));
+
+		Self {
+			root,
+			dh_secret,
+			dh_public,
+			remote: Some(remote),
+			send_chain: Some(send_chain),
+			recv_chain: None,
+			sent: 0,
+			received: 0,
+			previous: 0,
+			skipped: BTreeMap::new(),
+			pending: Some(init),
+		}
+	}
+
+	fn respond(secret: Key, dh_secret: Key, dh_public: Key) -> Self {
+		Self {
+			root: secret,
+			dh_secret,
+			dh_public,
+			remote: None,
+			send_chain: None,
+			recv_chain: None,
+			sent: 0,
+			received: 0,
+			previous: 0,
+			skipped: BTreeMap::new(),
+			pending: None,
+		}
+	}
+
+	fn advance(&mut self) -> Option<(Header, Key)> {
+		let (chain, key) = kdf_chain(self.send_chain.as_ref()?);
+		let header = Header {
+			dh: self.dh_public,
+			previous: self.previous,
+			n: self.sent,
+			init: self.pending.clone(),
+		};
+		self.send_chain = Some(chain);
+		self.sent += 1;
+
+		Some((header, key))
+	}
+
+	fn skip(&mut self, until: u32) -> Option<()> {
+		if until > self.received + MAXIMUM_SKIP {
+			return None
+		}
+
+		if let (Some(remote), Some(mut chain)) = (self.remote, self.recv_chain) {
+			while self.received < until {
+				let (next, key) = kdf_chain(&chain);
+				self.skipped.insert((remote, self.received), key);
+				chain = next;
+				self.received += 1;
+			}
+			self.recv_chain = Some(chain);
+		}
+
+		Some(())
+	}
+
+	fn decrypt(
+		&mut self,
+		header: &Header,
+		open: impl Fn(&Key) -> Option<Vec<u8>>,
+	) -> Option<Vec<u8>> {
+		let key = if let Some(key) = self.skipped.remove(&(header.dh, header.n)) {
+			key
+		} else {
+			if self.remote != Some(header.dh) {
+				self.skip(header.previous)?;
+
+				self.previous = self.sent;
+				self.sent = 0;
+				self.received = 0;
+				self.remote = Some(header.dh);
+
+				let (root, recv_chain) =
+					kdf_root(&self.root, &x25519_dalek::x25519(self.dh_secret, header.dh));
+				(self.dh_secret, self.dh_public) = keypair();
+				let (root, send_chain) =
+					kdf_root(&root, &x25519_dalek::x25519(self.dh_secret, header.dh));
+
+				self.root = root;
+				self.recv_chain = Some(recv_chain);
+				self.send_chain = Some(send_chain);
+			}
+
+			self.skip(header.n)?;
+
+			let (chain, key) = kdf_chain(self.recv_chain.as_ref()?);
+			self.recv_chain = Some(chain);
+			self.received += 1;
+			key
+		};
+
+		let plaintext = open(&key)?;
+		self.pending = None;
+
+		Some(plaintext)
+	}
+}
+
+fn encode_device(device: Option<&DeviceId>) -> Option<String> {
+	device.map(|x| bs58::encode(x).into_string())
+}
+
+fn session_binding(peer: &str) -> Vec<u8> {
+	[b"session".as_slice(), peer.as_bytes()].concat()
+}
+
+/// Sessions with every device of the peer, most recently used first.
+fn load_sessions(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	peer: &str,
+) -> rusqlite::Result<Vec<(i64, Option<String>, Session)>> {
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+
+	Ok(conn
+		.prepare(include_str!("../sql/get-sessions.sql"))?
+		.query_map([&owner, peer], |row| {
+			Ok((
+				row.get::<_, i64>(0)?,
+				row.get::<_, Vec<u8>>(1)?,
+				row.get::<_, Option<String>>(2)?,
+			))
+		})?
+		.filter_map(|x| x.ok())
+		.filter_map(|(id, state, device)| {
+			let state = utils::open_state(account, &session_binding(peer), state);
+			Some((id, device, borsh::from_slice::<Session>(&state).ok()?))
+		})
+		.collect::<Vec<_>>())
+}
+
+fn store_session(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	peer: &str,
+	device: Option<&str>,
+	id: Option<i64>,
+	session: &Session,
+) -> rusqlite::Result<()> {
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+	let state = utils::seal_state(
+		account,
+		&session_binding(peer),
+		&borsh::to_vec(session).unwrap(),
+	);
+	let now = chrono::Utc::now().timestamp_micros();
+
+	if let Some(id) = id {
+		conn.prepare(include_str!("../sql/update-session.sql"))?
+			.execute((id, state, now))?;
+	} else {
+		conn.prepare(include_str!("../sql/insert-session.sql"))?
+			.execute((&owner, peer, state, now, device))?;
+		conn.prepare(include_str!("../sql/trim-sessions.sql"))?
+			.execute((&owner, peer, MAXIMUM_SESSIONS, device))?;
+	}
+
+	Ok(())
+}
+
+pub(crate) fn prekey_binding(public: &Key) -> Vec<u8> {
+	[b"prekey".as_slice(), public].concat()
+}
+
+fn prekey_secret(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	public: &Key,
+) -> rusqlite::Result<Option<Key>> {
+	if *public == account.address.to_montgomery().0 {
+		return Ok(Some(account.secret.to_scalar_bytes()))
+	}
+
+	let res = conn
+		.prepare(include_str!("../sql/get-prekey.sql"))?
+		.query_row(
+			(
+				bs58::encode(account.address.as_bytes()).into_string(),
+				public,
+			),
+			|row| row.get::<_, Vec<u8>>(0),
+		);
+
+	match res {
+		Ok(secret) => Ok(utils::open_state(account, &prekey_binding(public), secret)
+			.try_into()
+			.ok()),
+		Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+		Err(err) => Err(err),
+	}
+}
+
+pub(crate) fn sign_prekey(account: &Account, prekey: &Key) -> ed25519_dalek::Signature {
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(b"signed-prekey");
+	hasher.update(prekey);
+	account.secret.sign_prehashed(hasher, None).unwrap()
+}
+
+pub(crate) fn established(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	peer: &VerifyingKey,
+	device: Option<&DeviceId>,
+) -> rusqlite::Result<bool> {
+	let device = encode_device(device);
+	Ok(
+		load_sessions(conn, account, &bs58::encode(peer.as_bytes()).into_string())?
+			.iter()
+			.any(|(_, x, _)| *x == device),
+	)
+}
+
+/// Starts a session with one device of the peer, `own` is announced as our device so the peer
+/// replies on the same session.
+pub(crate) fn initiate(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	peer: &VerifyingKey,
+	device: Option<&DeviceId>,
+	own: Option<DeviceId>,
+	bundle: Option<&IPrekeyBundle>,
+) -> rusqlite::Result<()> {
+	let identity = peer.to_montgomery().0;
+	let init = match bundle {
+		Some(bundle) => {
+			let mut hasher: Sha512 = Digest::new();
+			hasher.update(b"signed-prekey");
+			hasher.update(bundle.signed_prekey);
+			peer.verify_prehashed_strict(hasher, None, &bundle.signature)
+				.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?;
+
+			Init {
+				signed_prekey: bundle.signed_prekey,
+				one_time_prekey: bundle.one_time_prekey,
+				device: own,
+			}
+		},
+		None => Init {
+			signed_prekey: identity,
+			one_time_prekey: None,
+			device: own,
+		},
+	};
+
+	let (dh_secret, dh_public) = keypair();
+	let mut dh = vec![
+		x25519_dalek::x25519(account.secret.to_scalar_bytes(), init.signed_prekey),
+		x25519_dalek::x25519(dh_secret, identity),
+		x25519_dalek::x25519(dh_secret, init.signed_prekey),
+	];
+	if let Some(one_time_prekey) = init.one_time_prekey {
+		dh.push(x25519_dalek::x25519(dh_secret, one_time_prekey));
+	}
+
+	store_session(
+		conn,
+		account,
+		&bs58::encode(peer.as_bytes()).into_string(),
+		encode_device(device).as_deref(),
+		None,
+		&Session::initiate(agreement(&dh), dh_secret, dh_public, init),
+	)
+}
+
+/// Encrypts for one device of the peer, the session has to be initiated first.
+pub(crate) fn encrypt(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	peer: &VerifyingKey,
+	device: Option<&DeviceId>,
+	timestamp: i64,
+	padding: Padding,
+	plaintext: &[u8],
+) -> rusqlite::Result<Vec<u8>> {
+	let opposite = bs58::encode(peer.as_bytes()).into_string();
+	let device = encode_device(device);
+
+	let Some((id, _, mut session)) = load_sessions(conn, account, &opposite)?
+		.into_iter()
+		.find(|(_, x, _)| *x == device)
+	else {
+		return Err(rusqlite::Error::QueryReturnedNoRows)
+	};
+
+	let (header, key) = session.advance().ok_or(rusqlite::Error::InvalidQuery)?;
+	store_session(conn, account, &opposite, None, Some(id), &session)?;
+
+	Ok(envelope::seal(
+		envelope::DEFAULT_ALGORITHM,
+		padding,
+		&key,
+		Scheme::Ratchet(header),
+		Some(&account.secret),
+		&envelope::binding(&account.address, peer, timestamp),
+		plaintext,
+	))
+}
+
+fn respond(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	peer: &VerifyingKey,
+	header: &Header,
+	init: &Init,
+) -> rusqlite::Result<Option<Session>> {
+	let Some(signed_prekey) = prekey_secret(conn, account, &init.signed_prekey)? else {
+		return Ok(None)
+	};
+
+	let mut dh = vec![
+		x25519_dalek::x25519(signed_prekey, peer.to_montgomery().0),
+		x25519_dalek::x25519(account.secret.to_scalar_bytes(), header.dh),
+		x25519_dalek::x25519(signed_prekey, header.dh),
+	];
+	if let Some(public) = &init.one_time_prekey {
+		let Some(one_time_prekey) = prekey_secret(conn, account, public)? else {
+			return Ok(None)
+		};
+		dh.push(x25519_dalek::x25519(one_time_prekey, header.dh));
+	}
+
+	Ok(Some(Session::respond(
+		agreement(&dh),
+		signed_prekey,
+		init.signed_prekey,
+	)))
+}
+
+pub(crate) fn decrypt(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	peer: &VerifyingKey,
+	timestamp: i64,
+	envelope: &Envelope,
+) -> rusqlite::Result<Result<Vec<u8>, DecryptError>> {
+	let Scheme::Ratchet(header) = &envelope.scheme else {
+		return Ok(Err(DecryptError::UnsupportedFormat))
+	};
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+	let opposite = bs58::encode(peer.as_bytes()).into_string();
+	let binding = envelope::binding(peer, &account.address, timestamp);
+	let open = |key: &Key| envelope.open(key, &binding).ok();
+
+	for (id, _, mut session) in load_sessions(conn, account, &opposite)? {
+		if let Some(plaintext) = session.decrypt(header, open) {
+			store_session(conn, account, &opposite, None, Some(id), &session)?;
+			return Ok(Ok(plaintext))
+		}
+	}
+
+	let Some(init) = &header.init else {
+		return Ok(Err(DecryptError::NoSession))
+	};
+	let Some(mut session) = respond(conn, account, peer, header, init)? else {
+		return Ok(Err(DecryptError::NoSession))
+	};
+
+	if let Some(plaintext) = session.decrypt(header, open) {
+		store_session(
+			conn,
+			account,
+			&opposite,
+			encode_device(init.device.as_ref()).as_deref(),
+			None,
+			&session,
+		)?;
+		if let Some(public) = &init.one_time_prekey {
+			conn.prepare(include_str!("../sql/delete-prekey.sql"))?
+				.execute((&owner, public))?;
+		}
+
+		return Ok(Ok(plaintext))
+	}
+
+	Ok(Err(DecryptError::NoSession))
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::tasks::init_tables::MIGRATIONS, ed25519_dalek::SigningKey};
+
+	fn database() -> rusqlite::Connection {
+		let conn = rusqlite::Connection::open_in_memory().unwrap();
+		conn.execute_batch(include_str!("../sql/tables.sql"))
+			.unwrap();
+		for migration in MIGRATIONS {
+			conn.execute_batch(migration).unwrap();
+		}
+		conn
+	}
+
+	fn account() -> Account {
+		let secret = SigningKey::from_bytes(&rand::random());
+		Account {
+			address: secret.verifying_key(),
+			secret,
+			name: String::new(),
+			server: "https://localhost".parse().unwrap(),
+		}
+	}
+
+	fn send(conn: &rusqlite::Connection, from: &Account, to: &Account, text: &str) -> Vec<u8> {
+		encrypt(
+			conn,
+			from,
+			&to.address,
+			None,
+			0,
+			Padding::None,
+			text.as_bytes(),
+		)
+		.unwrap()
+	}
+
+	fn receive(conn: &rusqlite::Connection, from: &Account, to: &Account, data: &[u8]) -> String {
+		let envelope = envelope::decode(data).unwrap();
+		String::from_utf8(
+			decrypt(conn, to, &from.address, 0, &envelope)
+				.unwrap()
+				.unwrap(),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn out_of_order() {
+		let conn = database();
+		let (alice, bob) = (account(), account());
+		initiate(&conn, &alice, &bob.address, None, None, None).unwrap();
+
+		let first = send(&conn, &alice, &bob, "first");
+		let second = send(&conn, &alice, &bob, "second");
+		let third = send(&conn, &alice, &bob, "third");
+		assert_eq!(receive(&conn, &alice, &bob, &third), "third");
+		assert_eq!(receive(&conn, &alice, &bob, &first), "first");
+
+		// A reply ratchets forward while an older message of the first chain is still missing.
+		let reply = send(&conn, &bob, &alice, "reply");
+		assert_eq!(receive(&conn, &bob, &alice, &reply), "reply");
+		let fourth = send(&conn, &alice, &bob, "fourth");
+		assert_eq!(receive(&conn, &alice, &bob, &fourth), "fourth");
+		assert_eq!(receive(&conn, &alice, &bob, &second), "second");
+	}
+
+	#[test]
+	fn sealed_state() {
+		let conn = database();
+		let (alice, bob) = (account(), account());
+		initiate(&conn, &alice, &bob.address, None, None, None).unwrap();
+
+		let owner = bs58::encode(alice.address.as_bytes()).into_string();
+		let peer = bs58::encode(bob.address.as_bytes()).into_string();
+		let state = conn
+			.query_row(
+				include_str!("../sql/get-sessions.sql"),
+				[&owner, &peer],
+				|row| row.get::<_, Vec<u8>>(1),
+			)
+			.unwrap();
+		assert!(borsh::from_slice::<Session>(&state).is_err());
+
+		// Another account can't open it, and rows from before sealing still load.
+		assert!(load_sessions(&conn, &account(), &peer).unwrap().is_empty());
+		let (_, _, session) = load_sessions(&conn, &alice, &peer).unwrap().remove(0);
+		conn.execute(
+			"UPDATE \"sessions\" SET \"state\"=?1",
+			[borsh::to_vec(&session).unwrap()],
+		)
+		.unwrap();
+		assert_eq!(load_sessions(&conn, &alice, &peer).unwrap().len(), 1);
+	}
+}
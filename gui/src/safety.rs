@@ -0,0 +1,108 @@
+use {
+	crate::states::Contact,
+	ed25519_dalek::VerifyingKey,
+	sha2::{Digest, Sha512},
+};
+
+const VERSION: u16 = 0;
+const ITERATIONS: usize = 5_200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Verification {
+	Unverified,
+	Verified,
+	Changed,
+}
+
+fn fingerprint(address: &VerifyingKey) -> [u8; 30] {
+	let mut hash: [u8; 64] = {
+		let mut hasher: Sha512 = Digest::new();
+		hasher.update(VERSION.to_be_bytes());
+		hasher.update(address.as_bytes());
+		hasher.finalize().into()
+	};
+	for _ in 0..ITERATIONS {
+		let mut hasher: Sha512 = Digest::new();
+		hasher.update(hash);
+		hasher.update(address.as_bytes());
+		hash = hasher.finalize().into();
+	}
+
+	hash[..30].try_into().unwrap()
+}
+
+fn digits(fingerprint: &[u8; 30]) -> String {
+	fingerprint
+		.chunks(5)
+		.map(|chunk| {
+			let value = chunk
+				.iter()
+				.fold(0u64, |value, byte| value << 8 | u64::from(*byte));
+			format!("{:05}", value % 100_000)
+		})
+		.collect()
+}
+
+/// Both sides compute the same 60 digits regardless of which one is local.
+pub(crate) fn number(local: &VerifyingKey, remote: &VerifyingKey) -> String {
+	let mut halves = [digits(&fingerprint(local)), digits(&fingerprint(remote))];
+	halves.sort();
+	halves.concat()
+}
+
+pub(crate) fn format(number: &str) -> String {
+	number
+		.as_bytes()
+		.chunks(5)
+		.map(|chunk| std::str::from_utf8(chunk).unwrap())
+		.collect::<Vec<_>>()
+		.chunks(4)
+		.map(|line| line.join(" "))
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+/// Scannable form, ordered local first so the scanning side can tell the two apart.
+pub(crate) fn code(local: &VerifyingKey, remote: &VerifyingKey) -> Vec<u8> {
+	[
+		&VERSION.to_be_bytes()[..],
+		&fingerprint(local)[..],
+		&fingerprint(remote)[..],
+	]
+	.concat()
+}
+
+pub(crate) fn verification(number: &str, contact: &Contact) -> Verification {
+	match &contact.verified {
+		None => Verification::Unverified,
+		Some(verified) if verified == number => Verification::Verified,
+		Some(_) => Verification::Changed,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, ed25519_dalek::SigningKey};
+
+	fn address() -> VerifyingKey {
+		SigningKey::from_bytes(&rand::random()).verifying_key()
+	}
+
+	#[test]
+	fn number_is_symmetric() {
+		let (alice, bob, carol) = (address(), address(), address());
+
+		assert_eq!(number(&alice, &bob), number(&bob, &alice));
+		assert_eq!(number(&alice, &bob).len(), 60);
+		assert!(number(&alice, &bob).bytes().all(|x| x.is_ascii_digit()));
+		assert_ne!(number(&alice, &bob), number(&alice, &carol));
+	}
+
+	#[test]
+	fn code_is_ordered() {
+		let (alice, bob) = (address(), address());
+
+		assert_ne!(code(&alice, &bob), code(&bob, &alice));
+		assert_eq!(code(&alice, &bob)[2..32], code(&bob, &alice)[32..]);
+	}
+}
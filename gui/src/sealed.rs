@@ -0,0 +1,136 @@
+use {
+	crate::{
+		envelope::{self, DecryptError, Padding, Scheme},
+		ratchet,
+		states::Account,
+	},
+	borsh::BorshSerialize,
+	ed25519_dalek::VerifyingKey,
+	project_toot_sdk::{DeviceId, Sha512Data, LOOKUP_DIFFICULTY, SEALED_MESSAGE_DIFFICULTY},
+	sha2::{Digest, Sha512},
+};
+
+const SEALED_SENDER_TAG: &[u8] = b"ptd-sealed-sender";
+
+fn key(shared: &[u8; 32], ephemeral: &[u8; 32], receiver: &VerifyingKey) -> [u8; 32] {
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(SEALED_SENDER_TAG);
+	hasher.update(shared);
+	hasher.update(ephemeral);
+	hasher.update(receiver.as_bytes());
+	hasher.finalize()[..32].try_into().unwrap()
+}
+
+fn binding(receiver: &VerifyingKey, timestamp: i64) -> Vec<u8> {
+	let mut ret = Vec::<u8>::new();
+	ret.extend(receiver.as_bytes());
+	ret.extend(timestamp.to_le_bytes());
+	ret
+}
+
+/// Wraps an already signed envelope so that only the receiver learns who sent it.
+pub(crate) fn seal(
+	account: &Account,
+	receiver: &VerifyingKey,
+	timestamp: i64,
+	inner: &[u8],
+) -> Vec<u8> {
+	let (secret, ephemeral) = ratchet::keypair();
+	let shared = x25519_dalek::x25519(secret, receiver.to_montgomery().0);
+
+	envelope::seal(
+		envelope::DEFAULT_ALGORITHM,
+		Padding::None,
+		&key(&shared, &ephemeral, receiver),
+		Scheme::SealedSender(ephemeral),
+		None,
+		&binding(receiver, timestamp),
+		&borsh::to_vec(&(account.address.to_bytes(), inner)).unwrap(),
+	)
+}
+
+/// Returns the claimed sender, which the inner envelope signature still has to confirm.
+pub(crate) fn open(
+	account: &Account,
+	timestamp: i64,
+	data: &[u8],
+) -> Result<(VerifyingKey, Vec<u8>), DecryptError> {
+	let envelope = envelope::decode(data)?;
+	let Scheme::SealedSender(ephemeral) = envelope.scheme else {
+		return Err(DecryptError::UnsupportedFormat)
+	};
+	let shared = x25519_dalek::x25519(account.secret.to_scalar_bytes(), ephemeral);
+	let plaintext = envelope.open(
+		&key(&shared, &ephemeral, &account.address),
+		&binding(&account.address, timestamp),
+	)?;
+	let (sender, inner) =
+		borsh::from_slice::<([u8; 32], Vec<u8>)>(&plaintext).or(Err(DecryptError::Malformed))?;
+
+	Ok((
+		VerifyingKey::from_bytes(&sender).or(Err(DecryptError::Malformed))?,
+		inner,
+	))
+}
+
+/// The proof of work digest, which the server also uses as the message hash.
+pub(crate) fn digest(
+	receiver: &VerifyingKey,
+	data_hash: &Sha512Data,
+	timestamp: i64,
+	nonce: u64,
+	device: Option<&DeviceId>,
+) -> Sha512Data {
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(b"sealed-message");
+	hasher.update(receiver.as_bytes());
+	hasher.update(data_hash);
+	hasher.update(timestamp.to_le_bytes());
+	hasher.update(nonce.to_le_bytes());
+	if let Some(device) = device {
+		hasher.update(device);
+	}
+	hasher.finalize().into()
+}
+
+fn leading_zeros(digest: &[u8]) -> u32 {
+	let mut ret = 0;
+	for byte in digest {
+		ret += byte.leading_zeros();
+		if *byte != 0 {
+			break;
+		}
+	}
+	ret
+}
+
+/// Searches for a nonce meeting the server difficulty. Blocks for around a second.
+pub(crate) fn work(
+	receiver: &VerifyingKey,
+	data_hash: &Sha512Data,
+	timestamp: i64,
+	device: Option<&DeviceId>,
+) -> u64 {
+	(0..)
+		.find(|nonce| {
+			leading_zeros(&digest(receiver, data_hash, timestamp, *nonce, device)) >=
+				SEALED_MESSAGE_DIFFICULTY
+		})
+		.unwrap()
+}
+
+/// Searches for a nonce that lets an anonymous lookup through, far cheaper than a sealed message.
+pub(crate) fn lookup(method: &[u8], payload: &impl BorshSerialize, timestamp: i64) -> u64 {
+	let payload = borsh::to_vec(payload).unwrap();
+
+	(0..)
+		.find(|nonce: &u64| {
+			let mut hasher: Sha512 = Digest::new();
+			hasher.update(method);
+			hasher.update(&payload);
+			hasher.update(timestamp.to_le_bytes());
+			hasher.update(nonce.to_le_bytes());
+			leading_zeros(&hasher.finalize()) >= LOOKUP_DIFFICULTY
+		})
+		.unwrap()
+}
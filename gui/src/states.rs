@@ -1,19 +1,28 @@
 use {
+	crate::envelope::{DecryptError, Padding},
+	borsh::{BorshDeserialize, BorshSerialize},
 	ed25519_dalek::{SigningKey, VerifyingKey},
-	project_toot_sdk::Sha512Data,
+	project_toot_sdk::{DeviceId, GroupId, IGroup, Sha512Data},
+	std::{collections::HashMap, path::PathBuf},
+	url::Url,
 };
 
+pub(crate) const DEFAULT_SERVER: &str = "http://82.97.242.232:8080/";
+
 #[derive(Clone, Debug)]
 pub(crate) struct Account {
 	pub secret: SigningKey,
 	pub address: VerifyingKey,
 	pub name: String,
+	pub server: Url,
 }
 
 #[derive(Clone, Debug)]
 pub(crate) struct Contact {
 	pub address: VerifyingKey,
 	pub name: String,
+	pub timer: Option<u32>,
+	pub verified: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -24,12 +33,101 @@ pub(crate) struct Message {
 	pub data_hash: Sha512Data,
 	pub timestamp: i64,
 	pub success: bool,
+	pub content: Result<String, DecryptError>,
+	pub expires_at: Option<i64>,
+	pub verified: bool,
+	pub group: Option<GroupId>,
+	pub owner: VerifyingKey,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum ConnectionState {
+	#[default]
+	Offline,
+	Connecting,
+	Online,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Outgoing {
+	pub id: i64,
+	pub sender: VerifyingKey,
+	pub receiver: VerifyingKey,
+	pub local: Vec<u8>,
+	pub ttl: Option<u32>,
+	pub timestamp: i64,
+	pub attempts: u32,
+	pub next_attempt_at: i64,
 	pub content: String,
+	pub sending: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Request {
+	pub address: VerifyingKey,
+	pub messages: Vec<Message>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub(crate) enum Payload {
+	Text {
+		content: String,
+		timer: Option<u32>,
+	},
+	Timer(Option<u32>),
+	SenderKey {
+		group: GroupId,
+		id: u32,
+		chain: [u8; 32],
+		iteration: u32,
+	},
+}
+
+#[derive(Clone, Debug, Hash)]
+pub(crate) struct Server {
+	pub url: Url,
+	pub ca: Option<PathBuf>,
+	pub fingerprint: Option<[u8; 32]>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Settings {
+	pub server: Url,
+	pub passphrase: Option<String>,
+	pub lock_after: Option<u32>,
+	pub seed: Option<String>,
+	pub seed_index: u32,
+	pub device: DeviceId,
+	pub padding: Padding,
+	pub sealed_sender: bool,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			server: Url::parse(DEFAULT_SERVER).unwrap(),
+			passphrase: None,
+			lock_after: Some(15 * 60),
+			seed: None,
+			seed_index: 0,
+			device: rand::random(),
+			padding: Padding::default(),
+			sealed_sender: false,
+		}
+	}
 }
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct Data {
+	pub servers: Vec<Server>,
 	pub accounts: Vec<Account>,
 	pub contacts: Vec<Contact>,
+	pub groups: Vec<IGroup>,
 	pub messages: Vec<Message>,
+	pub outbox: Vec<Outgoing>,
+	pub requests: Vec<Request>,
+	/// Keyed by the account that blocked them.
+	pub blocked: HashMap<VerifyingKey, Vec<VerifyingKey>>,
+	pub unread: HashMap<VerifyingKey, usize>,
+	pub locked: Vec<(VerifyingKey, Url)>,
 }
@@ -1,5 +1,11 @@
 use {
-	crate::messages::{ProjectTootMessage, WebSocketMessage},
+	crate::{
+		messages::{ProjectTootMessage, WebSocketMessage},
+		states::Server,
+		tls,
+		utils,
+	},
+	ed25519_dalek::VerifyingKey,
 	futures::{
 		channel::mpsc::Sender,
 		sink::SinkExt,
@@ -7,25 +13,71 @@ use {
 	},
 	iced::subscription::{self, Subscription},
 	project_toot_sdk::{IWebSocketRequest, IWebSocketResponse},
+	rustls::ServerName,
+	std::time::Duration,
 	tokio::{
+		io::{AsyncRead, AsyncWrite},
 		net::TcpStream,
 		sync::mpsc::{self, Receiver},
 	},
+	tokio_rustls::TlsConnector,
 	tokio_tungstenite::{
-		tungstenite::{self, Message},
-		MaybeTlsStream,
+		tungstenite::{self, error::UrlError, Message},
 		WebSocketStream,
 	},
 	url::Url,
 };
 
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+trait Transport: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Transport for T {}
+
+type Socket = WebSocketStream<Box<dyn Transport>>;
+
+async fn connect(baseurl: &Url, server: Option<&Server>) -> Result<Socket, tungstenite::Error> {
+	let mut wsurl = baseurl.join("/ws").unwrap();
+	let host = wsurl
+		.host_str()
+		.ok_or(UrlError::NoHostName)?
+		.trim_start_matches('[')
+		.trim_end_matches(']')
+		.to_owned();
+	let port = wsurl
+		.port_or_known_default()
+		.ok_or(UrlError::UnsupportedUrlScheme)?;
+	let stream = TcpStream::connect((host.as_str(), port)).await?;
+
+	let stream: Box<dyn Transport> = match wsurl.scheme() {
+		"https" => {
+			let name = ServerName::try_from(host.as_str()).or(Err(UrlError::NoHostName))?;
+			let _ = wsurl.set_scheme("wss");
+
+			Box::new(
+				TlsConnector::from(tls::client_config(server)?)
+					.connect(name, stream)
+					.await?,
+			)
+		},
+		"http" => {
+			let _ = wsurl.set_scheme("ws");
+
+			Box::new(stream)
+		},
+		_ => return Err(UrlError::UnsupportedUrlScheme.into()),
+	};
+
+	let (websocket, _) = tokio_tungstenite::client_async(wsurl.as_str(), stream).await?;
+
+	Ok(websocket)
+}
+
 async fn requests_handler(
-	mut ws_tx: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+	mut ws_tx: SplitSink<Socket, Message>,
 	mut req_rx: Receiver<IWebSocketRequest>,
-) -> (
-	SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
-	Receiver<IWebSocketRequest>,
-) {
+) -> (SplitSink<Socket, Message>, Receiver<IWebSocketRequest>) {
 	while let Some(req) = req_rx.recv().await {
 		if ws_tx
 			.send(Message::Binary(borsh::to_vec(&req).unwrap()))
@@ -40,19 +92,19 @@ async fn requests_handler(
 }
 
 async fn responses_handler(
-	mut ws_rx: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+	mut ws_rx: SplitStream<Socket>,
 	mut output: Sender<ProjectTootMessage>,
-) -> (
-	SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
-	tungstenite::Error,
-) {
+	address: VerifyingKey,
+) -> (SplitStream<Socket>, tungstenite::Error) {
 	let error = loop {
 		match ws_rx.try_next().await {
 			Ok(Some(Message::Binary(bytes))) => {
 				if let Ok(resp) = borsh::from_slice::<IWebSocketResponse>(&bytes) {
 					match resp {
 						IWebSocketResponse::Authorized => {
-							let _ = output.send(WebSocketMessage::Authorized.into()).await;
+							let _ = output
+								.send(WebSocketMessage::Authorized(address).into())
+								.await;
 						},
 						IWebSocketResponse::NewMessage(msg) => {
 							let _ = output.send(WebSocketMessage::NewMessage(msg).into()).await;
@@ -68,26 +120,42 @@ async fn responses_handler(
 	(ws_rx, error)
 }
 
-pub fn subscribe(baseurl: Url) -> Subscription<ProjectTootMessage> {
+pub fn subscribe(
+	address: VerifyingKey,
+	baseurl: Url,
+	server: Option<Server>,
+) -> Subscription<ProjectTootMessage> {
 	struct WebSocketWorker;
 
 	subscription::channel(
-		std::any::TypeId::of::<WebSocketWorker>(),
+		(
+			std::any::TypeId::of::<WebSocketWorker>(),
+			address,
+			baseurl.clone(),
+			server.clone(),
+		),
 		128,
-		|mut output| async move {
+		move |mut output| async move {
+			let mut attempt = 0;
+
 			loop {
-				let mut wsurl = baseurl.clone();
-				let _ = wsurl.set_scheme("ws");
-				match tokio_tungstenite::connect_async(wsurl.join("/ws").unwrap()).await {
-					Ok((websocket, _)) => {
+				let _ = output
+					.send(WebSocketMessage::Connecting(address).into())
+					.await;
+
+				match connect(&baseurl, server.as_ref()).await {
+					Ok(websocket) => {
+						attempt = 0;
+
 						let (req_tx, req_rx) = mpsc::channel::<IWebSocketRequest>(64);
 						let (ws_tx, ws_rx) = websocket.split();
 
-						let resp_handle = tokio::spawn(responses_handler(ws_rx, output.clone()));
+						let resp_handle =
+							tokio::spawn(responses_handler(ws_rx, output.clone(), address));
 						let req_handle = tokio::spawn(requests_handler(ws_tx, req_rx));
 
 						let _ = output
-							.send(WebSocketMessage::Connected(req_tx).into())
+							.send(WebSocketMessage::Connected(address, req_tx).into())
 							.await;
 
 						if let Ok((_, error)) = resp_handle.await {
@@ -100,11 +168,16 @@ pub fn subscribe(baseurl: Url) -> Subscription<ProjectTootMessage> {
 						if !req_handle.is_finished() {
 							req_handle.abort();
 						}
-
-						let _ = output.send(WebSocketMessage::Disconnected.into()).await;
 					},
 					Err(error) => eprintln!("{:#?}", error),
 				}
+
+				let _ = output
+					.send(WebSocketMessage::Disconnected(address).into())
+					.await;
+
+				tokio::time::sleep(utils::backoff(attempt, BACKOFF_BASE, BACKOFF_MAX)).await;
+				attempt = attempt.saturating_add(1);
 			}
 		},
 	)
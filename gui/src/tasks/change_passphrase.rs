@@ -0,0 +1,77 @@
+use {
+	crate::{
+		hd::Seed,
+		states::Account,
+		vault::{self, VaultKey},
+	},
+	tokio_rusqlite::Connection,
+};
+
+#[derive(Debug)]
+pub(crate) enum ChangePassphraseTaskError {
+	JoinError(tokio::task::JoinError),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<tokio::task::JoinError> for ChangePassphraseTaskError {
+	fn from(value: tokio::task::JoinError) -> Self {
+		Self::JoinError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for ChangePassphraseTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+pub(crate) async fn run(
+	database: Connection,
+	accounts: Vec<Account>,
+	seed: Option<Seed>,
+	passphrase: Option<String>,
+) -> Result<Option<(String, VaultKey)>, ChangePassphraseTaskError> {
+	let vault = match passphrase {
+		Some(passphrase) => {
+			Some(tokio::task::spawn_blocking(move || vault::create(&passphrase)).await?)
+		},
+		None => None,
+	};
+
+	Ok(database
+		.call(move |conn| {
+			let tx = conn.transaction()?;
+			for account in accounts.iter() {
+				tx.execute(
+					include_str!("../../sql/update-account-secret.sql"),
+					[
+						bs58::encode(account.address.as_bytes()).into_string(),
+						vault::encode_secret(vault.as_ref().map(|(_, key)| key), &account.secret),
+					],
+				)?;
+			}
+
+			if let Some(seed) = seed {
+				tx.execute(
+					include_str!("../../sql/update-setting.sql"),
+					[
+						"seed",
+						vault::encode_seed(vault.as_ref().map(|(_, key)| key), &seed).as_str(),
+					],
+				)?;
+			}
+
+			if let Some((lock, _)) = &vault {
+				tx.execute(
+					include_str!("../../sql/update-setting.sql"),
+					["passphrase", lock],
+				)?;
+			} else {
+				tx.execute(include_str!("../../sql/delete-setting.sql"), ["passphrase"])?;
+			}
+			tx.commit()?;
+
+			Ok(vault)
+		})
+		.await?)
+}
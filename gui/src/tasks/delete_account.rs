@@ -8,8 +8,40 @@ pub(crate) async fn run(
 ) -> Result<(), DeleteAccountTaskError> {
 	database
 		.call(move |conn| {
-			conn.prepare(include_str!("../../sql/delete-account.sql"))?
-				.execute([bs58::encode(address.as_bytes()).into_string()])?;
+			let address = bs58::encode(address.as_bytes()).into_string();
+
+			let tx = conn.transaction()?;
+			tx.execute(
+				include_str!("../../sql/delete-account-contacts.sql"),
+				[&address],
+			)?;
+			tx.execute(
+				include_str!("../../sql/delete-account-outbox-deliveries.sql"),
+				[&address],
+			)?;
+			tx.execute(
+				include_str!("../../sql/delete-account-outbox.sql"),
+				[&address],
+			)?;
+			tx.execute(
+				include_str!("../../sql/delete-account-sessions.sql"),
+				[&address],
+			)?;
+			tx.execute(
+				include_str!("../../sql/delete-account-prekeys.sql"),
+				[&address],
+			)?;
+			tx.execute(
+				include_str!("../../sql/delete-account-sender-keys.sql"),
+				[&address],
+			)?;
+			tx.execute(
+				include_str!("../../sql/delete-account-pending-group-messages.sql"),
+				[&address],
+			)?;
+			tx.execute(include_str!("../../sql/delete-account.sql"), [&address])?;
+			tx.commit()?;
+
 			Ok(())
 		})
 		.await
@@ -4,12 +4,16 @@ pub(crate) type DeleteContactTaskError = tokio_rusqlite::Error;
 
 pub(crate) async fn run(
 	database: Connection,
+	owner: VerifyingKey,
 	address: VerifyingKey,
 ) -> Result<(), DeleteContactTaskError> {
 	database
 		.call(move |conn| {
 			conn.prepare(include_str!("../../sql/delete-contact.sql"))?
-				.execute([bs58::encode(address.as_bytes()).into_string()])?;
+				.execute([
+					bs58::encode(owner.as_bytes()).into_string(),
+					bs58::encode(address.as_bytes()).into_string(),
+				])?;
 			Ok(())
 		})
 		.await
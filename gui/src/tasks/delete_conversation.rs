@@ -0,0 +1,58 @@
+use {ed25519_dalek::VerifyingKey, std::path::PathBuf, tokio_rusqlite::Connection};
+
+#[derive(Debug)]
+pub(crate) enum DeleteConversationTaskError {
+	IoError(std::io::Error),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<std::io::Error> for DeleteConversationTaskError {
+	fn from(value: std::io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for DeleteConversationTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+pub(crate) async fn run(
+	basedir: PathBuf,
+	database: Connection,
+	account: VerifyingKey,
+	opposite: VerifyingKey,
+) -> Result<(), DeleteConversationTaskError> {
+	let messages = database
+		.call(move |conn| {
+			let messages = conn
+				.prepare(include_str!("../../sql/get-messages.sql"))?
+				.query_map(
+					[
+						bs58::encode(account.as_bytes()).into_string(),
+						bs58::encode(opposite.as_bytes()).into_string(),
+					],
+					super::get_messages::from_row,
+				)?
+				.flatten()
+				.collect::<Vec<_>>();
+
+			let mut stmt = conn.prepare(include_str!("../../sql/delete-message.sql"))?;
+			for message in messages.iter() {
+				stmt.execute([
+					bs58::encode(account.as_bytes()).into_string(),
+					bs58::encode(message.hash).into_string(),
+				])?;
+			}
+
+			Ok(messages)
+		})
+		.await?;
+
+	for message in messages {
+		super::remove_data::local(basedir.clone(), &account, &message.data_hash).await?;
+	}
+
+	Ok(())
+}
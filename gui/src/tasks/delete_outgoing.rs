@@ -0,0 +1,15 @@
+use tokio_rusqlite::Connection;
+
+pub(crate) type DeleteOutgoingTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(database: Connection, id: i64) -> Result<(), DeleteOutgoingTaskError> {
+	database
+		.call(move |conn| {
+			let tx = conn.transaction()?;
+			tx.execute(include_str!("../../sql/delete-outbox-deliveries.sql"), [id])?;
+			tx.execute(include_str!("../../sql/delete-outgoing.sql"), [id])?;
+			tx.commit()?;
+			Ok(())
+		})
+		.await
+}
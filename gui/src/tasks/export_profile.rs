@@ -0,0 +1,152 @@
+use {
+	crate::{
+		profile::{
+			self,
+			Profile,
+			ProfileAccount,
+			ProfileContact,
+			ProfileMessage,
+			PORTABLE_SETTINGS,
+		},
+		states::Account,
+		utils,
+		vault::{self, VaultKey},
+	},
+	ed25519_dalek::VerifyingKey,
+	std::path::PathBuf,
+	tokio_rusqlite::Connection,
+};
+
+#[derive(Debug)]
+pub(crate) enum ExportProfileTaskError {
+	IoError(std::io::Error),
+	JoinError(tokio::task::JoinError),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<std::io::Error> for ExportProfileTaskError {
+	fn from(value: std::io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<tokio::task::JoinError> for ExportProfileTaskError {
+	fn from(value: tokio::task::JoinError) -> Self {
+		Self::JoinError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for ExportProfileTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+pub(crate) async fn run(
+	basedir: PathBuf,
+	database: Connection,
+	key: Option<VaultKey>,
+	accounts: Vec<Account>,
+	path: PathBuf,
+	passphrase: String,
+) -> Result<(), ExportProfileTaskError> {
+	let (contacts, messages, settings, seed) = database
+		.call(move |conn| {
+			let contacts = conn
+				.prepare(include_str!("../../sql/export-contacts.sql"))?
+				.query_map([], |row| {
+					Ok(ProfileContact {
+						owner: row.get(0)?,
+						address: row.get(1)?,
+						name: row.get(2)?,
+						timer: row.get(3)?,
+						verified: row.get(4)?,
+					})
+				})?
+				.collect::<Result<Vec<_>, _>>()?;
+			let messages = conn
+				.prepare(include_str!("../../sql/export-messages.sql"))?
+				.query_map([], |row| {
+					Ok(ProfileMessage {
+						hash: row.get(0)?,
+						sender: row.get(1)?,
+						receiver: row.get(2)?,
+						data_hash: row.get(3)?,
+						timestamp: row.get(4)?,
+						success: row.get(5)?,
+						expires_at: row.get(6)?,
+						verified: row.get(7)?,
+						owner: row.get(8)?,
+						read: row.get(9)?,
+						group: row.get(10)?,
+					})
+				})?
+				.collect::<Result<Vec<_>, _>>()?;
+
+			let mut settings = Vec::new();
+			let (mut seed, mut seed_index) = (None, 0);
+			for row in conn
+				.prepare(include_str!("../../sql/get-settings.sql"))?
+				.query_map([], |row| {
+					Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+				})? {
+				let (name, value) = row?;
+				match name.as_str() {
+					"seed" => seed = vault::decode_seed(key.as_ref(), &value),
+					"seed_index" => seed_index = value.parse().unwrap_or_default(),
+					_ if PORTABLE_SETTINGS.contains(&name.as_str()) => settings.push((name, value)),
+					_ => {},
+				}
+			}
+
+			Ok((
+				contacts,
+				messages,
+				settings,
+				seed.map(|seed| (seed, seed_index)),
+			))
+		})
+		.await?;
+
+	let mut blobs = Vec::new();
+	for message in messages.iter() {
+		let (Some(owner), Ok(data_hash)) = (
+			bs58::decode(&message.owner)
+				.into_vec()
+				.ok()
+				.and_then(|x| VerifyingKey::try_from(x.as_slice()).ok()),
+			bs58::decode(&message.data_hash).into_vec(),
+		) else {
+			continue
+		};
+		let key = utils::data_key(&owner, &data_hash);
+		if blobs.iter().any(|(x, _)| x == &key) {
+			continue
+		}
+
+		match super::read_data::local(basedir.clone(), &owner, &data_hash).await {
+			Ok(data) => blobs.push((key, data)),
+			Err(err) => eprintln!("{:#?}", err),
+		}
+	}
+
+	let profile = Profile {
+		accounts: accounts
+			.into_iter()
+			.map(|account| ProfileAccount {
+				secret: account.secret.to_bytes(),
+				name: account.name,
+				server: account.server.to_string(),
+			})
+			.collect(),
+		contacts,
+		messages,
+		blobs,
+		settings,
+		seed,
+	};
+	let data = tokio::task::spawn_blocking(move || profile::seal(&passphrase, &profile)).await?;
+	tokio::fs::write(path, data).await?;
+
+	Ok(())
+}
@@ -1,29 +1,48 @@
-use {crate::states::Account, ed25519_dalek::SigningKey, tokio_rusqlite::Connection};
+use {
+	crate::{
+		states::{Account, DEFAULT_SERVER},
+		vault::{self, VaultKey},
+	},
+	ed25519_dalek::VerifyingKey,
+	tokio_rusqlite::Connection,
+	url::Url,
+};
 
 pub(crate) type GetAccountsTaskError = tokio_rusqlite::Error;
 
-pub(crate) async fn run(database: Connection) -> Result<Vec<Account>, GetAccountsTaskError> {
+pub(crate) async fn run(
+	database: Connection,
+	key: Option<VaultKey>,
+) -> Result<Vec<Account>, GetAccountsTaskError> {
 	database
-		.call(|conn| {
+		.call(move |conn| {
 			Ok(conn
 				.prepare(include_str!("../../sql/get-accounts.sql"))?
 				.query_map([], |row| {
-					let secret = SigningKey::from_bytes(
-						&bs58::decode(row.get::<_, String>(0)?)
+					let address = VerifyingKey::from_bytes(
+						&bs58::decode(row.get::<_, String>(3)?)
 							.into_vec()
-							.unwrap()
+							.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
 							.try_into()
 							.unwrap(),
-					);
-					let address = secret.verifying_key();
+					)
+					.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?;
+					let secret =
+						vault::decode_secret(key.as_ref(), &address, &row.get::<_, String>(0)?)
+							.ok_or(rusqlite::Error::InvalidQuery)?;
 					Ok(Account {
 						secret,
 						address,
 						name: row.get(1)?,
+						server: Url::parse(
+							row.get::<_, Option<String>>(2)?
+								.as_deref()
+								.unwrap_or(DEFAULT_SERVER),
+						)
+						.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?,
 					})
 				})?
-				.filter(|x| x.is_ok())
-				.map(|x| x.unwrap())
+				.flatten()
 				.collect::<Vec<_>>())
 		})
 		.await
@@ -2,12 +2,15 @@ use {crate::states::Contact, ed25519_dalek::VerifyingKey, tokio_rusqlite::Connec
 
 pub(crate) type GetContactsTaskError = tokio_rusqlite::Error;
 
-pub(crate) async fn run(database: Connection) -> Result<Vec<Contact>, GetContactsTaskError> {
+pub(crate) async fn run(
+	database: Connection,
+	owner: VerifyingKey,
+) -> Result<Vec<Contact>, GetContactsTaskError> {
 	database
-		.call(|conn| {
+		.call(move |conn| {
 			Ok(conn
 				.prepare(include_str!("../../sql/get-contacts.sql"))?
-				.query_map([], |row| {
+				.query_map([bs58::encode(owner.as_bytes()).into_string()], |row| {
 					Ok(Contact {
 						address: VerifyingKey::from_bytes(
 							&bs58::decode(row.get::<_, String>(0)?)
@@ -16,12 +19,13 @@ pub(crate) async fn run(database: Connection) -> Result<Vec<Contact>, GetContact
 								.try_into()
 								.unwrap(),
 						)
-						.or_else(|err| Err(rusqlite::Error::UserFunctionError(Box::new(err))))?,
+						.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?,
 						name: row.get(1)?,
+						timer: row.get(2)?,
+						verified: row.get(3)?,
 					})
 				})?
-				.filter(|x| x.is_ok())
-				.map(|x| x.unwrap())
+				.flatten()
 				.collect::<Vec<_>>())
 		})
 		.await
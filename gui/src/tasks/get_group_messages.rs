@@ -0,0 +1,35 @@
+use {
+	crate::states::{Account, Message},
+	project_toot_sdk::GroupId,
+	std::path::PathBuf,
+	tokio_rusqlite::Connection,
+};
+
+pub(crate) type GetGroupMessagesTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	basedir: PathBuf,
+	account: Account,
+	group: GroupId,
+	database: Connection,
+) -> Result<Vec<Message>, GetGroupMessagesTaskError> {
+	let mut messages = database
+		.call(move |conn| {
+			Ok(conn
+				.prepare(include_str!("../../sql/get-group-messages.sql"))?
+				.query_map(
+					[
+						bs58::encode(account.address.as_bytes()).into_string(),
+						bs58::encode(group).into_string(),
+					],
+					super::get_messages::from_row,
+				)?
+				.flatten()
+				.collect::<Vec<_>>())
+		})
+		.await?;
+
+	super::get_messages::load_contents(basedir, &account, &mut messages).await;
+
+	Ok(messages)
+}
@@ -1,5 +1,6 @@
 use {
 	crate::{
+		envelope::DecryptError,
 		states::{Account, Contact, Message},
 		utils,
 	},
@@ -10,6 +11,83 @@ use {
 
 pub(crate) type GetMessagesTaskError = tokio_rusqlite::Error;
 
+pub(crate) fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Message> {
+	Ok(Message {
+		hash: bs58::decode(row.get::<_, String>(0)?)
+			.into_vec()
+			.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
+			.try_into()
+			.unwrap(),
+		sender: VerifyingKey::from_bytes(
+			&bs58::decode(row.get::<_, String>(1)?)
+				.into_vec()
+				.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
+				.try_into()
+				.unwrap(),
+		)
+		.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?,
+		receiver: VerifyingKey::from_bytes(
+			&bs58::decode(row.get::<_, String>(2)?)
+				.into_vec()
+				.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
+				.try_into()
+				.unwrap(),
+		)
+		.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?,
+		data_hash: bs58::decode(row.get::<_, String>(3)?)
+			.into_vec()
+			.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
+			.try_into()
+			.unwrap(),
+		timestamp: row.get(4)?,
+		success: row.get(5)?,
+		content: Ok(String::new()),
+		expires_at: row.get(6)?,
+		verified: row.get(7)?,
+		group: row
+			.get::<_, Option<String>>(8)?
+			.map(|group| {
+				bs58::decode(group)
+					.into_vec()
+					.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
+					.try_into()
+					.or(Err(rusqlite::Error::InvalidQuery))
+			})
+			.transpose()?,
+		owner: VerifyingKey::from_bytes(
+			&bs58::decode(row.get::<_, String>(9)?)
+				.into_vec()
+				.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
+				.try_into()
+				.or(Err(rusqlite::Error::InvalidQuery))?,
+		)
+		.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?,
+	})
+}
+
+pub(crate) async fn load_contents(basedir: PathBuf, account: &Account, messages: &mut [Message]) {
+	for message in messages.iter_mut() {
+		let res =
+			super::read_data::local(basedir.clone(), &message.owner, &message.data_hash).await;
+		message.content = match res {
+			Ok(data) => utils::decrypt(
+				account,
+				&if account.address == message.sender {
+					message.receiver
+				} else {
+					message.sender
+				},
+				&data,
+			)
+			.map(|payload| utils::content(&payload)),
+			Err(err) => {
+				eprintln!("{:#?}", err);
+				Err(DecryptError::Missing)
+			},
+		};
+	}
+}
+
 pub(crate) async fn run(
 	basedir: PathBuf,
 	account: Account,
@@ -25,74 +103,14 @@ pub(crate) async fn run(
 						bs58::encode(account.address.as_bytes()).into_string(),
 						bs58::encode(contact.address.as_bytes()).into_string(),
 					],
-					|row| {
-						Ok(Message {
-							hash: bs58::decode(row.get::<_, String>(0)?)
-								.into_vec()
-								.or_else(|err| {
-									Err(rusqlite::Error::UserFunctionError(Box::new(err)))
-								})?
-								.try_into()
-								.unwrap(),
-							sender: VerifyingKey::from_bytes(
-								&bs58::decode(row.get::<_, String>(1)?)
-									.into_vec()
-									.or_else(|err| {
-										Err(rusqlite::Error::UserFunctionError(Box::new(err)))
-									})?
-									.try_into()
-									.unwrap(),
-							)
-							.or_else(|err| {
-								Err(rusqlite::Error::UserFunctionError(Box::new(err)))
-							})?,
-							receiver: VerifyingKey::from_bytes(
-								&bs58::decode(row.get::<_, String>(2)?)
-									.into_vec()
-									.or_else(|err| {
-										Err(rusqlite::Error::UserFunctionError(Box::new(err)))
-									})?
-									.try_into()
-									.unwrap(),
-							)
-							.or_else(|err| {
-								Err(rusqlite::Error::UserFunctionError(Box::new(err)))
-							})?,
-							data_hash: bs58::decode(row.get::<_, String>(3)?)
-								.into_vec()
-								.or_else(|err| {
-									Err(rusqlite::Error::UserFunctionError(Box::new(err)))
-								})?
-								.try_into()
-								.unwrap(),
-							timestamp: row.get(4)?,
-							success: row.get(5)?,
-							content: String::new(),
-						})
-					},
+					from_row,
 				)?
-				.filter(|x| x.is_ok())
-				.map(|x| x.unwrap())
+				.flatten()
 				.collect::<Vec<_>>())
 		})
 		.await?;
 
-	for message in messages.iter_mut() {
-		let res = super::read_data::run(basedir.clone(), message.data_hash).await;
-		if let Ok(data) = res {
-			message.content = utils::decrypt(
-				&account,
-				&if account.address == message.sender {
-					message.receiver
-				} else {
-					message.sender
-				},
-				&data,
-			);
-		} else {
-			eprintln!("{:#?}", res.unwrap_err());
-		}
-	}
+	load_contents(basedir, &account, &mut messages).await;
 
 	Ok(messages)
 }
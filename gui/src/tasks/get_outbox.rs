@@ -0,0 +1,61 @@
+use {
+	crate::{
+		states::{Account, Outgoing},
+		utils,
+	},
+	ed25519_dalek::VerifyingKey,
+	tokio_rusqlite::Connection,
+};
+
+pub(crate) type GetOutboxTaskError = tokio_rusqlite::Error;
+
+fn address(value: String) -> rusqlite::Result<VerifyingKey> {
+	VerifyingKey::from_bytes(
+		&bs58::decode(value)
+			.into_vec()
+			.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
+			.try_into()
+			.unwrap(),
+	)
+	.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))
+}
+
+pub(crate) async fn run(
+	database: Connection,
+	accounts: Vec<Account>,
+) -> Result<Vec<Outgoing>, GetOutboxTaskError> {
+	let mut outbox = database
+		.call(|conn| {
+			Ok(conn
+				.prepare(include_str!("../../sql/get-outbox.sql"))?
+				.query_map([], |row| {
+					Ok(Outgoing {
+						id: row.get(0)?,
+						sender: address(row.get(1)?)?,
+						receiver: address(row.get(2)?)?,
+						local: row.get(3)?,
+						ttl: row.get(4)?,
+						timestamp: row.get(5)?,
+						attempts: row.get(6)?,
+						next_attempt_at: row.get(7)?,
+						content: String::new(),
+						sending: false,
+					})
+				})?
+				.flatten()
+				.collect::<Vec<_>>())
+		})
+		.await?;
+
+	outbox.retain_mut(|outgoing| {
+		let Some(account) = accounts.iter().find(|x| x.address == outgoing.sender) else {
+			return false
+		};
+
+		outgoing.content = utils::decrypt(account, &outgoing.receiver, &outgoing.local)
+			.map_or_else(|err| err.to_string(), |payload| utils::content(&payload));
+		true
+	});
+
+	Ok(outbox)
+}
@@ -0,0 +1,42 @@
+use {
+	crate::states::{Account, Request},
+	std::path::PathBuf,
+	tokio_rusqlite::Connection,
+};
+
+pub(crate) type GetRequestsTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	basedir: PathBuf,
+	account: Account,
+	database: Connection,
+) -> Result<Vec<Request>, GetRequestsTaskError> {
+	let mut messages = database
+		.call(move |conn| {
+			Ok(conn
+				.prepare(include_str!("../../sql/get-requests.sql"))?
+				.query_map(
+					[bs58::encode(account.address.as_bytes()).into_string()],
+					super::get_messages::from_row,
+				)?
+				.flatten()
+				.collect::<Vec<_>>())
+		})
+		.await?;
+
+	super::get_messages::load_contents(basedir, &account, &mut messages).await;
+
+	let mut requests = Vec::<Request>::new();
+	for message in messages {
+		if let Some(request) = requests.iter_mut().find(|x| x.address == message.sender) {
+			request.messages.push(message);
+		} else {
+			requests.push(Request {
+				address: message.sender,
+				messages: vec![message],
+			});
+		}
+	}
+
+	Ok(requests)
+}
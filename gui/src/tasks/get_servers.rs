@@ -0,0 +1,25 @@
+use {crate::states::Server, std::path::PathBuf, tokio_rusqlite::Connection, url::Url};
+
+pub(crate) type GetServersTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(database: Connection) -> Result<Vec<Server>, GetServersTaskError> {
+	database
+		.call(|conn| {
+			Ok(conn
+				.prepare(include_str!("../../sql/get-servers.sql"))?
+				.query_map([], |row| {
+					Ok(Server {
+						url: Url::parse(&row.get::<_, String>(0)?)
+							.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?,
+						ca: row.get::<_, Option<String>>(1)?.map(PathBuf::from),
+						fingerprint: row
+							.get::<_, Option<String>>(2)?
+							.and_then(|x| hex::decode(x).ok())
+							.and_then(|x| x.try_into().ok()),
+					})
+				})?
+				.flatten()
+				.collect::<Vec<_>>())
+		})
+		.await
+}
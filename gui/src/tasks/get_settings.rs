@@ -0,0 +1,63 @@
+use {
+	crate::{envelope::Padding, states::Settings},
+	tokio_rusqlite::Connection,
+	url::Url,
+};
+
+pub(crate) type GetSettingsTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(database: Connection) -> Result<Settings, GetSettingsTaskError> {
+	database
+		.call(|conn| {
+			let mut settings = Settings::default();
+			let mut device = None;
+
+			for row in conn
+				.prepare(include_str!("../../sql/get-settings.sql"))?
+				.query_map([], |row| {
+					Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+				})? {
+				let (key, value) = row?;
+				match key.as_str() {
+					"server" => {
+						if let Ok(server) = Url::parse(&value) {
+							settings.server = server;
+						}
+					},
+					"passphrase" => settings.passphrase = Some(value),
+					"lock_after" => settings.lock_after = value.parse().ok(),
+					"seed" => settings.seed = Some(value),
+					"padding" => {
+						if let Some(padding) = Padding::parse(&value) {
+							settings.padding = padding;
+						}
+					},
+					"sealed_sender" => settings.sealed_sender = value == "on",
+					"seed_index" => settings.seed_index = value.parse().unwrap_or_default(),
+					"device" => {
+						device = bs58::decode(value)
+							.into_vec()
+							.ok()
+							.and_then(|x| x.try_into().ok())
+					},
+					_ => {},
+				}
+			}
+
+			// The device id is generated once and never changes afterwards.
+			if let Some(device) = device {
+				settings.device = device;
+			} else {
+				conn.execute(
+					include_str!("../../sql/update-setting.sql"),
+					[
+						"device",
+						bs58::encode(settings.device).into_string().as_str(),
+					],
+				)?;
+			}
+
+			Ok(settings)
+		})
+		.await
+}
@@ -0,0 +1,29 @@
+use {ed25519_dalek::VerifyingKey, std::collections::HashMap, tokio_rusqlite::Connection};
+
+pub(crate) type GetUnreadTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	database: Connection,
+) -> Result<HashMap<VerifyingKey, usize>, GetUnreadTaskError> {
+	database
+		.call(|conn| {
+			Ok(conn
+				.prepare(include_str!("../../sql/get-unread.sql"))?
+				.query_map([], |row| {
+					Ok((
+						VerifyingKey::from_bytes(
+							&bs58::decode(row.get::<_, String>(0)?)
+								.into_vec()
+								.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
+								.try_into()
+								.unwrap(),
+						)
+						.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?,
+						row.get::<_, usize>(1)?,
+					))
+				})?
+				.flatten()
+				.collect::<HashMap<_, _>>())
+		})
+		.await
+}
@@ -0,0 +1,234 @@
+use {
+	crate::{
+		profile::{self, Profile, PORTABLE_SETTINGS},
+		states::{Account, DEFAULT_SERVER},
+		utils,
+		vault::{self, VaultKey},
+	},
+	ed25519_dalek::{SigningKey, VerifyingKey},
+	std::path::PathBuf,
+	tokio_rusqlite::Connection,
+	url::Url,
+};
+
+#[derive(Debug)]
+pub(crate) enum ImportProfileTaskError {
+	InvalidProfile,
+	IoError(std::io::Error),
+	JoinError(tokio::task::JoinError),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<std::io::Error> for ImportProfileTaskError {
+	fn from(value: std::io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<tokio::task::JoinError> for ImportProfileTaskError {
+	fn from(value: tokio::task::JoinError) -> Self {
+		Self::JoinError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for ImportProfileTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+/// Merges the profile into the local database, returning the accounts that were not known yet.
+pub(crate) async fn merge(
+	basedir: PathBuf,
+	database: Connection,
+	key: Option<VaultKey>,
+	profile: Profile,
+) -> Result<Vec<Account>, ImportProfileTaskError> {
+	let accounts = profile
+		.accounts
+		.into_iter()
+		.map(|account| {
+			let secret = SigningKey::from_bytes(&account.secret);
+			Account {
+				address: secret.verifying_key(),
+				secret,
+				name: account.name,
+				server: Url::parse(&account.server)
+					.unwrap_or_else(|_| Url::parse(DEFAULT_SERVER).unwrap()),
+			}
+		})
+		.collect::<Vec<_>>();
+	let messages = profile.messages;
+	let contacts = profile.contacts;
+	let settings = profile.settings;
+	let seed = profile.seed;
+
+	let (accounts, inserted) = database
+		.call(move |conn| {
+			let tx = conn.transaction()?;
+
+			let mut added = Vec::new();
+			for account in accounts {
+				let address = bs58::encode(account.address.as_bytes()).into_string();
+				let exists = tx
+					.prepare(include_str!("../../sql/get-account.sql"))?
+					.exists([&address])?;
+				if exists {
+					continue;
+				}
+
+				tx.execute(
+					include_str!("../../sql/insert-account.sql"),
+					[
+						vault::encode_secret(key.as_ref(), &account.secret),
+						address,
+						account.name.clone(),
+						account.server.to_string(),
+					],
+				)?;
+				added.push(account);
+			}
+
+			for contact in contacts {
+				tx.execute(
+					include_str!("../../sql/import-contact.sql"),
+					(
+						contact.owner,
+						contact.address,
+						contact.name,
+						contact.timer,
+						contact.verified,
+					),
+				)?;
+			}
+
+			// Settings made on this device win over the imported ones, and so does its own seed.
+			for (name, value) in settings {
+				if PORTABLE_SETTINGS.contains(&name.as_str()) {
+					tx.execute(include_str!("../../sql/import-setting.sql"), [name, value])?;
+				}
+			}
+			if let Some((seed, seed_index)) = seed {
+				if tx.execute(
+					include_str!("../../sql/import-setting.sql"),
+					["seed", &vault::encode_seed(key.as_ref(), &seed)],
+				)? != 0
+				{
+					tx.execute(
+						include_str!("../../sql/update-setting.sql"),
+						["seed_index", &seed_index.to_string()],
+					)?;
+				}
+			}
+
+			let mut inserted = Vec::new();
+			for message in messages {
+				if tx.execute(
+					include_str!("../../sql/import-message.sql"),
+					(
+						message.hash,
+						message.sender,
+						message.receiver,
+						&message.data_hash,
+						message.timestamp,
+						message.success,
+						message.expires_at,
+						message.verified,
+						&message.owner,
+						message.read,
+						message.group,
+					),
+				)? != 0
+				{
+					let owner = bs58::decode(&message.owner)
+						.into_vec()
+						.ok()
+						.and_then(|x| VerifyingKey::try_from(x.as_slice()).ok());
+					if let (Some(owner), Ok(data_hash)) =
+						(owner, bs58::decode(&message.data_hash).into_vec())
+					{
+						inserted.push(utils::data_key(&owner, &data_hash));
+					}
+				}
+			}
+
+			tx.commit()?;
+
+			Ok((added, inserted))
+		})
+		.await?;
+
+	for (key, data) in profile.blobs {
+		if inserted.contains(&key) {
+			super::write_data::run(basedir.clone(), key, data).await?;
+		}
+	}
+
+	Ok(accounts)
+}
+
+pub(crate) async fn run(
+	basedir: PathBuf,
+	database: Connection,
+	key: Option<VaultKey>,
+	path: PathBuf,
+	passphrase: String,
+) -> Result<Vec<Account>, ImportProfileTaskError> {
+	let data = tokio::fs::read(path).await?;
+	let profile = tokio::task::spawn_blocking(move || profile::open(&passphrase, &data))
+		.await?
+		.ok_or(ImportProfileTaskError::InvalidProfile)?;
+
+	merge(basedir, database, key, profile).await
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::tasks::get_settings};
+
+	#[tokio::test]
+	async fn settings_and_seed() {
+		let basedir = std::env::temp_dir().join(hex::encode(rand::random::<[u8; 8]>()));
+		let database = Connection::open_in_memory().await.unwrap();
+		crate::tasks::init_tables::run(database.clone())
+			.await
+			.unwrap();
+		database
+			.call(|conn| {
+				Ok(conn.execute(
+					include_str!("../../sql/update-setting.sql"),
+					["server", "https://local.example/"],
+				)?)
+			})
+			.await
+			.unwrap();
+		let device = get_settings::run(database.clone()).await.unwrap().device;
+
+		let seed = rand::random::<[u8; 32]>();
+		let profile = Profile {
+			settings: vec![
+				(
+					String::from("server"),
+					String::from("https://imported.example/"),
+				),
+				(String::from("sealed_sender"), String::from("on")),
+				(String::from("device"), bs58::encode([1; 16]).into_string()),
+			],
+			seed: Some((seed, 3)),
+			..Default::default()
+		};
+		merge(basedir, database.clone(), None, profile)
+			.await
+			.unwrap();
+
+		let settings = get_settings::run(database).await.unwrap();
+		assert_eq!(settings.server.as_str(), "https://local.example/");
+		assert!(settings.sealed_sender);
+		assert_eq!(settings.device, device);
+		assert_eq!(
+			vault::decode_seed(None, &settings.seed.unwrap()),
+			Some(seed)
+		);
+		assert_eq!(settings.seed_index, 3);
+	}
+}
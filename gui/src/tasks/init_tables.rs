@@ -2,10 +2,42 @@ use tokio_rusqlite::Connection;
 
 pub(crate) type InitTablesTaskError = tokio_rusqlite::Error;
 
+pub(crate) const MIGRATIONS: &[&str] = &[
+	include_str!("../../sql/migrations/001-disappearing-messages.sql"),
+	include_str!("../../sql/migrations/002-contact-owners.sql"),
+	include_str!("../../sql/migrations/003-servers.sql"),
+	include_str!("../../sql/migrations/004-server-trust.sql"),
+	include_str!("../../sql/migrations/005-outbox.sql"),
+	include_str!("../../sql/migrations/006-unread.sql"),
+	include_str!("../../sql/migrations/007-ratchet-sessions.sql"),
+	include_str!("../../sql/migrations/008-prekeys.sql"),
+	include_str!("../../sql/migrations/009-verified-messages.sql"),
+	include_str!("../../sql/migrations/010-pending-messages.sql"),
+	include_str!("../../sql/migrations/011-contact-verification.sql"),
+	include_str!("../../sql/migrations/012-sealed-pending.sql"),
+	include_str!("../../sql/migrations/013-groups.sql"),
+	include_str!("../../sql/migrations/014-sender-keys.sql"),
+	include_str!("../../sql/migrations/015-message-owners.sql"),
+	include_str!("../../sql/migrations/016-device-sessions.sql"),
+	include_str!("../../sql/migrations/017-pending-group-messages.sql"),
+	include_str!("../../sql/migrations/018-unique-messages.sql"),
+	include_str!("../../sql/migrations/019-outbox-deliveries.sql"),
+];
+
 pub(crate) async fn run(database: Connection) -> Result<(), InitTablesTaskError> {
 	database
 		.call(|conn| {
 			conn.execute_batch(include_str!("../../sql/tables.sql"))?;
+
+			let version =
+				conn.query_row("PRAGMA user_version", [], |row| row.get::<_, usize>(0))?;
+			for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+				let tx = conn.transaction()?;
+				tx.execute_batch(migration)?;
+				tx.pragma_update(None, "user_version", index + 1)?;
+				tx.commit()?;
+			}
+
 			Ok(())
 		})
 		.await
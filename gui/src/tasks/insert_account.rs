@@ -1,18 +1,26 @@
-use {crate::states::Account, tokio_rusqlite::Connection};
+use {
+	crate::{
+		states::Account,
+		vault::{self, VaultKey},
+	},
+	tokio_rusqlite::Connection,
+};
 
 pub(crate) type InsertAccountTaskError = tokio_rusqlite::Error;
 
 pub(crate) async fn run(
 	database: Connection,
 	account: Account,
+	key: Option<VaultKey>,
 ) -> Result<(), InsertAccountTaskError> {
 	database
 		.call(move |conn| {
 			conn.prepare(include_str!("../../sql/insert-account.sql"))?
 				.execute([
-					bs58::encode(account.secret.as_bytes()).into_string(),
+					vault::encode_secret(key.as_ref(), &account.secret),
 					bs58::encode(account.secret.verifying_key().as_bytes()).into_string(),
 					account.name,
+					account.server.to_string(),
 				])?;
 			Ok(())
 		})
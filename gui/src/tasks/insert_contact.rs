@@ -1,18 +1,21 @@
-use {crate::states::Contact, tokio_rusqlite::Connection};
+use {crate::states::Contact, ed25519_dalek::VerifyingKey, tokio_rusqlite::Connection};
 
 pub(crate) type InsertContactTaskError = tokio_rusqlite::Error;
 
 pub(crate) async fn run(
 	database: Connection,
+	owner: VerifyingKey,
 	contact: Contact,
 ) -> Result<(), InsertContactTaskError> {
 	database
 		.call(move |conn| {
 			conn.prepare(include_str!("../../sql/insert-contact.sql"))?
-				.execute([
+				.execute((
+					bs58::encode(owner.as_bytes()).into_string(),
 					bs58::encode(contact.address.as_bytes()).into_string(),
 					contact.name,
-				])?;
+					contact.timer,
+				))?;
 			Ok(())
 		})
 		.await
@@ -1,32 +1,41 @@
-use {crate::states::Message, tokio_rusqlite::Connection};
+use {crate::states::Message, ed25519_dalek::VerifyingKey};
 
-pub(crate) type InsertMessageTaskError = tokio_rusqlite::Error;
+pub(crate) fn exists(
+	conn: &rusqlite::Connection,
+	owner: &VerifyingKey,
+	hash: &[u8],
+) -> rusqlite::Result<bool> {
+	let res = conn
+		.prepare(include_str!("../../sql/get-message.sql"))?
+		.query_row(
+			(
+				bs58::encode(owner.as_bytes()).into_string(),
+				bs58::encode(hash).into_string(),
+			),
+			|_| Ok(()),
+		);
 
-pub(crate) async fn run(
-	database: Connection,
-	message: Message,
-) -> Result<(), InsertMessageTaskError> {
-	database
-		.call(move |conn| {
-			let res = conn
-				.prepare(include_str!("../../sql/get-message.sql"))?
-				.query_row((bs58::encode(message.hash).into_string(),), |_| Ok(()));
+	if let Err(rusqlite::Error::QueryReturnedNoRows) = res {
+		Ok(false)
+	} else {
+		res.and(Ok(true))
+	}
+}
 
-			if let Err(rusqlite::Error::QueryReturnedNoRows) = res {
-				conn.prepare(include_str!("../../sql/insert-message.sql"))?
-					.execute((
-						bs58::encode(message.hash).into_string(),
-						bs58::encode(message.sender.as_bytes()).into_string(),
-						bs58::encode(message.receiver.as_bytes()).into_string(),
-						bs58::encode(message.data_hash).into_string(),
-						message.timestamp,
-						message.success,
-					))?;
+pub(crate) fn insert(conn: &rusqlite::Connection, message: &Message) -> rusqlite::Result<()> {
+	conn.prepare(include_str!("../../sql/insert-message.sql"))?
+		.execute((
+			bs58::encode(message.hash).into_string(),
+			bs58::encode(message.sender.as_bytes()).into_string(),
+			bs58::encode(message.receiver.as_bytes()).into_string(),
+			bs58::encode(message.data_hash).into_string(),
+			message.timestamp,
+			message.success,
+			message.expires_at,
+			message.verified,
+			message.group.map(|group| bs58::encode(group).into_string()),
+			bs58::encode(message.owner.as_bytes()).into_string(),
+		))?;
 
-				Ok(())
-			} else {
-				Ok(res?)
-			}
-		})
-		.await
+	Ok(())
 }
@@ -1,20 +1,22 @@
 use {
+	super::receive_message::ReceiveMessageTaskError,
 	crate::{
 		client::{ClientError, ProjectTootClient},
-		states::Account,
+		states::{Account, Message, Payload},
 		utils,
 	},
 	project_toot_sdk::{
+		DeviceId,
 		IDeleteMessagesPayload,
 		IGetMessagesPayload,
+		IListBlockedPayload,
 		IMessage,
+		IRegisterDevicePayload,
 		ISigner,
 		Salt,
-		Sha512Data,
 	},
 	sha2::{Digest, Sha512},
 	std::path::PathBuf,
-	tokio::{fs, io::AsyncWriteExt},
 	tokio_rusqlite::Connection,
 	url::ParseError as UrlParseError,
 };
@@ -26,6 +28,7 @@ pub(crate) enum LoadMessagesTaskError {
 	ReqwestError(reqwest::Error),
 	TokioRusqliteError(tokio_rusqlite::Error),
 	ClientError(ClientError),
+	ReceiveMessageTaskError(ReceiveMessageTaskError),
 }
 
 impl From<UrlParseError> for LoadMessagesTaskError {
@@ -58,12 +61,39 @@ impl From<ClientError> for LoadMessagesTaskError {
 	}
 }
 
+impl From<ReceiveMessageTaskError> for LoadMessagesTaskError {
+	fn from(value: ReceiveMessageTaskError) -> Self {
+		Self::ReceiveMessageTaskError(value)
+	}
+}
+
 pub(crate) async fn run(
 	basedir: PathBuf,
 	client: ProjectTootClient,
 	database: Connection,
 	account: Account,
-) -> Result<(), LoadMessagesTaskError> {
+	device: DeviceId,
+) -> Result<Vec<(Message, Option<Payload>)>, LoadMessagesTaskError> {
+	// Server copies are only dropped once every registered device acknowledged them.
+	client
+		.clone()
+		.register_device(
+			utils::signer(&account, b"register-device"),
+			IRegisterDevicePayload { device },
+		)
+		.await?;
+
+	let blocked = client
+		.clone()
+		.list_blocked(
+			utils::signer(&account, b"list-blocked"),
+			IListBlockedPayload {
+				offset: 0,
+				limit: 10_000,
+			},
+		)
+		.await?;
+
 	let mut messages = Vec::<IMessage>::new();
 	loop {
 		let salt = rand::random::<Salt>();
@@ -88,6 +118,7 @@ pub(crate) async fn run(
 					limit: 10_000,
 					with: None,
 					mine: false,
+					device: Some(device),
 				},
 			)
 			.await?;
@@ -99,65 +130,26 @@ pub(crate) async fn run(
 		}
 	}
 
-	let messages_cloned = messages.clone();
-	let found = database
-		.call(move |conn| {
-			let mut ret = Vec::<Sha512Data>::new();
-			let mut stmt = conn.prepare(include_str!("../../sql/get-existing-messages.sql"))?;
-
-			for hash in messages_cloned.iter().map(|x| x.hash) {
-				if stmt
-					.query_row([bs58::encode(hash).into_string()], |_| Ok(()))
-					.is_ok()
-				{
-					ret.push(hash);
-				}
-			}
-
-			Ok(ret)
-		})
-		.await?;
-
-	let messages_cloned = messages.clone();
-	database
-		.call(move |conn| {
-			let mut stmt = conn.prepare(include_str!("../../sql/insert-message.sql"))?;
-
-			for message in messages_cloned
-				.iter()
-				.filter(|x| !found.iter().any(|y| y == &x.hash))
-			{
-				stmt.execute((
-					bs58::encode(message.hash).into_string(),
-					bs58::encode(message.sender.as_bytes()).into_string(),
-					bs58::encode(message.receiver.as_bytes()).into_string(),
-					bs58::encode(message.data_hash).into_string(),
-					message.timestamp,
-					true,
-				))?;
-			}
-
-			Ok(())
-		})
-		.await?;
-
 	let baseurl = client.baseurl().join("cdn/")?;
+	let mut received = Vec::<(Message, Option<Payload>)>::new();
 
 	for msg in messages.iter() {
-		let hash = hex::encode(msg.data_hash);
-		let url = baseurl.join(&(hash.clone() + ".dat"))?;
-		let resp = &mut client.http().get(url).send().await?;
+		let url = baseurl.join(&(hex::encode(msg.data_hash) + ".dat"))?;
+		let resp = client.http().get(url).send().await?;
 
 		if resp.status().is_success() {
-			let dirpath = utils::data_path(basedir.clone(), hex::encode(msg.data_hash));
-			fs::create_dir_all(&dirpath).await?;
-			let file = &mut fs::File::create(dirpath.join(hash + ".dat")).await?;
-
-			while let Some(bytes) = resp.chunk().await? {
-				file.write(&bytes).await?;
-			}
-
-			file.flush().await?;
+			received.extend(
+				super::receive_message::run(
+					basedir.clone(),
+					database.clone(),
+					account.clone(),
+					msg.clone(),
+					resp.bytes().await?.to_vec(),
+					true,
+					blocked.clone(),
+				)
+				.await?,
+			);
 		}
 	}
 
@@ -180,11 +172,12 @@ pub(crate) async fn run(
 					},
 				},
 				IDeleteMessagesPayload {
-					hashes: chunk.into_iter().map(|x| x.hash).collect::<Vec<_>>(),
+					hashes: chunk.iter().map(|x| x.hash).collect::<Vec<_>>(),
+					device: Some(device),
 				},
 			)
 			.await?;
 	}
 
-	Ok(())
+	Ok(received)
 }
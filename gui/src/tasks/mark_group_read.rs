@@ -0,0 +1,27 @@
+use {
+	ed25519_dalek::VerifyingKey,
+	project_toot_sdk::GroupId,
+	std::collections::HashMap,
+	tokio_rusqlite::Connection,
+};
+
+pub(crate) type MarkGroupReadTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	database: Connection,
+	receiver: VerifyingKey,
+	group: GroupId,
+) -> Result<HashMap<VerifyingKey, usize>, MarkGroupReadTaskError> {
+	database
+		.call(move |conn| {
+			conn.prepare(include_str!("../../sql/mark-group-read.sql"))?
+				.execute([
+					bs58::encode(receiver.as_bytes()).into_string(),
+					bs58::encode(group).into_string(),
+				])?;
+			Ok(())
+		})
+		.await?;
+
+	super::get_unread::run(database).await
+}
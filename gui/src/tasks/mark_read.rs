@@ -0,0 +1,22 @@
+use {ed25519_dalek::VerifyingKey, std::collections::HashMap, tokio_rusqlite::Connection};
+
+pub(crate) type MarkReadTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	database: Connection,
+	receiver: VerifyingKey,
+	sender: VerifyingKey,
+) -> Result<HashMap<VerifyingKey, usize>, MarkReadTaskError> {
+	database
+		.call(move |conn| {
+			conn.prepare(include_str!("../../sql/mark-read.sql"))?
+				.execute([
+					bs58::encode(receiver.as_bytes()).into_string(),
+					bs58::encode(sender.as_bytes()).into_string(),
+				])?;
+			Ok(())
+		})
+		.await?;
+
+	super::get_unread::run(database).await
+}
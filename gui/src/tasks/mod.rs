@@ -1,14 +1,46 @@
+pub(crate) mod change_passphrase;
 pub(crate) mod delete_account;
 pub(crate) mod delete_contact;
+pub(crate) mod delete_conversation;
+pub(crate) mod delete_outgoing;
 pub(crate) mod download;
+pub(crate) mod export_profile;
 pub(crate) mod get_accounts;
 pub(crate) mod get_contacts;
+pub(crate) mod get_group_messages;
 pub(crate) mod get_messages;
+pub(crate) mod get_outbox;
+pub(crate) mod get_requests;
+pub(crate) mod get_servers;
+pub(crate) mod get_settings;
+pub(crate) mod get_unread;
+pub(crate) mod import_profile;
 pub(crate) mod init_tables;
 pub(crate) mod insert_account;
 pub(crate) mod insert_contact;
 pub(crate) mod insert_message;
 pub(crate) mod load_messages;
+pub(crate) mod mark_group_read;
+pub(crate) mod mark_read;
+pub(crate) mod purge_messages;
+pub(crate) mod queue_message;
 pub(crate) mod read_data;
+pub(crate) mod receive_link;
+pub(crate) mod receive_message;
+pub(crate) mod receive_pending;
+pub(crate) mod remove_data;
+pub(crate) mod replenish_prekeys;
+pub(crate) mod restore_seed;
+pub(crate) mod send_group_message;
+pub(crate) mod send_link;
+pub(crate) mod send_outgoing;
+pub(crate) mod store_pending;
+pub(crate) mod unlock_vault;
 pub(crate) mod update_account;
+pub(crate) mod update_contact;
+pub(crate) mod update_contact_timer;
+pub(crate) mod update_contact_verified;
+pub(crate) mod update_outgoing;
+pub(crate) mod update_server;
+pub(crate) mod update_settings;
 pub(crate) mod write_data;
@@ -0,0 +1,60 @@
+use {ed25519_dalek::VerifyingKey, std::path::PathBuf, tokio_rusqlite::Connection};
+
+#[derive(Debug)]
+pub(crate) enum PurgeMessagesTaskError {
+	IoError(std::io::Error),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<std::io::Error> for PurgeMessagesTaskError {
+	fn from(value: std::io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for PurgeMessagesTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+pub(crate) async fn run(
+	basedir: PathBuf,
+	database: Connection,
+	now: i64,
+) -> Result<(), PurgeMessagesTaskError> {
+	let expired = database
+		.call(move |conn| {
+			let expired = conn
+				.prepare(include_str!("../../sql/get-expired-messages.sql"))?
+				.query_map([now], |row| {
+					Ok((
+						row.get::<_, String>(0)?,
+						row.get::<_, String>(1)?,
+						row.get::<_, String>(2)?,
+					))
+				})?
+				.flatten()
+				.collect::<Vec<_>>();
+
+			let mut stmt = conn.prepare(include_str!("../../sql/delete-message.sql"))?;
+			for (owner, hash, _) in expired.iter() {
+				stmt.execute([owner, hash])?;
+			}
+
+			Ok(expired)
+		})
+		.await?;
+
+	for (owner, _, data_hash) in expired {
+		let owner = bs58::decode(owner)
+			.into_vec()
+			.ok()
+			.and_then(|x| VerifyingKey::try_from(x.as_slice()).ok());
+		if let (Some(owner), Ok(data_hash)) = (owner, bs58::decode(data_hash).into_vec()) {
+			super::remove_data::local(basedir.clone(), &owner, &data_hash).await?;
+		}
+	}
+
+	Ok(())
+}
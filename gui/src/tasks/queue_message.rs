@@ -0,0 +1,36 @@
+use {
+	crate::{
+		states::{Account, Outgoing, Payload},
+		utils,
+	},
+	tokio_rusqlite::Connection,
+};
+
+pub(crate) type QueueMessageTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	database: Connection,
+	account: Account,
+	mut outgoing: Outgoing,
+	payload: Payload,
+) -> Result<Outgoing, QueueMessageTaskError> {
+	outgoing.local = utils::seal(&account, utils::encode_payload(&payload));
+
+	database
+		.call(move |conn| {
+			conn.prepare(include_str!("../../sql/insert-outgoing.sql"))?
+				.execute((
+					bs58::encode(outgoing.sender.as_bytes()).into_string(),
+					bs58::encode(outgoing.receiver.as_bytes()).into_string(),
+					outgoing.ttl,
+					outgoing.timestamp,
+					outgoing.attempts,
+					outgoing.next_attempt_at,
+					&outgoing.local,
+				))?;
+			outgoing.id = conn.last_insert_rowid();
+
+			Ok(outgoing)
+		})
+		.await
+}
@@ -1,4 +1,9 @@
-use {crate::utils, std::path::PathBuf, tokio::fs};
+use {
+	crate::utils,
+	ed25519_dalek::VerifyingKey,
+	std::{io::ErrorKind, path::PathBuf},
+	tokio::fs,
+};
 
 pub(crate) type ReadDataTaskError = std::io::Error;
 
@@ -7,10 +12,22 @@ pub(crate) async fn run(
 	hash: impl AsRef<[u8]>,
 ) -> Result<Vec<u8>, ReadDataTaskError> {
 	let hash = hex::encode(hash.as_ref());
-	Ok(fs::read(
+	fs::read(
 		utils::data_path(basedir, hash.clone())
 			.join(hash)
 			.with_extension("dat"),
 	)
-	.await?)
+	.await
+}
+
+/// Reads the local copy of a message, older copies were kept under the bare data hash.
+pub(crate) async fn local(
+	basedir: PathBuf,
+	owner: &VerifyingKey,
+	data_hash: &[u8],
+) -> Result<Vec<u8>, ReadDataTaskError> {
+	match run(basedir.clone(), utils::data_key(owner, data_hash)).await {
+		Err(err) if err.kind() == ErrorKind::NotFound => run(basedir, data_hash).await,
+		res => res,
+	}
 }
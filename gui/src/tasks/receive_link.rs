@@ -0,0 +1,102 @@
+use {
+	super::import_profile::ImportProfileTaskError,
+	crate::{
+		client::{ClientError, ProjectTootClient},
+		link,
+		states::Account,
+		utils,
+		vault::VaultKey,
+	},
+	ed25519_dalek::SigningKey,
+	project_toot_sdk::{IDeleteMessagesPayload, IGetMessagesPayload},
+	std::path::PathBuf,
+	tokio_rusqlite::Connection,
+	url::ParseError as UrlParseError,
+};
+
+#[derive(Debug)]
+pub(crate) enum ReceiveLinkTaskError {
+	UrlError(UrlParseError),
+	ReqwestError(reqwest::Error),
+	ClientError(ClientError),
+	ImportProfileTaskError(ImportProfileTaskError),
+}
+
+impl From<UrlParseError> for ReceiveLinkTaskError {
+	fn from(value: UrlParseError) -> Self {
+		Self::UrlError(value)
+	}
+}
+
+impl From<reqwest::Error> for ReceiveLinkTaskError {
+	fn from(value: reqwest::Error) -> Self {
+		Self::ReqwestError(value)
+	}
+}
+
+impl From<ClientError> for ReceiveLinkTaskError {
+	fn from(value: ClientError) -> Self {
+		Self::ClientError(value)
+	}
+}
+
+impl From<ImportProfileTaskError> for ReceiveLinkTaskError {
+	fn from(value: ImportProfileTaskError) -> Self {
+		Self::ImportProfileTaskError(value)
+	}
+}
+
+/// Checks the temporary address for an account bundle, merging it once one arrives.
+pub(crate) async fn run(
+	basedir: PathBuf,
+	client: ProjectTootClient,
+	database: Connection,
+	key: Option<VaultKey>,
+	temporary: SigningKey,
+) -> Result<Option<Vec<Account>>, ReceiveLinkTaskError> {
+	let account = Account {
+		address: temporary.verifying_key(),
+		secret: temporary.clone(),
+		name: String::new(),
+		server: client.baseurl(),
+	};
+	let messages = client
+		.clone()
+		.get_messages(
+			utils::signer(&account, b"get-messages"),
+			IGetMessagesPayload {
+				offset: 0,
+				limit: 16,
+				with: None,
+				mine: false,
+				device: None,
+			},
+		)
+		.await?;
+
+	let baseurl = client.baseurl().join("cdn/")?;
+	for message in messages {
+		let url = baseurl.join(&(hex::encode(message.data_hash) + ".dat"))?;
+		let data = client.http().get(url).send().await?.bytes().await?;
+		let Some(profile) = link::open(&temporary, &message, &data) else {
+			continue;
+		};
+
+		client
+			.clone()
+			.delete_messages(
+				utils::signer(&account, b"delete-messages"),
+				IDeleteMessagesPayload {
+					hashes: vec![message.hash],
+					device: None,
+				},
+			)
+			.await?;
+
+		return Ok(Some(
+			super::import_profile::merge(basedir, database, key, profile).await?,
+		))
+	}
+
+	Ok(None)
+}
@@ -0,0 +1,391 @@
+use {
+	crate::{
+		envelope::{self, DecryptError, Envelope, Scheme},
+		group,
+		ratchet,
+		sealed,
+		states::{Account, Message, Payload},
+		utils,
+	},
+	ed25519_dalek::VerifyingKey,
+	project_toot_sdk::{GroupId, IMessage},
+	std::path::PathBuf,
+	tokio_rusqlite::Connection,
+};
+
+#[derive(Debug)]
+pub(crate) enum ReceiveMessageTaskError {
+	IoError(std::io::Error),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<std::io::Error> for ReceiveMessageTaskError {
+	fn from(value: std::io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for ReceiveMessageTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+fn hold(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	message: &IMessage,
+	group: &GroupId,
+	data: &[u8],
+	success: bool,
+) -> rusqlite::Result<()> {
+	conn.prepare(include_str!("../../sql/insert-pending-group-message.sql"))?
+		.execute((
+			bs58::encode(account.address.as_bytes()).into_string(),
+			bs58::encode(message.hash).into_string(),
+			bs58::encode(message.sender.as_bytes()).into_string(),
+			bs58::encode(message.receiver.as_bytes()).into_string(),
+			bs58::encode(group).into_string(),
+			bs58::encode(message.data_hash).into_string(),
+			message.timestamp,
+			success,
+			data,
+		))?;
+
+	Ok(())
+}
+
+/// Takes the group messages that waited for a sender key of this sender and receives them again.
+fn release(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	sender: &VerifyingKey,
+	group: &GroupId,
+	received: &mut Vec<(Message, Option<Payload>, Vec<u8>)>,
+) -> rusqlite::Result<()> {
+	let params = [
+		bs58::encode(account.address.as_bytes()).into_string(),
+		bs58::encode(group).into_string(),
+		bs58::encode(sender.as_bytes()).into_string(),
+	];
+
+	let pending = conn
+		.prepare(include_str!("../../sql/get-pending-group-messages.sql"))?
+		.query_map(params.clone(), |row| {
+			Ok((
+				row.get::<_, String>(0)?,
+				row.get::<_, String>(1)?,
+				row.get::<_, String>(2)?,
+				row.get::<_, i64>(3)?,
+				row.get::<_, bool>(4)?,
+				row.get::<_, Vec<u8>>(5)?,
+			))
+		})?
+		.collect::<Result<Vec<_>, _>>()?;
+	conn.prepare(include_str!("../../sql/delete-pending-group-messages.sql"))?
+		.execute(params)?;
+
+	for (hash, receiver, data_hash, timestamp, success, data) in pending {
+		let (Some(hash), Some(receiver), Some(data_hash)) = (
+			bs58::decode(hash)
+				.into_vec()
+				.ok()
+				.and_then(|x| x.try_into().ok()),
+			bs58::decode(receiver)
+				.into_vec()
+				.ok()
+				.and_then(|x| VerifyingKey::try_from(x.as_slice()).ok()),
+			bs58::decode(data_hash)
+				.into_vec()
+				.ok()
+				.and_then(|x| x.try_into().ok()),
+		) else {
+			continue
+		};
+
+		accept(
+			conn,
+			account,
+			IMessage {
+				hash,
+				sender: *sender,
+				receiver,
+				data_hash,
+				timestamp,
+				sealed: false,
+				group: Some(*group),
+				device: None,
+			},
+			data,
+			success,
+			received,
+		)?;
+	}
+
+	Ok(())
+}
+
+fn accept(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	message: IMessage,
+	data: Vec<u8>,
+	success: bool,
+	received: &mut Vec<(Message, Option<Payload>, Vec<u8>)>,
+) -> rusqlite::Result<()> {
+	if super::insert_message::exists(conn, &account.address, &message.hash)? {
+		return Ok(())
+	}
+
+	let opposite = if account.address == message.sender {
+		message.receiver
+	} else {
+		message.sender
+	};
+
+	// Our own group messages come back sealed locally, the chain has already moved past them.
+	let group = message.group.filter(|_| account.address != message.sender);
+	let (payload, data, verified) = if let Some(group) = group {
+		match envelope::decode(&data) {
+			Ok(envelope) => {
+				let verified = envelope.verify(
+					&message.sender,
+					&group::binding(&message.sender, &group, message.timestamp),
+				);
+
+				let plaintext = match group::decrypt(
+					conn,
+					account,
+					&message.sender,
+					&group,
+					message.timestamp,
+					&envelope,
+				)? {
+					// The sender key may still be on its way over the pairwise session.
+					Err(DecryptError::NoSession) => {
+						hold(conn, account, &message, &group, &data, success)?;
+						return Ok(())
+					},
+					plaintext => plaintext,
+				};
+
+				match plaintext.and_then(utils::decode_payload) {
+					Ok(payload) => {
+						let data = utils::seal(account, utils::encode_payload(&payload));
+						(Ok(payload), data, verified)
+					},
+					Err(err) => (Err(err), data, verified),
+				}
+			},
+			Err(err) => (Err(err), data, false),
+		}
+	} else {
+		match envelope::decode(&data) {
+			Ok(
+				envelope @ Envelope {
+					scheme: Scheme::Ratchet(_),
+					..
+				},
+			) => {
+				let verified = envelope.verify(
+					&message.sender,
+					&envelope::binding(&message.sender, &message.receiver, message.timestamp),
+				);
+
+				match ratchet::decrypt(conn, account, &opposite, message.timestamp, &envelope)?
+					.and_then(utils::decode_payload)
+				{
+					Ok(payload) => {
+						let data = utils::seal(account, utils::encode_payload(&payload));
+						(Ok(payload), data, verified)
+					},
+					Err(err) => (Err(err), data, verified),
+				}
+			},
+			_ => (
+				utils::decrypt(account, &opposite, &data),
+				data,
+				account.address == message.sender,
+			),
+		}
+	};
+
+	// Sender keys arrive over the pairwise sessions but belong to the group conversation.
+	let group = match &payload {
+		Ok(Payload::SenderKey {
+			group,
+			id,
+			chain,
+			iteration,
+		}) => {
+			if account.address != message.sender && message.group.is_none() {
+				group::store(
+					conn,
+					account,
+					&message.sender,
+					group,
+					*id,
+					*chain,
+					*iteration,
+				)?;
+				release(conn, account, &message.sender, group, received)?;
+			}
+			Some(*group)
+		},
+		_ => message.group,
+	};
+
+	let msg = Message {
+		hash: message.hash,
+		sender: message.sender,
+		receiver: message.receiver,
+		data_hash: message.data_hash,
+		timestamp: message.timestamp,
+		success,
+		content: payload.as_ref().map(utils::content).map_err(|err| *err),
+		expires_at: match &payload {
+			Ok(Payload::Text { timer, .. }) => utils::expires_at(message.timestamp, *timer),
+			_ => None,
+		},
+		verified,
+		group,
+		owner: account.address,
+	};
+	super::insert_message::insert(conn, &msg)?;
+
+	received.push((msg, payload.ok(), data));
+
+	Ok(())
+}
+
+/// Group messages whose sender key hasn't arrived yet are held back instead, and come out along
+/// with the message that carries the key.
+pub(crate) async fn run(
+	basedir: PathBuf,
+	database: Connection,
+	account: Account,
+	message: IMessage,
+	data: Vec<u8>,
+	success: bool,
+	blocked: Vec<VerifyingKey>,
+) -> Result<Vec<(Message, Option<Payload>)>, ReceiveMessageTaskError> {
+	let (message, data) = if message.sealed {
+		match sealed::open(&account, message.timestamp, &data) {
+			Ok((sender, inner)) => (IMessage { sender, ..message }, inner),
+			Err(_) => (message, data),
+		}
+	} else {
+		(message, data)
+	};
+	// The server drops what blocked senders send directly but only knows the receiver of sealed
+	// messages, so those are dropped here. Neither way tells the sender.
+	if blocked.contains(&message.sender) {
+		return Ok(Vec::new())
+	}
+
+	let received = database
+		.call(move |conn| {
+			let mut received = Vec::new();
+			accept(conn, &account, message, data, success, &mut received)?;
+
+			Ok(received)
+		})
+		.await?;
+
+	let mut ret = Vec::<(Message, Option<Payload>)>::new();
+	for (msg, payload, data) in received {
+		super::write_data::run(
+			basedir.clone(),
+			utils::data_key(&msg.owner, &msg.data_hash),
+			data,
+		)
+		.await?;
+		ret.push((msg, payload));
+	}
+
+	Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, ed25519_dalek::SigningKey};
+
+	fn account() -> Account {
+		let secret = SigningKey::from_bytes(&rand::random());
+		Account {
+			address: secret.verifying_key(),
+			secret,
+			name: String::new(),
+			server: "https://localhost".parse().unwrap(),
+		}
+	}
+
+	fn message(sender: &VerifyingKey, receiver: &VerifyingKey, sealed: bool, seq: u8) -> IMessage {
+		IMessage {
+			hash: [seq; 64],
+			sender: *sender,
+			receiver: *receiver,
+			data_hash: [seq; 64],
+			timestamp: 0,
+			sealed,
+			group: None,
+			device: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn blocked_senders() {
+		let basedir = std::env::temp_dir().join(hex::encode(rand::random::<[u8; 8]>()));
+		let database = Connection::open_in_memory().await.unwrap();
+		crate::tasks::init_tables::run(database.clone())
+			.await
+			.unwrap();
+		let (receiver, blocked, other) = (account(), account(), account());
+
+		let receive = |message: IMessage, data: Vec<u8>| {
+			run(
+				basedir.clone(),
+				database.clone(),
+				receiver.clone(),
+				message,
+				data,
+				true,
+				vec![blocked.address],
+			)
+		};
+
+		// Direct messages name their sender, sealed ones only the receiver.
+		assert!(receive(
+			message(&blocked.address, &receiver.address, false, 1),
+			b"hello".to_vec(),
+		)
+		.await
+		.unwrap()
+		.is_empty());
+		assert!(receive(
+			message(&receiver.address, &receiver.address, true, 2),
+			sealed::seal(&blocked, &receiver.address, 0, b"hello"),
+		)
+		.await
+		.unwrap()
+		.is_empty());
+
+		let received = receive(
+			message(&other.address, &receiver.address, false, 3),
+			b"hello".to_vec(),
+		)
+		.await
+		.unwrap();
+		assert_eq!(received.len(), 1);
+		let received = receive(
+			message(&receiver.address, &receiver.address, true, 4),
+			sealed::seal(&other, &receiver.address, 0, b"hello"),
+		)
+		.await
+		.unwrap();
+		assert_eq!(received.len(), 1);
+		assert_eq!(received[0].0.sender, other.address);
+
+		let _ = std::fs::remove_dir_all(basedir);
+	}
+}
@@ -0,0 +1,89 @@
+use {
+	super::receive_message::ReceiveMessageTaskError,
+	crate::states::{Account, Message, Payload},
+	ed25519_dalek::VerifyingKey,
+	project_toot_sdk::IMessage,
+	std::path::PathBuf,
+	tokio_rusqlite::Connection,
+};
+
+fn decode<const N: usize>(value: String) -> rusqlite::Result<[u8; N]> {
+	bs58::decode(value)
+		.into_vec()
+		.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))?
+		.try_into()
+		.or(Err(rusqlite::Error::InvalidQuery))
+}
+
+fn address(value: String) -> rusqlite::Result<VerifyingKey> {
+	VerifyingKey::from_bytes(&decode(value)?)
+		.map_err(|err| rusqlite::Error::UserFunctionError(Box::new(err)))
+}
+
+pub(crate) async fn run(
+	basedir: PathBuf,
+	database: Connection,
+	accounts: Vec<Account>,
+) -> Result<Vec<(Message, Option<Payload>)>, ReceiveMessageTaskError> {
+	let pending = database
+		.call(|conn| {
+			Ok(conn
+				.prepare(include_str!("../../sql/get-pending.sql"))?
+				.query_map([], |row| {
+					Ok((
+						IMessage {
+							hash: decode(row.get(0)?)?,
+							sender: address(row.get(1)?)?,
+							receiver: address(row.get(2)?)?,
+							data_hash: decode(row.get(3)?)?,
+							timestamp: row.get(4)?,
+							sealed: row.get(6)?,
+							group: row.get::<_, Option<String>>(7)?.map(decode).transpose()?,
+							device: None,
+						},
+						row.get::<_, bool>(5)?,
+					))
+				})?
+				.flatten()
+				.collect::<Vec<_>>())
+		})
+		.await?;
+
+	let mut received = Vec::<(Message, Option<Payload>)>::new();
+
+	for (message, success) in pending {
+		let hash = bs58::encode(message.hash).into_string();
+		let data_hash = message.data_hash;
+
+		// Pending messages come straight from the server, which only delivers to the receiver.
+		if let Some(account) = accounts.iter().find(|x| x.address == message.receiver) {
+			// Sealed ones stay on the server until the account syncs, which also checks its blocks.
+			if !message.sealed {
+				let data = super::read_data::run(basedir.clone(), data_hash).await?;
+				received.extend(
+					super::receive_message::run(
+						basedir.clone(),
+						database.clone(),
+						account.clone(),
+						message,
+						data,
+						success,
+						Vec::new(),
+					)
+					.await?,
+				);
+			}
+			super::remove_data::run(basedir.clone(), data_hash).await?;
+		}
+
+		database
+			.call(move |conn| {
+				conn.execute(include_str!("../../sql/delete-pending.sql"), [hash])?;
+
+				Ok(())
+			})
+			.await?;
+	}
+
+	Ok(received)
+}
@@ -0,0 +1,38 @@
+use {
+	crate::utils,
+	ed25519_dalek::VerifyingKey,
+	std::{io::ErrorKind, path::PathBuf},
+	tokio::fs,
+};
+
+pub(crate) type RemoveDataTaskError = std::io::Error;
+
+pub(crate) async fn run(
+	basedir: PathBuf,
+	hash: impl AsRef<[u8]>,
+) -> Result<(), RemoveDataTaskError> {
+	let hash = hex::encode(hash.as_ref());
+	if let Err(err) = fs::remove_file(
+		utils::data_path(basedir, hash.clone())
+			.join(hash)
+			.with_extension("dat"),
+	)
+	.await
+	{
+		if err.kind() != ErrorKind::NotFound {
+			return Err(err)
+		}
+	}
+
+	Ok(())
+}
+
+/// Removes the local copy of a message along with an older copy under the bare data hash.
+pub(crate) async fn local(
+	basedir: PathBuf,
+	owner: &VerifyingKey,
+	data_hash: &[u8],
+) -> Result<(), RemoveDataTaskError> {
+	run(basedir.clone(), utils::data_key(owner, data_hash)).await?;
+	run(basedir, data_hash).await
+}
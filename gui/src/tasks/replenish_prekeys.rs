@@ -0,0 +1,146 @@
+use {
+	crate::{
+		client::{ClientError, ProjectTootClient},
+		ratchet,
+		states::Account,
+		utils,
+	},
+	project_toot_sdk::{
+		DeviceId,
+		ICountOneTimePrekeysPayload,
+		IPublishOneTimePrekeysPayload,
+		IPublishSignedPrekeyPayload,
+		X25519Data,
+	},
+	tokio_rusqlite::Connection,
+};
+
+const SIGNED_PREKEY_LIFETIME: i64 = 7 * 24 * 60 * 60 * 1_000_000;
+const ONE_TIME_PREKEYS_MINIMUM: u32 = 25;
+const ONE_TIME_PREKEYS_BATCH: u32 = 100;
+
+#[derive(Debug)]
+pub(crate) enum ReplenishPrekeysTaskError {
+	ClientError(ClientError),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<ClientError> for ReplenishPrekeysTaskError {
+	fn from(value: ClientError) -> Self {
+		Self::ClientError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for ReplenishPrekeysTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+fn insert_prekeys(
+	conn: &rusqlite::Connection,
+	account: &Account,
+	prekeys: &[(X25519Data, X25519Data)],
+	signed: bool,
+	now: i64,
+) -> rusqlite::Result<()> {
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+	let tx = conn.unchecked_transaction()?;
+	for (secret, public) in prekeys {
+		let secret = utils::seal_state(account, &ratchet::prekey_binding(public), secret);
+		tx.execute(
+			include_str!("../../sql/insert-prekey.sql"),
+			(&owner, public, secret, signed, now),
+		)?;
+	}
+	tx.commit()
+}
+
+pub(crate) async fn run(
+	client: ProjectTootClient,
+	database: Connection,
+	account: Account,
+	device: DeviceId,
+) -> Result<(), ReplenishPrekeysTaskError> {
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+	let now = chrono::Utc::now().timestamp_micros();
+
+	let signed_prekey = database
+		.call({
+			let account = account.clone();
+			move |conn| {
+				let res = conn
+					.prepare(include_str!("../../sql/get-signed-prekey.sql"))?
+					.query_row([&owner], |row| {
+						Ok((row.get::<_, X25519Data>(0)?, row.get::<_, i64>(1)?))
+					});
+
+				let public = match res {
+					Ok((public, created_at)) if created_at + SIGNED_PREKEY_LIFETIME > now => public,
+					Ok(_) | Err(rusqlite::Error::QueryReturnedNoRows) => {
+						let (secret, public) = ratchet::keypair();
+						insert_prekeys(conn, &account, &[(secret, public)], true, now)?;
+						public
+					},
+					Err(err) => return Err(err.into()),
+				};
+
+				conn.execute(
+					include_str!("../../sql/trim-signed-prekeys.sql"),
+					(&owner, now - 2 * SIGNED_PREKEY_LIFETIME),
+				)?;
+
+				Ok(public)
+			}
+		})
+		.await?;
+
+	client
+		.clone()
+		.publish_signed_prekey(
+			utils::signer(&account, b"publish-signed-prekey"),
+			IPublishSignedPrekeyPayload {
+				prekey: signed_prekey,
+				signature: ratchet::sign_prekey(&account, &signed_prekey),
+				device,
+			},
+		)
+		.await?;
+
+	let count = client
+		.clone()
+		.count_one_time_prekeys(
+			utils::signer(&account, b"count-one-time-prekeys"),
+			ICountOneTimePrekeysPayload { device },
+		)
+		.await?;
+	if count >= ONE_TIME_PREKEYS_MINIMUM {
+		return Ok(())
+	}
+
+	let prekeys = (0..ONE_TIME_PREKEYS_BATCH)
+		.map(|_| ratchet::keypair())
+		.collect::<Vec<_>>();
+	let public = prekeys
+		.iter()
+		.map(|(_, public)| *public)
+		.collect::<Vec<_>>();
+	database
+		.call({
+			let account = account.clone();
+			move |conn| Ok(insert_prekeys(conn, &account, &prekeys, false, now)?)
+		})
+		.await?;
+
+	client
+		.publish_one_time_prekeys(
+			utils::signer(&account, b"publish-one-time-prekeys"),
+			IPublishOneTimePrekeysPayload {
+				prekeys: public,
+				device,
+			},
+		)
+		.await?;
+
+	Ok(())
+}
@@ -0,0 +1,119 @@
+use {
+	crate::{
+		client::{ClientError, ProjectTootClient},
+		hd::{self, Seed},
+		states::Account,
+		utils,
+		vault::{self, VaultKey},
+	},
+	ed25519_dalek::VerifyingKey,
+	project_toot_sdk::{IGetMessagesPayload, IListDevicesPayload},
+	tokio_rusqlite::Connection,
+};
+
+/// Consecutive unused identities after which probing stops.
+const GAP_LIMIT: u32 = 5;
+
+#[derive(Debug)]
+pub(crate) enum RestoreSeedTaskError {
+	ClientError(ClientError),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<ClientError> for RestoreSeedTaskError {
+	fn from(value: ClientError) -> Self {
+		Self::ClientError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for RestoreSeedTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+async fn used(client: &ProjectTootClient, account: &Account) -> Result<bool, ClientError> {
+	let pending = client
+		.clone()
+		.get_messages(
+			utils::signer(account, b"get-messages"),
+			IGetMessagesPayload {
+				offset: 0,
+				limit: 1,
+				with: None,
+				mine: false,
+				device: None,
+			},
+		)
+		.await?;
+	if !pending.is_empty() {
+		return Ok(true)
+	}
+
+	let payload = IListDevicesPayload {
+		address: account.address,
+	};
+	Ok(!client
+		.clone()
+		.list_devices(
+			utils::caller(account, b"list-devices", &payload, false),
+			payload,
+		)
+		.await?
+		.is_empty())
+}
+
+/// Returns the rediscovered accounts that were not known yet and the next unused index.
+pub(crate) async fn run(
+	client: ProjectTootClient,
+	database: Connection,
+	key: Option<VaultKey>,
+	seed: Seed,
+	known: Vec<VerifyingKey>,
+) -> Result<(Vec<Account>, u32), RestoreSeedTaskError> {
+	let mut accounts = Vec::new();
+	let mut next = 0;
+	let mut index = 0;
+
+	while index < next + GAP_LIMIT {
+		let secret = hd::derive(&seed, index);
+		let address = secret.verifying_key();
+		let account = Account {
+			secret,
+			address,
+			name: format!("Account {}", utils::small_public_key(&address)),
+			server: client.baseurl(),
+		};
+
+		if known.contains(&address) || used(&client, &account).await? {
+			next = index + 1;
+			if !known.contains(&address) {
+				accounts.push(account);
+			}
+		}
+		index += 1;
+	}
+
+	let inserted = accounts.clone();
+	database
+		.call(move |conn| {
+			let tx = conn.transaction()?;
+			for account in inserted {
+				tx.execute(
+					include_str!("../../sql/insert-account.sql"),
+					[
+						vault::encode_secret(key.as_ref(), &account.secret),
+						bs58::encode(account.address.as_bytes()).into_string(),
+						account.name,
+						account.server.to_string(),
+					],
+				)?;
+			}
+			tx.commit()?;
+
+			Ok(())
+		})
+		.await?;
+
+	Ok((accounts, next))
+}
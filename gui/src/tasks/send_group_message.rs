@@ -0,0 +1,157 @@
+use {
+	super::send_outgoing::SendOutgoingTaskError,
+	crate::{
+		client::{ClientError, ProjectTootClient},
+		envelope::Padding,
+		group,
+		states::{Account, Outgoing, Payload},
+		utils,
+	},
+	project_toot_sdk::{
+		irpcerror,
+		DeviceId,
+		GroupId,
+		IMessage,
+		ISendGroupMessagePayload,
+		Sha512Data,
+	},
+	sha2::{Digest, Sha512},
+	tokio_rusqlite::Connection,
+};
+
+#[derive(Debug)]
+pub(crate) enum SendGroupMessageTaskError {
+	ClientError(ClientError),
+	SendOutgoingTaskError(SendOutgoingTaskError),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<ClientError> for SendGroupMessageTaskError {
+	fn from(value: ClientError) -> Self {
+		Self::ClientError(value)
+	}
+}
+
+impl From<SendOutgoingTaskError> for SendGroupMessageTaskError {
+	fn from(value: SendOutgoingTaskError) -> Self {
+		Self::SendOutgoingTaskError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for SendGroupMessageTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+/// Hands a new sender key to the other members first when the membership changed, then uploads
+/// the message once and the server copies it into the inbox of every other member. Nothing is
+/// sent, and the new key isn't kept, unless every member got the key.
+pub(crate) async fn run(
+	client: ProjectTootClient,
+	database: Connection,
+	account: Account,
+	group: GroupId,
+	payload: Payload,
+	own: DeviceId,
+	padding: Padding,
+	sealed_sender: bool,
+) -> Result<(IMessage, Vec<u8>), SendGroupMessageTaskError> {
+	let Some(info) = client
+		.clone()
+		.list_groups(utils::signer(&account, b"list-groups"))
+		.await?
+		.into_iter()
+		.find(|x| x.id == group)
+	else {
+		return Err(ClientError::RPCError(irpcerror::NOT_GROUP_MEMBER).into())
+	};
+
+	let rotated = {
+		let account = account.clone();
+		let info = info.clone();
+		database
+			.call(move |conn| Ok(group::rotate(conn, &account, &info)?))
+			.await?
+	};
+
+	if let Some(distribution) = rotated {
+		for member in info.members.iter().filter(|x| x.address != account.address) {
+			let timestamp = chrono::Utc::now().timestamp_micros();
+			super::send_outgoing::run(
+				client.clone(),
+				database.clone(),
+				account.clone(),
+				Outgoing {
+					id: 0,
+					sender: account.address,
+					receiver: member.address,
+					local: utils::seal(&account, utils::encode_payload(&distribution)),
+					ttl: None,
+					timestamp,
+					attempts: 0,
+					next_attempt_at: timestamp,
+					content: utils::content(&distribution),
+					sending: false,
+				},
+				own,
+				padding,
+				sealed_sender,
+			)
+			.await?;
+		}
+
+		let account = account.clone();
+		database
+			.call(move |conn| Ok(group::commit(conn, &account, &info, &distribution)?))
+			.await?;
+	}
+
+	let timestamp = chrono::Utc::now().timestamp_micros();
+	let data = {
+		let account = account.clone();
+		let buf = utils::encode_payload(&payload);
+		database
+			.call(move |conn| {
+				Ok(group::encrypt(
+					conn, &account, &group, timestamp, padding, &buf,
+				)?)
+			})
+			.await?
+	};
+
+	let data_hash: Sha512Data = {
+		let mut hasher: Sha512 = Digest::new();
+		hasher.update(&data);
+		hasher.finalize().into()
+	};
+	let message = ISendGroupMessagePayload {
+		group,
+		data,
+		ttl: None,
+	};
+	let signer = utils::payload_signer_at(&account, b"send-group-message", &message, timestamp);
+	let hash: Sha512Data = {
+		let mut hasher: Sha512 = Digest::new();
+		hasher.update(b"send-group-message");
+		hasher.update(signer.salt);
+		hasher.update(signer.timestamp.to_le_bytes());
+		hasher.finalize().into()
+	};
+
+	client.send_group_message(signer, message).await?;
+
+	Ok((
+		IMessage {
+			hash,
+			sender: account.address,
+			receiver: account.address,
+			data_hash,
+			timestamp,
+			sealed: false,
+			group: Some(group),
+			device: None,
+		},
+		utils::seal(&account, utils::encode_payload(&payload)),
+	))
+}
@@ -0,0 +1,76 @@
+use {
+	crate::{
+		client::{ClientError, ProjectTootClient},
+		link,
+		profile::{Profile, ProfileAccount, ProfileContact},
+		states::Account,
+		utils,
+	},
+	ed25519_dalek::VerifyingKey,
+	project_toot_sdk::ISendMessagePayload,
+	tokio_rusqlite::Connection,
+};
+
+#[derive(Debug)]
+pub(crate) enum SendLinkTaskError {
+	ClientError(ClientError),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<ClientError> for SendLinkTaskError {
+	fn from(value: ClientError) -> Self {
+		Self::ClientError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for SendLinkTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+/// Hands the account key and contacts to the temporary address of a new device.
+pub(crate) async fn run(
+	client: ProjectTootClient,
+	database: Connection,
+	account: Account,
+	temporary: VerifyingKey,
+) -> Result<(), SendLinkTaskError> {
+	let owner = bs58::encode(account.address.as_bytes()).into_string();
+	let contacts = super::get_contacts::run(database, account.address)
+		.await?
+		.into_iter()
+		.map(|contact| ProfileContact {
+			owner: owner.clone(),
+			address: bs58::encode(contact.address.as_bytes()).into_string(),
+			name: contact.name,
+			timer: contact.timer,
+			verified: contact.verified,
+		})
+		.collect::<Vec<_>>();
+	let profile = Profile {
+		accounts: vec![ProfileAccount {
+			secret: account.secret.to_bytes(),
+			name: account.name.clone(),
+			server: account.server.to_string(),
+		}],
+		contacts,
+		..Default::default()
+	};
+
+	let signer = utils::signer(&account, b"send-message");
+	let data = link::seal(&account, &temporary, signer.timestamp, &profile);
+	client
+		.send_message(
+			signer,
+			ISendMessagePayload {
+				receiver: temporary,
+				data,
+				ttl: Some(link::LINK_TTL),
+				device: None,
+			},
+		)
+		.await?;
+
+	Ok(())
+}
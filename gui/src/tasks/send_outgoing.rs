@@ -0,0 +1,260 @@
+use {
+	crate::{
+		client::{ClientError, ProjectTootClient},
+		envelope::{DecryptError, Padding},
+		ratchet,
+		sealed,
+		states::{Account, Outgoing},
+		utils,
+	},
+	project_toot_sdk::{
+		DeviceId,
+		IFetchPrekeyBundlePayload,
+		IListDevicesPayload,
+		IMessage,
+		ISendMessagePayload,
+		ISendSealedMessagePayload,
+		Sha512Data,
+	},
+	sha2::{Digest, Sha512},
+	tokio_rusqlite::Connection,
+};
+
+#[derive(Debug)]
+pub(crate) enum SendOutgoingTaskError {
+	ClientError(ClientError),
+	DecryptError(DecryptError),
+	JoinError(tokio::task::JoinError),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<ClientError> for SendOutgoingTaskError {
+	fn from(value: ClientError) -> Self {
+		Self::ClientError(value)
+	}
+}
+
+impl From<DecryptError> for SendOutgoingTaskError {
+	fn from(value: DecryptError) -> Self {
+		Self::DecryptError(value)
+	}
+}
+
+impl From<tokio::task::JoinError> for SendOutgoingTaskError {
+	fn from(value: tokio::task::JoinError) -> Self {
+		Self::JoinError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for SendOutgoingTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+fn load_deliveries(
+	conn: &rusqlite::Connection,
+	outgoing: &Outgoing,
+) -> rusqlite::Result<Vec<IMessage>> {
+	conn.prepare(include_str!("../../sql/get-outbox-deliveries.sql"))?
+		.query_map([outgoing.id], |row| {
+			Ok(IMessage {
+				hash: row.get(1)?,
+				sender: outgoing.sender,
+				receiver: outgoing.receiver,
+				timestamp: row.get(3)?,
+				data_hash: row.get(2)?,
+				sealed: false,
+				group: None,
+				device: row.get(0)?,
+			})
+		})?
+		.collect()
+}
+
+/// Encrypts the message on its own session with every device of the receiver, receivers without
+/// devices are sent a single copy. The first copy stands in for the message locally.
+///
+/// Each copy is recorded once the server took it, so a retry skips the devices that already got
+/// the message. Outgoing messages with id 0 never went through the outbox and are not recorded.
+pub(crate) async fn run(
+	client: ProjectTootClient,
+	database: Connection,
+	account: Account,
+	outgoing: Outgoing,
+	own: DeviceId,
+	padding: Padding,
+	sealed_sender: bool,
+) -> Result<IMessage, SendOutgoingTaskError> {
+	let receiver = outgoing.receiver;
+	let buf = utils::encode_payload(&utils::decrypt(&account, &receiver, &outgoing.local)?);
+	// Lookups stay anonymous with sealed sender, or the server could still pair both sides.
+	let payload = IListDevicesPayload { address: receiver };
+	let mut devices = client
+		.clone()
+		.list_devices(
+			utils::caller(&account, b"list-devices", &payload, sealed_sender),
+			payload,
+		)
+		.await?;
+	if devices.is_empty() {
+		devices.push(None);
+	}
+
+	let mut sent = {
+		let outgoing = outgoing.clone();
+		database
+			.call(move |conn| Ok(load_deliveries(conn, &outgoing)?))
+			.await?
+	};
+	for device in devices {
+		if sent.iter().any(|x| x.device == device) {
+			continue
+		}
+
+		let established = {
+			let account = account.clone();
+			database
+				.call(move |conn| {
+					Ok(ratchet::established(
+						conn,
+						&account,
+						&receiver,
+						device.as_ref(),
+					)?)
+				})
+				.await?
+		};
+		if !established {
+			let payload = IFetchPrekeyBundlePayload {
+				address: receiver,
+				device,
+			};
+			let bundle = client
+				.clone()
+				.fetch_prekey_bundle(
+					utils::caller(&account, b"fetch-prekey-bundle", &payload, sealed_sender),
+					payload,
+				)
+				.await?;
+			let account = account.clone();
+			database
+				.call(move |conn| {
+					Ok(ratchet::initiate(
+						conn,
+						&account,
+						&receiver,
+						device.as_ref(),
+						Some(own),
+						bundle.as_ref(),
+					)?)
+				})
+				.await?;
+		}
+
+		let signer = (!sealed_sender).then(|| utils::signer(&account, b"send-message"));
+		let timestamp = signer
+			.as_ref()
+			.map_or_else(|| chrono::Utc::now().timestamp_micros(), |x| x.timestamp);
+		let data = {
+			let account = account.clone();
+			let buf = buf.clone();
+			database
+				.call(move |conn| {
+					Ok(ratchet::encrypt(
+						conn,
+						&account,
+						&receiver,
+						device.as_ref(),
+						timestamp,
+						padding,
+						&buf,
+					)?)
+				})
+				.await?
+		};
+		let data = if sealed_sender {
+			sealed::seal(&account, &receiver, timestamp, &data)
+		} else {
+			data
+		};
+
+		let data_hash: Sha512Data = {
+			let mut hasher: Sha512 = Digest::new();
+			hasher.update(&data);
+			hasher.finalize().into()
+		};
+
+		let hash: Sha512Data = if let Some(signer) = signer {
+			let hash = {
+				let mut hasher: Sha512 = Digest::new();
+				hasher.update(b"send-message");
+				hasher.update(signer.salt);
+				hasher.update(signer.timestamp.to_le_bytes());
+				hasher.finalize().into()
+			};
+
+			client
+				.clone()
+				.send_message(
+					signer,
+					ISendMessagePayload {
+						receiver,
+						data,
+						ttl: outgoing.ttl,
+						device,
+					},
+				)
+				.await?;
+
+			hash
+		} else {
+			let nonce = tokio::task::spawn_blocking(move || {
+				sealed::work(&receiver, &data_hash, timestamp, device.as_ref())
+			})
+			.await?;
+
+			client
+				.clone()
+				.send_sealed_message(ISendSealedMessagePayload {
+					receiver,
+					data,
+					ttl: outgoing.ttl,
+					timestamp,
+					nonce,
+					device,
+				})
+				.await?;
+
+			sealed::digest(&receiver, &data_hash, timestamp, nonce, device.as_ref())
+		};
+
+		if outgoing.id != 0 {
+			let id = outgoing.id;
+			database
+				.call(move |conn| {
+					conn.execute(
+						include_str!("../../sql/insert-outbox-delivery.sql"),
+						(id, device, hash, data_hash, timestamp),
+					)?;
+					Ok(())
+				})
+				.await?;
+		}
+
+		sent.push(IMessage {
+			hash,
+			sender: outgoing.sender,
+			receiver: outgoing.receiver,
+			timestamp,
+			data_hash,
+			sealed: false,
+			group: None,
+			device,
+		});
+	}
+
+	super::delete_outgoing::run(database, outgoing.id).await?;
+
+	Ok(sent.swap_remove(0))
+}
@@ -0,0 +1,49 @@
+use {project_toot_sdk::IMessage, std::path::PathBuf, tokio_rusqlite::Connection};
+
+#[derive(Debug)]
+pub(crate) enum StorePendingTaskError {
+	IoError(std::io::Error),
+	TokioRusqliteError(tokio_rusqlite::Error),
+}
+
+impl From<std::io::Error> for StorePendingTaskError {
+	fn from(value: std::io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<tokio_rusqlite::Error> for StorePendingTaskError {
+	fn from(value: tokio_rusqlite::Error) -> Self {
+		Self::TokioRusqliteError(value)
+	}
+}
+
+pub(crate) async fn run(
+	basedir: PathBuf,
+	database: Connection,
+	message: IMessage,
+	data: Vec<u8>,
+	success: bool,
+) -> Result<(), StorePendingTaskError> {
+	super::write_data::run(basedir, message.data_hash, data).await?;
+
+	database
+		.call(move |conn| {
+			conn.prepare(include_str!("../../sql/insert-pending.sql"))?
+				.execute((
+					bs58::encode(message.hash).into_string(),
+					bs58::encode(message.sender.as_bytes()).into_string(),
+					bs58::encode(message.receiver.as_bytes()).into_string(),
+					bs58::encode(message.data_hash).into_string(),
+					message.timestamp,
+					success,
+					message.sealed,
+					message.group.map(|group| bs58::encode(group).into_string()),
+				))?;
+
+			Ok(())
+		})
+		.await?;
+
+	Ok(())
+}
@@ -0,0 +1,10 @@
+use crate::vault::{self, VaultKey};
+
+pub(crate) type UnlockVaultTaskError = tokio::task::JoinError;
+
+pub(crate) async fn run(
+	lock: String,
+	passphrase: String,
+) -> Result<Option<VaultKey>, UnlockVaultTaskError> {
+	tokio::task::spawn_blocking(move || vault::unlock(&lock, &passphrase)).await
+}
@@ -1,4 +1,4 @@
-use {ed25519_dalek::VerifyingKey, tokio_rusqlite::Connection};
+use {ed25519_dalek::VerifyingKey, tokio_rusqlite::Connection, url::Url};
 
 pub(crate) type UpdateAccountTaskError = tokio_rusqlite::Error;
 
@@ -6,11 +6,16 @@ pub(crate) async fn run(
 	database: Connection,
 	address: VerifyingKey,
 	name: String,
+	server: Url,
 ) -> Result<(), UpdateAccountTaskError> {
 	database
 		.call(move |conn| {
 			conn.prepare(include_str!("../../sql/update-account.sql"))?
-				.execute([bs58::encode(address.as_bytes()).into_string(), name])?;
+				.execute([
+					bs58::encode(address.as_bytes()).into_string(),
+					name,
+					server.to_string(),
+				])?;
 			Ok(())
 		})
 		.await
@@ -0,0 +1,24 @@
+use {ed25519_dalek::VerifyingKey, tokio_rusqlite::Connection};
+
+pub(crate) type UpdateContactTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	database: Connection,
+	owner: VerifyingKey,
+	address: VerifyingKey,
+	new_address: VerifyingKey,
+	name: String,
+) -> Result<(), UpdateContactTaskError> {
+	database
+		.call(move |conn| {
+			conn.prepare(include_str!("../../sql/update-contact.sql"))?
+				.execute((
+					bs58::encode(owner.as_bytes()).into_string(),
+					bs58::encode(address.as_bytes()).into_string(),
+					bs58::encode(new_address.as_bytes()).into_string(),
+					name,
+				))?;
+			Ok(())
+		})
+		.await
+}
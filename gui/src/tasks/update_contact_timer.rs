@@ -0,0 +1,22 @@
+use {ed25519_dalek::VerifyingKey, tokio_rusqlite::Connection};
+
+pub(crate) type UpdateContactTimerTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	database: Connection,
+	owner: VerifyingKey,
+	address: VerifyingKey,
+	timer: Option<u32>,
+) -> Result<(), UpdateContactTimerTaskError> {
+	database
+		.call(move |conn| {
+			conn.prepare(include_str!("../../sql/update-contact-timer.sql"))?
+				.execute((
+					bs58::encode(owner.as_bytes()).into_string(),
+					bs58::encode(address.as_bytes()).into_string(),
+					timer,
+				))?;
+			Ok(())
+		})
+		.await
+}
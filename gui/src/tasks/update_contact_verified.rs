@@ -0,0 +1,22 @@
+use {ed25519_dalek::VerifyingKey, tokio_rusqlite::Connection};
+
+pub(crate) type UpdateContactVerifiedTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	database: Connection,
+	owner: VerifyingKey,
+	address: VerifyingKey,
+	verified: Option<String>,
+) -> Result<(), UpdateContactVerifiedTaskError> {
+	database
+		.call(move |conn| {
+			conn.prepare(include_str!("../../sql/update-contact-verified.sql"))?
+				.execute((
+					bs58::encode(owner.as_bytes()).into_string(),
+					bs58::encode(address.as_bytes()).into_string(),
+					verified,
+				))?;
+			Ok(())
+		})
+		.await
+}
@@ -0,0 +1,18 @@
+use tokio_rusqlite::Connection;
+
+pub(crate) type UpdateOutgoingTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	database: Connection,
+	id: i64,
+	attempts: u32,
+	next_attempt_at: i64,
+) -> Result<(), UpdateOutgoingTaskError> {
+	database
+		.call(move |conn| {
+			conn.prepare(include_str!("../../sql/update-outgoing.sql"))?
+				.execute((id, attempts, next_attempt_at))?;
+			Ok(())
+		})
+		.await
+}
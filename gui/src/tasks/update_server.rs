@@ -0,0 +1,23 @@
+use {crate::states::Server, tokio_rusqlite::Connection};
+
+pub(crate) type UpdateServerTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(database: Connection, server: Server) -> Result<(), UpdateServerTaskError> {
+	database
+		.call(move |conn| {
+			if server.ca.is_none() && server.fingerprint.is_none() {
+				conn.prepare(include_str!("../../sql/delete-server.sql"))?
+					.execute([server.url.as_str()])?;
+			} else {
+				conn.prepare(include_str!("../../sql/update-server.sql"))?
+					.execute((
+						server.url.as_str(),
+						server.ca.map(|x| x.to_string_lossy().into_owned()),
+						server.fingerprint.map(hex::encode),
+					))?;
+			}
+
+			Ok(())
+		})
+		.await
+}
@@ -0,0 +1,54 @@
+use {crate::states::Settings, tokio_rusqlite::Connection};
+
+pub(crate) type UpdateSettingsTaskError = tokio_rusqlite::Error;
+
+pub(crate) async fn run(
+	database: Connection,
+	settings: Settings,
+) -> Result<(), UpdateSettingsTaskError> {
+	database
+		.call(move |conn| {
+			let tx = conn.transaction()?;
+			tx.execute(
+				include_str!("../../sql/update-setting.sql"),
+				["server", settings.server.as_str()],
+			)?;
+			tx.execute(
+				include_str!("../../sql/update-setting.sql"),
+				[
+					"lock_after",
+					settings
+						.lock_after
+						.map_or(String::from("off"), |secs| secs.to_string())
+						.as_str(),
+				],
+			)?;
+			tx.execute(
+				include_str!("../../sql/update-setting.sql"),
+				["padding", settings.padding.name()],
+			)?;
+			tx.execute(
+				include_str!("../../sql/update-setting.sql"),
+				[
+					"sealed_sender",
+					if settings.sealed_sender { "on" } else { "off" },
+				],
+			)?;
+			tx.execute(
+				include_str!("../../sql/update-setting.sql"),
+				["seed_index", settings.seed_index.to_string().as_str()],
+			)?;
+			if let Some(seed) = &settings.seed {
+				tx.execute(
+					include_str!("../../sql/update-setting.sql"),
+					["seed", seed.as_str()],
+				)?;
+			} else {
+				tx.execute(include_str!("../../sql/delete-setting.sql"), ["seed"])?;
+			}
+			tx.commit()?;
+
+			Ok(())
+		})
+		.await
+}
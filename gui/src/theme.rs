@@ -144,7 +144,7 @@ impl scrollable::StyleSheet for Theme {
 	) -> scrollable::Scrollbar {
 		scrollable::Scrollbar {
 			background: Some(Background::Color([0.0, 0.0, 0.0, 0.15].into())),
-			..Self::active(&self, style)
+			..Self::active(self, style)
 		}
 	}
 }
@@ -154,6 +154,9 @@ pub(crate) enum TextStyle {
 	#[default]
 	Default,
 	Disabled,
+	Success,
+	Warning,
+	Danger,
 }
 
 impl text::StyleSheet for Theme {
@@ -165,6 +168,15 @@ impl text::StyleSheet for Theme {
 			TextStyle::Disabled => text::Appearance {
 				color: Some([0.0, 0.0, 0.0, 0.25].into()),
 			},
+			TextStyle::Success => text::Appearance {
+				color: Some([0.1, 0.6, 0.1].into()),
+			},
+			TextStyle::Warning => text::Appearance {
+				color: Some([0.8, 0.55, 0.0].into()),
+			},
+			TextStyle::Danger => text::Appearance {
+				color: Some([0.8, 0.1, 0.1].into()),
+			},
 		}
 	}
 }
@@ -193,7 +205,7 @@ impl text_input::StyleSheet for Theme {
 	}
 
 	fn focused(&self, style: &Self::Style) -> text_input::Appearance {
-		Self::active(&self, style)
+		Self::active(self, style)
 	}
 
 	fn placeholder_color(&self, _: &Self::Style) -> Color {
@@ -205,7 +217,7 @@ impl text_input::StyleSheet for Theme {
 	}
 
 	fn disabled_color(&self, style: &Self::Style) -> Color {
-		Self::placeholder_color(&self, style)
+		Self::placeholder_color(self, style)
 	}
 
 	fn selection_color(&self, _: &Self::Style) -> Color {
@@ -213,6 +225,6 @@ impl text_input::StyleSheet for Theme {
 	}
 
 	fn disabled(&self, style: &Self::Style) -> text_input::Appearance {
-		Self::active(&self, style)
+		Self::active(self, style)
 	}
 }
@@ -0,0 +1,80 @@
+use {
+	crate::states::Server,
+	rustls::{
+		client::{ServerCertVerified, ServerCertVerifier},
+		Certificate,
+		CertificateError,
+		ClientConfig,
+		OwnedTrustAnchor,
+		RootCertStore,
+		ServerName,
+	},
+	sha2::{Digest, Sha256},
+	std::{
+		fs::File,
+		io::{self, BufReader},
+		sync::Arc,
+		time::SystemTime,
+	},
+};
+
+struct PinnedCertVerifier([u8; 32]);
+
+impl ServerCertVerifier for PinnedCertVerifier {
+	fn verify_server_cert(
+		&self,
+		end_entity: &Certificate,
+		_intermediates: &[Certificate],
+		_server_name: &ServerName,
+		_scts: &mut dyn Iterator<Item = &[u8]>,
+		_ocsp_response: &[u8],
+		_now: SystemTime,
+	) -> Result<ServerCertVerified, rustls::Error> {
+		let mut hasher: Sha256 = Digest::new();
+		hasher.update(&end_entity.0);
+
+		if hasher.finalize().as_slice() == self.0 {
+			Ok(ServerCertVerified::assertion())
+		} else {
+			Err(rustls::Error::InvalidCertificate(
+				CertificateError::ApplicationVerificationFailure,
+			))
+		}
+	}
+}
+
+pub(crate) fn fingerprint(input: &str) -> Option<[u8; 32]> {
+	hex::decode(input.trim().replace(':', ""))
+		.ok()?
+		.try_into()
+		.ok()
+}
+
+pub(crate) fn client_config(server: Option<&Server>) -> io::Result<Arc<ClientConfig>> {
+	let builder = ClientConfig::builder().with_safe_defaults();
+
+	if let Some(fingerprint) = server.and_then(|x| x.fingerprint) {
+		return Ok(Arc::new(
+			builder
+				.with_custom_certificate_verifier(Arc::new(PinnedCertVerifier(fingerprint)))
+				.with_no_client_auth(),
+		))
+	}
+
+	let mut roots = RootCertStore::empty();
+	roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|x| {
+		OwnedTrustAnchor::from_subject_spki_name_constraints(x.subject, x.spki, x.name_constraints)
+	}));
+
+	if let Some(ca) = server.and_then(|x| x.ca.as_ref()) {
+		for cert in rustls_pemfile::certs(&mut BufReader::new(File::open(ca)?))? {
+			roots
+				.add(&Certificate(cert))
+				.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+		}
+	}
+
+	Ok(Arc::new(
+		builder.with_root_certificates(roots).with_no_client_auth(),
+	))
+}
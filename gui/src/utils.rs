@@ -1,8 +1,15 @@
 use {
-	crate::states::Account,
-	aes_siv::{aead::Aead, AeadInPlace, Aes128SivAead, KeyInit, Nonce},
+	crate::{
+		envelope::{self, DecryptError, Padding, Scheme},
+		sealed,
+		states::{Account, Payload},
+	},
+	aes_siv::{aead::Aead, Aes128SivAead, KeyInit, Nonce},
+	borsh::BorshSerialize,
 	ed25519_dalek::VerifyingKey,
-	std::path::PathBuf,
+	project_toot_sdk::{ICaller, ISigner, Salt, Sha512Data},
+	sha2::{Digest, Sha512},
+	std::{path::PathBuf, time::Duration},
 };
 
 pub(crate) fn small_public_key(pk: &VerifyingKey) -> String {
@@ -15,44 +22,250 @@ pub(crate) fn public_key(pk: &VerifyingKey) -> String {
 	bs58::encode(pk.to_bytes()).into_string()
 }
 
+pub(crate) fn signer(account: &Account, method: &[u8]) -> ISigner {
+	sign(account, method, None, chrono::Utc::now().timestamp_micros())
+}
+
+/// Signs the digest of the payload along with the method, the server checks both.
+pub(crate) fn payload_signer(
+	account: &Account,
+	method: &[u8],
+	payload: &impl BorshSerialize,
+) -> ISigner {
+	payload_signer_at(
+		account,
+		method,
+		payload,
+		chrono::Utc::now().timestamp_micros(),
+	)
+}
+
+/// For payloads that were encrypted against the timestamp of their own signature.
+pub(crate) fn payload_signer_at(
+	account: &Account,
+	method: &[u8],
+	payload: &impl BorshSerialize,
+	timestamp: i64,
+) -> ISigner {
+	let digest: Sha512Data = Sha512::digest(borsh::to_vec(payload).unwrap()).into();
+	sign(account, method, Some(&digest), timestamp)
+}
+
+/// Signs lookups, or proves work over them instead when they have to stay anonymous.
+pub(crate) fn caller(
+	account: &Account,
+	method: &[u8],
+	payload: &impl BorshSerialize,
+	anonymous: bool,
+) -> ICaller {
+	if !anonymous {
+		return ICaller::Signer(payload_signer(account, method, payload))
+	}
+
+	let timestamp = chrono::Utc::now().timestamp_micros();
+	ICaller::Anonymous {
+		timestamp,
+		nonce: sealed::lookup(method, payload, timestamp),
+	}
+}
+
+fn sign(account: &Account, method: &[u8], payload: Option<&Sha512Data>, timestamp: i64) -> ISigner {
+	let salt = rand::random::<Salt>();
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(method);
+	hasher.update(salt);
+	hasher.update(timestamp.to_le_bytes());
+	if let Some(payload) = payload {
+		hasher.update(payload);
+	}
+
+	ISigner {
+		address: account.address,
+		salt,
+		timestamp,
+		signature: account.secret.sign_prehashed(hasher, None).unwrap(),
+	}
+}
+
+pub(crate) fn mnemonic(entropy: &[u8; 32]) -> String {
+	bip39::Mnemonic::from_entropy(entropy).unwrap().to_string()
+}
+
+/// Accepts either a recovery phrase or its bs58 encoded bytes.
+pub(crate) fn parse_phrase(value: &str) -> Option<[u8; 32]> {
+	let value = value.trim();
+	let bytes = if value.contains(char::is_whitespace) {
+		bip39::Mnemonic::parse_in_normalized(bip39::Language::English, &value.to_lowercase())
+			.ok()?
+			.to_entropy()
+	} else {
+		bs58::decode(value).into_vec().ok()?
+	};
+
+	bytes.try_into().ok()
+}
+
+pub(crate) fn basedir() -> PathBuf {
+	let basedir = home::home_dir()
+		.expect("Failed to get home dir")
+		.join(".local/share/ptd/");
+	std::fs::create_dir_all(basedir.clone()).expect("Failed to create program data dir");
+	basedir
+}
+
 pub(crate) fn data_path(basedir: PathBuf, hash: String) -> PathBuf {
 	basedir.join("data").join(&hash[0..4]).join(&hash[4..8])
 }
 
-pub(crate) fn encrypt(account: &Account, opposite: &VerifyingKey, mut buf: Vec<u8>) -> Vec<u8> {
-	let nonce = rand::random::<[u8; 16]>();
-	Aes128SivAead::new_from_slice(&x25519_dalek::x25519(
-		account.secret.to_scalar_bytes(),
-		opposite.to_montgomery().0,
-	))
-	.unwrap()
-	.encrypt_in_place(Nonce::from_slice(&nonce), b"", &mut buf)
-	.unwrap();
+/// Local copies are sealed for one account, so two local accounts in the same conversation
+/// keep separate files.
+pub(crate) fn data_key(owner: &VerifyingKey, data_hash: &[u8]) -> Sha512Data {
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(owner.as_bytes());
+	hasher.update(data_hash);
+	hasher.finalize().into()
+}
+
+pub(crate) const TIMERS: [Option<u32>; 6] = [
+	None,
+	Some(30),
+	Some(5 * 60),
+	Some(60 * 60),
+	Some(24 * 60 * 60),
+	Some(7 * 24 * 60 * 60),
+];
+
+pub(crate) const LOCK_TIMEOUTS: [Option<u32>; 5] =
+	[None, Some(60), Some(5 * 60), Some(15 * 60), Some(60 * 60)];
+
+pub(crate) fn duration(secs: i64) -> String {
+	match secs.max(0) {
+		secs @ 0..=59 => format!("{}s", secs),
+		secs @ 60..=3_599 => format!("{}m", secs / 60),
+		secs @ 3_600..=86_399 => format!("{}h", secs / 3_600),
+		secs => format!("{}d", secs / 86_400),
+	}
+}
+
+pub(crate) fn timer(timer: Option<u32>) -> String {
+	timer.map_or(String::from("Off"), |secs| duration(secs.into()))
+}
+
+pub(crate) fn backoff(attempt: u32, base: Duration, max: Duration) -> Duration {
+	let delay = base.saturating_mul(2u32.saturating_pow(attempt)).min(max);
+
+	delay / 2 + delay.mul_f64(rand::random::<f64>() / 2.0)
+}
+
+pub(crate) fn expires_at(timestamp: i64, timer: Option<u32>) -> Option<i64> {
+	timer.map(|secs| timestamp + i64::from(secs) * 1_000_000)
+}
+
+pub(crate) fn content(payload: &Payload) -> String {
+	match payload {
+		Payload::Text { content, .. } => content.clone(),
+		Payload::Timer(None) => String::from("Disappearing messages turned off"),
+		Payload::Timer(inner) => format!("Disappearing messages set to {}", timer(*inner)),
+		Payload::SenderKey { .. } => String::from("Group key updated"),
+	}
+}
+
+const PAYLOAD_TAG: &[u8] = b"ptd-payload";
+
+pub(crate) fn encode_payload(payload: &Payload) -> Vec<u8> {
 	let mut ret = Vec::<u8>::new();
-	ret.extend(b"aes-256-siv");
-	ret.extend(nonce);
-	ret.extend(buf);
+	ret.extend(PAYLOAD_TAG);
+	ret.extend(borsh::to_vec(payload).unwrap());
 	ret
 }
 
-pub(crate) fn decrypt(account: &Account, opposite: &VerifyingKey, data: &Vec<u8>) -> String {
-	if data.starts_with(b"aes-256-siv") {
-		let offset = b"aes-256-siv".len();
-		let Some(nonce) = data.get(offset..offset + 16) else {
-			return String::from("Failed to decrypt this message")
-		};
-
-		let Ok(buf) = Aes128SivAead::new_from_slice(&x25519_dalek::x25519(
-			account.secret.to_scalar_bytes(),
-			opposite.to_montgomery().0,
-		))
+pub(crate) fn decode_payload(buf: Vec<u8>) -> Result<Payload, DecryptError> {
+	if let Some(inner) = buf.strip_prefix(PAYLOAD_TAG) {
+		return borsh::from_slice::<Payload>(inner).or(Err(DecryptError::InvalidPayload))
+	}
+
+	Ok(Payload::Text {
+		content: String::from_utf8(buf).or(Err(DecryptError::InvalidPayload))?,
+		timer: None,
+	})
+}
+
+const SEAL_TAG: &[u8] = b"ptd-sealed";
+
+fn seal_key(account: &Account) -> [u8; 32] {
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(SEAL_TAG);
+	hasher.update(account.secret.as_bytes());
+	hasher.finalize()[..32].try_into().unwrap()
+}
+
+pub(crate) fn seal(account: &Account, buf: Vec<u8>) -> Vec<u8> {
+	envelope::seal(
+		envelope::DEFAULT_ALGORITHM,
+		Padding::None,
+		&seal_key(account),
+		Scheme::Sealed,
+		None,
+		account.address.as_bytes(),
+		&buf,
+	)
+}
+
+/// Seals sessions, prekey secrets and sender keys with the account secret, which the vault
+/// protects, so a locked vault leaves them as unreadable as the secret itself.
+pub(crate) fn seal_state(account: &Account, binding: &[u8], state: &[u8]) -> Vec<u8> {
+	envelope::seal(
+		envelope::DEFAULT_ALGORITHM,
+		Padding::None,
+		&seal_key(account),
+		Scheme::Sealed,
+		None,
+		binding,
+		state,
+	)
+}
+
+/// Rows stored before they were sealed are read as they are and sealed on their next write.
+pub(crate) fn open_state(account: &Account, binding: &[u8], state: Vec<u8>) -> Vec<u8> {
+	match envelope::decode(&state).and_then(|x| x.open(&seal_key(account), binding)) {
+		Ok(inner) => inner,
+		Err(_) => state,
+	}
+}
+
+fn decrypt_legacy(key: &[u8], tag: &[u8], data: &[u8]) -> Result<Vec<u8>, DecryptError> {
+	let offset = tag.len();
+	let Some(nonce) = data.get(offset..offset + 16) else {
+		return Err(DecryptError::Malformed)
+	};
+
+	Aes128SivAead::new_from_slice(key)
 		.unwrap()
-		.decrypt(Nonce::from_slice(nonce), &data[offset + 16..]) else {
-			return String::from("Failed to decrypt this message due decryption")
-		};
+		.decrypt(Nonce::from_slice(nonce), &data[offset + 16..])
+		.or(Err(DecryptError::Authentication))
+}
 
-		return String::from_utf8(buf).unwrap_or(String::from("Failed to decrypt this message"))
+pub(crate) fn decrypt(
+	account: &Account,
+	opposite: &VerifyingKey,
+	data: &[u8],
+) -> Result<Payload, DecryptError> {
+	if data.starts_with(SEAL_TAG) {
+		return decode_payload(decrypt_legacy(&seal_key(account), SEAL_TAG, data)?)
 	}
 
-	String::from("Cannot get message format")
+	if data.starts_with(b"aes-256-siv") {
+		let key =
+			x25519_dalek::x25519(account.secret.to_scalar_bytes(), opposite.to_montgomery().0);
+		return decode_payload(decrypt_legacy(&key, b"aes-256-siv", data)?)
+	}
+
+	let envelope = envelope::decode(data)?;
+	match envelope.scheme {
+		Scheme::Sealed => {
+			decode_payload(envelope.open(&seal_key(account), account.address.as_bytes())?)
+		},
+		Scheme::Ratchet(_) => Err(DecryptError::NoSession),
+		Scheme::SealedSender(_) | Scheme::Group(_) => Err(DecryptError::UnsupportedFormat),
+	}
 }
@@ -0,0 +1,113 @@
+use {
+	crate::{
+		envelope::{self, Padding, Scheme},
+		hd::Seed,
+	},
+	argon2::Argon2,
+	borsh::{BorshDeserialize, BorshSerialize},
+	ed25519_dalek::{SigningKey, VerifyingKey},
+};
+
+pub(crate) type VaultKey = [u8; 32];
+
+const VAULT_TAG: &[u8] = b"ptd-vault";
+const SEED_TAG: &[u8] = b"ptd-seed";
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+struct Lock {
+	salt: [u8; 16],
+	check: Vec<u8>,
+}
+
+pub(crate) fn derive(passphrase: &str, salt: &[u8; 16]) -> VaultKey {
+	let mut key = VaultKey::default();
+	Argon2::default()
+		.hash_password_into(passphrase.as_bytes(), salt, &mut key)
+		.unwrap();
+	key
+}
+
+pub(crate) fn create(passphrase: &str) -> (String, VaultKey) {
+	let salt = rand::random::<[u8; 16]>();
+	let key = derive(passphrase, &salt);
+	let check = envelope::seal(
+		envelope::DEFAULT_ALGORITHM,
+		Padding::None,
+		&key,
+		Scheme::Sealed,
+		None,
+		VAULT_TAG,
+		VAULT_TAG,
+	);
+
+	(
+		bs58::encode(borsh::to_vec(&Lock { salt, check }).unwrap()).into_string(),
+		key,
+	)
+}
+
+pub(crate) fn unlock(lock: &str, passphrase: &str) -> Option<VaultKey> {
+	let lock = borsh::from_slice::<Lock>(&bs58::decode(lock).into_vec().ok()?).ok()?;
+	let key = derive(passphrase, &lock.salt);
+	envelope::decode(&lock.check)
+		.ok()?
+		.open(&key, VAULT_TAG)
+		.ok()?;
+
+	Some(key)
+}
+
+fn encode(key: Option<&VaultKey>, binding: &[u8], value: &[u8]) -> String {
+	match key {
+		Some(key) => bs58::encode(envelope::seal(
+			envelope::DEFAULT_ALGORITHM,
+			Padding::None,
+			key,
+			Scheme::Sealed,
+			None,
+			binding,
+			value,
+		))
+		.into_string(),
+		None => bs58::encode(value).into_string(),
+	}
+}
+
+fn decode(key: Option<&VaultKey>, binding: &[u8], value: &str) -> Option<[u8; 32]> {
+	let buf = bs58::decode(value).into_vec().ok()?;
+	if let Ok(bytes) = <[u8; 32]>::try_from(buf.as_slice()) {
+		return Some(bytes)
+	}
+
+	envelope::decode(&buf)
+		.ok()?
+		.open(key?, binding)
+		.ok()?
+		.try_into()
+		.ok()
+}
+
+/// Only the secret and the seed go through the vault. Sessions, prekey secrets, sender keys and
+/// local message copies are sealed with a key derived from the secret instead, so they stay
+/// unreadable for as long as the vault is locked.
+pub(crate) fn encode_secret(key: Option<&VaultKey>, secret: &SigningKey) -> String {
+	encode(key, secret.verifying_key().as_bytes(), secret.as_bytes())
+}
+
+pub(crate) fn decode_secret(
+	key: Option<&VaultKey>,
+	address: &VerifyingKey,
+	value: &str,
+) -> Option<SigningKey> {
+	let secret = SigningKey::from_bytes(&decode(key, address.as_bytes(), value)?);
+
+	(secret.verifying_key() == *address).then_some(secret)
+}
+
+pub(crate) fn encode_seed(key: Option<&VaultKey>, seed: &Seed) -> String {
+	encode(key, SEED_TAG, seed)
+}
+
+pub(crate) fn decode_seed(key: Option<&VaultKey>, value: &str) -> Option<Seed> {
+	decode(key, SEED_TAG, value)
+}
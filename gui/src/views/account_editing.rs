@@ -2,7 +2,7 @@ use {
 	crate::{
 		messages::AccountEditingMessage,
 		states::Account,
-		theme::{ButtonStyle, TextInputStyle},
+		theme::{ButtonStyle, TextInputStyle, TextStyle},
 	},
 	iced::{
 		alignment::{Alignment, Horizontal, Vertical},
@@ -15,7 +15,11 @@ use {
 
 pub(crate) fn view<'a, Message, Theme>(
 	account: &Account,
-	input: &'a str,
+	name: &'a str,
+	server: &'a str,
+	ca: &'a str,
+	fingerprint: &'a str,
+	recovery: Option<&'a str>,
 ) -> Element<'a, Message, Renderer<Theme>>
 where
 	Message: 'a + Clone + From<AccountEditingMessage>,
@@ -23,7 +27,7 @@ where
 		+ Default
 		+ button::StyleSheet<Style = ButtonStyle>
 		+ container::StyleSheet
-		+ text::StyleSheet
+		+ text::StyleSheet<Style = TextStyle>
 		+ text_input::StyleSheet<Style = TextInputStyle>,
 {
 	container(
@@ -31,7 +35,7 @@ where
 			button(text(bs58::encode(account.address).into_string()))
 				.on_press(AccountEditingMessage::Copy.into())
 				.style(ButtonStyle::Clipboard),
-			text_input("Account name", input)
+			text_input("Account name", name)
 				.size(24)
 				.width(Length::Fixed(512.0))
 				.padding(8)
@@ -39,10 +43,54 @@ where
 				.on_paste(|new_value| AccountEditingMessage::UpdateName(new_value).into())
 				.on_submit(AccountEditingMessage::End(true).into())
 				.style(TextInputStyle::Dialog),
+			text_input("Home server", server)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| AccountEditingMessage::UpdateServer(new_value).into())
+				.on_paste(|new_value| AccountEditingMessage::UpdateServer(new_value).into())
+				.on_submit(AccountEditingMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			text_input("CA certificate file (optional)", ca)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| AccountEditingMessage::UpdateCa(new_value).into())
+				.on_paste(|new_value| AccountEditingMessage::UpdateCa(new_value).into())
+				.on_submit(AccountEditingMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			text_input("Pinned SHA-256 fingerprint (optional)", fingerprint)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| AccountEditingMessage::UpdateFingerprint(new_value).into())
+				.on_paste(|new_value| AccountEditingMessage::UpdateFingerprint(new_value).into())
+				.on_submit(AccountEditingMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			if let Some(recovery) = recovery {
+				column![
+					text(recovery)
+						.size(20)
+						.width(Length::Fixed(512.0))
+						.horizontal_alignment(Horizontal::Center),
+					text("Anyone with this phrase can read and send messages as this account.")
+						.size(16)
+						.style(TextStyle::Warning),
+				]
+				.spacing(4)
+				.align_items(Alignment::Center)
+			} else {
+				column![button(text("Show recovery phrase").size(24))
+					.padding([4, 8])
+					.on_press(AccountEditingMessage::ShowRecovery.into())]
+			},
 			row![
 				button(text("Cancel").size(24))
 					.padding([4, 8])
 					.on_press(AccountEditingMessage::End(false).into()),
+				button(text("Link device").size(24))
+					.padding([4, 8])
+					.on_press(AccountEditingMessage::LinkDevice.into()),
 				button(text("Confirm").size(24))
 					.padding([4, 8])
 					.on_press(AccountEditingMessage::End(true).into()),
@@ -0,0 +1,77 @@
+use {
+	crate::{
+		messages::AccountImportMessage,
+		theme::{TextInputStyle, TextStyle},
+	},
+	iced::{
+		alignment::{Alignment, Horizontal, Vertical},
+		widget::{button, column, container, row, text, text_input},
+		Element,
+		Length,
+		Renderer,
+	},
+};
+
+pub(crate) fn view<'a, Message, Theme>(
+	secret: &'a str,
+	name: &'a str,
+	failed: bool,
+) -> Element<'a, Message, Renderer<Theme>>
+where
+	Message: 'a + Clone + From<AccountImportMessage>,
+	Theme: 'a
+		+ Default
+		+ button::StyleSheet
+		+ container::StyleSheet
+		+ text::StyleSheet<Style = TextStyle>
+		+ text_input::StyleSheet<Style = TextInputStyle>,
+{
+	container(
+		column![
+			text("Import account").size(40),
+			text_input("Recovery phrase or secret key", secret)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.password()
+				.on_input(|new_value| AccountImportMessage::UpdateSecret(new_value).into())
+				.on_paste(|new_value| AccountImportMessage::UpdateSecret(new_value).into())
+				.on_submit(AccountImportMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			text_input("Account name (optional)", name)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| AccountImportMessage::UpdateName(new_value).into())
+				.on_paste(|new_value| AccountImportMessage::UpdateName(new_value).into())
+				.on_submit(AccountImportMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			if failed {
+				text("Unable to import from this recovery phrase or secret key")
+					.size(16)
+					.style(TextStyle::Danger)
+			} else {
+				text("").size(16)
+			},
+			row![
+				button(text("Cancel").size(24))
+					.padding([4, 8])
+					.on_press(AccountImportMessage::End(false).into()),
+				button(text("Restore seed").size(24))
+					.padding([4, 8])
+					.on_press(AccountImportMessage::RestoreSeed.into()),
+				button(text("Import").size(24))
+					.padding([4, 8])
+					.on_press(AccountImportMessage::End(true).into()),
+			]
+			.spacing(4),
+		]
+		.spacing(12)
+		.align_items(Alignment::Center),
+	)
+	.width(Length::Fill)
+	.height(Length::Fill)
+	.align_x(Horizontal::Center)
+	.align_y(Vertical::Center)
+	.into()
+}
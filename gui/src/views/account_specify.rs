@@ -5,6 +5,7 @@ use {
 		theme::{ButtonStyle, TextStyle},
 		utils::small_public_key,
 	},
+	ed25519_dalek::VerifyingKey,
 	iced::{
 		alignment::{Alignment, Horizontal, Vertical},
 		widget::{button, column, horizontal_space, row, scrollable, text, Column},
@@ -12,10 +13,12 @@ use {
 		Length,
 		Renderer,
 	},
+	std::collections::HashMap,
 };
 
 pub(crate) fn view<'a, Message, Theme>(
-	accounts: &'a Vec<Account>,
+	accounts: &'a [Account],
+	unread: &HashMap<VerifyingKey, usize>,
 ) -> Element<'a, Message, Renderer<Theme>>
 where
 	Message: 'a + Clone + From<AccountSpecifyMessage>,
@@ -29,7 +32,7 @@ where
 		text("Choose an account!").size(40),
 		row![
 			horizontal_space(Length::FillPortion(1)),
-			if accounts.len() != 0 {
+			if !accounts.is_empty() {
 				scrollable(
 					accounts
 						.iter()
@@ -45,6 +48,21 @@ where
 												small_public_key(&account.address),
 											))
 											.size(12),
+											text(account.server.as_str())
+												.size(12)
+												.style(TextStyle::Disabled),
+											text(
+												match unread
+													.get(&account.address)
+													.copied()
+													.unwrap_or(0)
+												{
+													0 => String::new(),
+													count => format!("{} unread", count),
+												}
+											)
+											.size(12)
+											.style(TextStyle::Success),
 										]
 										.spacing(4),
 									)
@@ -79,9 +97,24 @@ where
 			horizontal_space(Length::FillPortion(1)),
 		]
 		.height(Length::Fill),
-		button(text("Create").size(28))
-			.padding([4, 8])
-			.on_press(AccountSpecifyMessage::Create.into()),
+		row![
+			button(text("Settings").size(28))
+				.padding([4, 8])
+				.on_press(AccountSpecifyMessage::Settings.into()),
+			button(text("Backup").size(28))
+				.padding([4, 8])
+				.on_press(AccountSpecifyMessage::Backup.into()),
+			button(text("Link").size(28))
+				.padding([4, 8])
+				.on_press(AccountSpecifyMessage::Link.into()),
+			button(text("Import").size(28))
+				.padding([4, 8])
+				.on_press(AccountSpecifyMessage::Import.into()),
+			button(text("Create").size(28))
+				.padding([4, 8])
+				.on_press(AccountSpecifyMessage::Create.into()),
+		]
+		.spacing(4),
 	]
 	.spacing(12)
 	.padding(8)
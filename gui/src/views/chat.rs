@@ -1,11 +1,13 @@
 use {
 	crate::{
 		messages::ChatMessage,
-		states::{self, Account, Contact},
-		theme::{ButtonStyle, ContainerStyle},
-		utils::small_public_key,
+		safety::Verification,
+		states::{self, Account, ConnectionState, Contact, Outgoing},
+		theme::{ButtonStyle, ContainerStyle, TextStyle},
+		utils::{self, small_public_key},
 	},
 	chrono::TimeZone,
+	ed25519_dalek::VerifyingKey,
 	iced::{
 		alignment::{Alignment, Horizontal, Vertical},
 		widget::{button, column, container, row, scrollable, text, text_input, tooltip, Column},
@@ -13,15 +15,24 @@ use {
 		Length,
 		Renderer,
 	},
+	project_toot_sdk::IGroup,
 };
 
 pub(crate) fn view<'a, Message, Theme>(
-	accounts: &'a Vec<Account>,
-	contacts: &'a Vec<Contact>,
-	messages: &'a Vec<states::Message>,
+	accounts: &'a [Account],
+	contacts: &'a [Contact],
+	groups: &'a [IGroup],
+	messages: &'a [states::Message],
+	outbox: &'a [Outgoing],
+	blocked: &'a [VerifyingKey],
+	requests: usize,
 	account_index: usize,
+	connection: ConnectionState,
 	contact_index: Option<usize>,
+	group_index: Option<usize>,
+	verification: Option<Verification>,
 	message_text: &'a str,
+	now: i64,
 ) -> Element<'a, Message, Renderer<Theme>>
 where
 	Message: 'a + Clone + From<ChatMessage>,
@@ -30,7 +41,7 @@ where
 		+ button::StyleSheet<Style = ButtonStyle>
 		+ container::StyleSheet<Style = ContainerStyle>
 		+ scrollable::StyleSheet
-		+ text::StyleSheet
+		+ text::StyleSheet<Style = TextStyle>
 		+ text_input::StyleSheet,
 {
 	let account = &accounts[account_index];
@@ -42,9 +53,30 @@ where
 					row![
 						text(account.name.clone()).size(22),
 						text(format!("({})", small_public_key(&account.address))).size(12),
+						match connection {
+							ConnectionState::Online => text("Online").style(TextStyle::Success),
+							ConnectionState::Connecting => {
+								text("Connecting...").style(TextStyle::Warning)
+							},
+							ConnectionState::Offline => text("Offline").style(TextStyle::Danger),
+						}
+						.size(12),
 					]
 					.spacing(4)
+					.align_items(Alignment::Center)
 					.width(Length::Fill),
+					button(text("Switch").size(16).vertical_alignment(Vertical::Center),)
+						.height(40)
+						.on_press(ChatMessage::SwitchAccount.into())
+						.style(ButtonStyle::Contact),
+					button(
+						text(format!("Requests ({})", requests))
+							.size(16)
+							.vertical_alignment(Vertical::Center),
+					)
+					.height(40)
+					.on_press(ChatMessage::OpenRequests.into())
+					.style(ButtonStyle::Contact),
 					button(
 						text("+")
 							.size(22)
@@ -87,6 +119,47 @@ where
 					)
 				}
 			)),
+			row![
+				text("Groups").size(16).width(Length::Fill),
+				button(
+					text("+")
+						.size(16)
+						.horizontal_alignment(Horizontal::Center)
+						.vertical_alignment(Vertical::Center),
+				)
+				.width(32)
+				.height(32)
+				.on_press(ChatMessage::CreateGroup.into())
+				.style(ButtonStyle::Contact),
+			]
+			.padding([8, 6, 0, 8])
+			.align_items(Alignment::Center),
+			scrollable(
+				groups
+					.iter()
+					.enumerate()
+					.fold(Column::new(), |items, (index, group)| {
+						let maybe = if group_index != Some(index) {
+							Some(ChatMessage::ChooseGroup(index).into())
+						} else {
+							None
+						};
+
+						items.push(
+							button(
+								row![
+									text(group.name.clone()).size(22),
+									text(format!("({} members)", group.members.len())).size(12),
+								]
+								.spacing(4),
+							)
+							.padding([4, 6, 4, 8])
+							.width(Length::Fill)
+							.on_press_maybe(maybe)
+							.style(ButtonStyle::Contact),
+						)
+					})
+			),
 		])
 		.width(384)
 		.height(Length::Fill)
@@ -99,14 +172,43 @@ where
 					row![
 						row![
 							text(contact.name.clone()).size(24),
-							text(format!("({})", small_public_key(&contact.address))).size(12)
+							text(format!("({})", small_public_key(&contact.address))).size(12),
+							match verification {
+								Some(Verification::Verified) => {
+									text("Verified").size(12).style(TextStyle::Success)
+								},
+								Some(Verification::Changed) => text("Safety number changed")
+									.size(16)
+									.style(TextStyle::Danger),
+								_ => text(""),
+							},
 						]
 						.spacing(2)
+						.align_items(Alignment::Center)
 						.width(Length::Fill),
+						button(text("Details").size(24))
+							.on_press(ChatMessage::OpenDetails.into())
+							.style(ButtonStyle::Contact),
+						button(text(format!("Timer: {}", utils::timer(contact.timer))).size(24))
+							.on_press(ChatMessage::CycleTimer.into())
+							.style(ButtonStyle::Contact),
+						button(
+							text(
+								if blocked.contains(&contact.address) {
+									"Unblock"
+								} else {
+									"Block"
+								}
+							)
+							.size(24)
+						)
+						.on_press(ChatMessage::BlockContact(index).into())
+						.style(ButtonStyle::Contact),
 						button(text("Delete").size(24))
 							.on_press(ChatMessage::DeleteContact(index).into())
 							.style(ButtonStyle::Contact)
 					]
+					.spacing(4)
 					.align_items(Alignment::Center),
 				)
 				.padding([4, 6, 4, 8])
@@ -118,40 +220,67 @@ where
 					messages
 						.iter()
 						.filter(|x| {
-							x.sender == contact.address && x.receiver == account.address ||
-								x.sender == account.address && x.receiver == contact.address
+							x.owner == account.address &&
+								x.group.is_none() && (x.sender == contact.address &&
+								x.receiver == account.address ||
+								x.sender == account.address && x.receiver == contact.address)
 						})
 						.fold(Column::new(), |items, message| {
+							let bubble = row![
+								match &message.content {
+									Ok(content) => text(content).size(18),
+									Err(err) => text(format!("Unable to decrypt: {}", err))
+										.size(18)
+										.style(TextStyle::Danger),
+								},
+								tooltip(
+									text(
+										chrono::Utc
+											.timestamp_micros(message.timestamp)
+											.single()
+											.unwrap()
+											.naive_utc()
+											.format("%H:%M")
+									)
+									.size(12),
+									chrono::Utc
+										.timestamp_micros(message.timestamp)
+										.single()
+										.unwrap()
+										.naive_utc()
+										.format("%B %d %Y %H:%M:%S"),
+									tooltip::Position::Top,
+								)
+								.size(12)
+								.gap(4)
+								.padding(4)
+								.style(ContainerStyle::MessageSent),
+							]
+							.align_items(Alignment::End)
+							.spacing(4);
+							let bubble = if message.verified {
+								bubble
+							} else {
+								bubble.push(text("Unverified").size(12).style(TextStyle::Warning))
+							};
+
 							items.push(
 								container(
 									container(
-										row![
-											text(message.content.clone()).size(18),
-											tooltip(
-												text(
-													chrono::Utc
-														.timestamp_micros(message.timestamp)
-														.single()
-														.unwrap()
-														.naive_utc()
-														.format("%H:%M")
+										if let Some(expires_at) = message.expires_at {
+											bubble.push(
+												container(
+													text(utils::duration(
+														(expires_at - now) / 1_000_000,
+													))
+													.size(12),
 												)
-												.size(12),
-												chrono::Utc
-													.timestamp_micros(message.timestamp)
-													.single()
-													.unwrap()
-													.naive_utc()
-													.format("%B %d %Y %H:%M:%S"),
-												tooltip::Position::Top,
+												.padding(4)
+												.style(ContainerStyle::MessageSent),
 											)
-											.size(12)
-											.gap(4)
-											.padding(4)
-											.style(ContainerStyle::MessageSent),
-										]
-										.align_items(Alignment::End)
-										.spacing(4),
+										} else {
+											bubble
+										},
 									)
 									.padding(8)
 									.style(ContainerStyle::Message(
@@ -169,6 +298,175 @@ where
 								),
 							)
 						})
+						.push(
+							outbox
+								.iter()
+								.filter(|x| x.sender == account.address &&
+									x.receiver == contact.address)
+								.fold(Column::new(), |items, outgoing| {
+									let pending = outgoing.sending || outgoing.attempts == 0;
+									let bubble = row![
+										text(outgoing.content.clone()).size(18),
+										if pending {
+											text("Sending...").size(12).style(TextStyle::Disabled)
+										} else {
+											text("Failed").size(12).style(TextStyle::Danger)
+										},
+									]
+									.align_items(Alignment::End)
+									.spacing(4);
+
+									items.push(
+										container(
+											container(
+												if pending {
+													bubble
+												} else {
+													bubble
+														.push(
+															button(text("Retry").size(12))
+																.padding([2, 4])
+																.on_press(
+																	ChatMessage::RetryMessage(
+																		outgoing.id,
+																	)
+																	.into(),
+																)
+																.style(ButtonStyle::Contact),
+														)
+														.push(
+															button(text("Cancel").size(12))
+																.padding([2, 4])
+																.on_press(
+																	ChatMessage::CancelMessage(
+																		outgoing.id,
+																	)
+																	.into(),
+																)
+																.style(ButtonStyle::Contact),
+														)
+												},
+											)
+											.padding(8)
+											.style(ContainerStyle::Message(pending, true)),
+										)
+										.width(Length::Fill)
+										.align_x(Horizontal::Right),
+									)
+								})
+								.spacing(4),
+						)
+						.spacing(4)
+						.padding(16),
+				)
+				.width(Length::Fill)
+				.height(Length::Fill),
+				container(
+					text_input("Type message...", message_text)
+						.size(24)
+						.padding(8)
+						.on_input(|new_value| ChatMessage::UpdateMessage(new_value).into())
+						.on_paste(|new_value| ChatMessage::UpdateMessage(new_value).into())
+						.on_submit(ChatMessage::SendMessage.into())
+				)
+				.width(Length::Fill)
+				.style(ContainerStyle::MessageInput),
+			]
+		} else if let Some(index) = group_index {
+			let group = &groups[index];
+
+			column![
+				container(
+					row![
+						row![
+							text(group.name.clone()).size(24),
+							text(format!("({} members)", group.members.len())).size(12),
+						]
+						.spacing(2)
+						.align_items(Alignment::Center)
+						.width(Length::Fill),
+						button(text("Details").size(24))
+							.on_press(ChatMessage::OpenGroupDetails.into())
+							.style(ButtonStyle::Contact),
+					]
+					.spacing(4)
+					.align_items(Alignment::Center),
+				)
+				.padding([4, 6, 4, 8])
+				.width(Length::Fill)
+				.height(48)
+				.align_y(Vertical::Center)
+				.style(ContainerStyle::Contact),
+				scrollable(
+					messages
+						.iter()
+						.filter(|x| x.owner == account.address && x.group == Some(group.id))
+						.fold(Column::new(), |items, message| {
+							let mine = message.sender == account.address;
+							let bubble = row![
+								match &message.content {
+									Ok(content) => text(content).size(18),
+									Err(err) => text(format!("Unable to decrypt: {}", err))
+										.size(18)
+										.style(TextStyle::Danger),
+								},
+								text(
+									chrono::Utc
+										.timestamp_micros(message.timestamp)
+										.single()
+										.unwrap()
+										.naive_utc()
+										.format("%H:%M")
+								)
+								.size(12),
+							]
+							.align_items(Alignment::End)
+							.spacing(4);
+							let bubble = if message.verified {
+								bubble
+							} else {
+								bubble.push(text("Unverified").size(12).style(TextStyle::Warning))
+							};
+
+							items.push(
+								container(
+									column![
+										if mine {
+											text("")
+										} else {
+											text(
+												contacts
+													.iter()
+													.find(|x| x.address == message.sender)
+													.map_or_else(
+														|| small_public_key(&message.sender),
+														|x| x.name.clone(),
+													),
+											)
+										}
+										.size(12),
+										container(bubble)
+											.padding(8)
+											.style(ContainerStyle::Message(message.success, mine)),
+									]
+									.align_items(
+										if mine {
+											Alignment::End
+										} else {
+											Alignment::Start
+										},
+									),
+								)
+								.width(Length::Fill)
+								.align_x(
+									if mine {
+										Horizontal::Right
+									} else {
+										Horizontal::Left
+									},
+								),
+							)
+						})
 						.spacing(4)
 						.padding(16),
 				)
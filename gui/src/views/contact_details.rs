@@ -0,0 +1,102 @@
+use {
+	crate::{
+		messages::ContactDetailsMessage,
+		safety::{self, Verification},
+		states::Contact,
+		theme::{ButtonStyle, TextInputStyle, TextStyle},
+	},
+	iced::{
+		alignment::{Alignment, Horizontal, Vertical},
+		widget::{button, column, container, qr_code, row, text, text_input, QRCode},
+		Element,
+		Font,
+		Length,
+		Renderer,
+	},
+};
+
+pub(crate) fn view<'a, Message, Theme>(
+	contact: &Contact,
+	name: &'a str,
+	address: &'a str,
+	number: &str,
+	code: Option<&'a qr_code::State>,
+	verification: Verification,
+) -> Element<'a, Message, Renderer<Theme>>
+where
+	Message: 'a + Clone + From<ContactDetailsMessage>,
+	Theme: 'a
+		+ Default
+		+ button::StyleSheet<Style = ButtonStyle>
+		+ container::StyleSheet
+		+ text::StyleSheet<Style = TextStyle>
+		+ text_input::StyleSheet<Style = TextInputStyle>,
+{
+	container(
+		column![
+			button(text(bs58::encode(contact.address).into_string()))
+				.on_press(ContactDetailsMessage::Copy.into())
+				.style(ButtonStyle::Clipboard),
+			match verification {
+				Verification::Unverified => text("Not verified").size(16),
+				Verification::Verified => text("Verified").size(16).style(TextStyle::Success),
+				Verification::Changed => text(
+					"Safety number has changed since this contact was verified. Compare it again \
+					 before trusting new messages."
+				)
+				.size(20)
+				.style(TextStyle::Danger),
+			},
+			text(safety::format(number)).size(20).font(Font::MONOSPACE),
+			if let Some(code) = code {
+				container(QRCode::new(code).cell_size(4))
+			} else {
+				container(text("Unable to render QR code").size(16))
+			},
+			button(
+				text(
+					if verification == Verification::Verified {
+						"Clear verification"
+					} else {
+						"Mark as verified"
+					}
+				)
+				.size(24)
+			)
+			.padding([4, 8])
+			.on_press(ContactDetailsMessage::ToggleVerified.into()),
+			text_input("Contact name", name)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| ContactDetailsMessage::UpdateName(new_value).into())
+				.on_paste(|new_value| ContactDetailsMessage::UpdateName(new_value).into())
+				.on_submit(ContactDetailsMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			text_input("Contact address", address)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| ContactDetailsMessage::UpdateAddress(new_value).into())
+				.on_paste(|new_value| ContactDetailsMessage::UpdateAddress(new_value).into())
+				.on_submit(ContactDetailsMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			row![
+				button(text("Cancel").size(24))
+					.padding([4, 8])
+					.on_press(ContactDetailsMessage::End(false).into()),
+				button(text("Save").size(24))
+					.padding([4, 8])
+					.on_press(ContactDetailsMessage::End(true).into()),
+			]
+			.spacing(4),
+		]
+		.spacing(12)
+		.align_items(Alignment::Center),
+	)
+	.width(Length::Fill)
+	.height(Length::Fill)
+	.align_x(Horizontal::Center)
+	.align_y(Vertical::Center)
+	.into()
+}
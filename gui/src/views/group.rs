@@ -0,0 +1,166 @@
+use {
+	crate::{
+		messages::GroupMessage,
+		states::Contact,
+		theme::{ButtonStyle, TextInputStyle, TextStyle},
+		utils::small_public_key,
+	},
+	ed25519_dalek::VerifyingKey,
+	iced::{
+		alignment::{Alignment, Horizontal, Vertical},
+		widget::{button, column, container, row, scrollable, text, text_input, Column},
+		Element,
+		Length,
+		Renderer,
+	},
+	project_toot_sdk::IGroup,
+};
+
+pub(crate) fn view<'a, Message, Theme>(
+	address: &VerifyingKey,
+	group: Option<&'a IGroup>,
+	contacts: &'a [Contact],
+	name: &'a str,
+	member: &'a str,
+) -> Element<'a, Message, Renderer<Theme>>
+where
+	Message: 'a + Clone + From<GroupMessage>,
+	Theme: 'a
+		+ Default
+		+ button::StyleSheet<Style = ButtonStyle>
+		+ container::StyleSheet
+		+ scrollable::StyleSheet
+		+ text::StyleSheet<Style = TextStyle>
+		+ text_input::StyleSheet<Style = TextInputStyle>,
+{
+	container(
+		if let Some(group) = group {
+			let admin = group
+				.members
+				.iter()
+				.any(|x| &x.address == address && x.admin);
+
+			column![
+				text(group.name.clone()).size(40),
+				text(format!("{} members", group.members.len())).size(16),
+				scrollable(group.members.iter().enumerate().fold(
+					Column::new().spacing(4),
+					|items, (index, x)| {
+						let entry = row![
+							text(
+								if &x.address == address {
+									String::from("You")
+								} else {
+									contacts
+										.iter()
+										.find(|contact| contact.address == x.address)
+										.map_or_else(
+											|| small_public_key(&x.address),
+											|contact| contact.name.clone(),
+										)
+								}
+							)
+							.size(20)
+							.width(Length::Fill),
+							if x.admin {
+								text("Admin").size(12).style(TextStyle::Success)
+							} else {
+								text("").size(12)
+							},
+						]
+						.spacing(4)
+						.align_items(Alignment::Center);
+
+						items.push(
+							if admin && &x.address != address {
+								entry
+									.push(
+										button(
+											text(
+												if x.admin {
+													"Revoke admin"
+												} else {
+													"Make admin"
+												},
+											)
+											.size(16),
+										)
+										.padding([2, 4])
+										.on_press(GroupMessage::ToggleAdmin(index).into())
+										.style(ButtonStyle::Contact),
+									)
+									.push(
+										button(text("Remove").size(16))
+											.padding([2, 4])
+											.on_press(GroupMessage::Remove(index).into())
+											.style(ButtonStyle::Contact),
+									)
+							} else {
+								entry
+							},
+						)
+					}
+				))
+				.width(Length::Fixed(512.0))
+				.height(Length::Fixed(256.0)),
+				if admin {
+					row![
+						text_input("Member address", member)
+							.size(24)
+							.width(Length::Fill)
+							.padding(8)
+							.on_input(|new_value| GroupMessage::UpdateMember(new_value).into())
+							.on_paste(|new_value| GroupMessage::UpdateMember(new_value).into())
+							.on_submit(GroupMessage::Invite.into())
+							.style(TextInputStyle::Dialog),
+						button(text("Invite").size(24))
+							.padding([4, 8])
+							.on_press(GroupMessage::Invite.into()),
+					]
+					.spacing(4)
+					.width(Length::Fixed(512.0))
+					.align_items(Alignment::Center)
+				} else {
+					row![]
+				},
+				row![
+					button(text("Back").size(24))
+						.padding([4, 8])
+						.on_press(GroupMessage::Back.into()),
+					button(text("Leave").size(24))
+						.padding([4, 8])
+						.on_press(GroupMessage::Leave.into()),
+				]
+				.spacing(4),
+			]
+		} else {
+			column![
+				text("New group").size(40),
+				text_input("Group name", name)
+					.size(24)
+					.width(Length::Fixed(512.0))
+					.padding(8)
+					.on_input(|new_value| GroupMessage::UpdateName(new_value).into())
+					.on_paste(|new_value| GroupMessage::UpdateName(new_value).into())
+					.on_submit(GroupMessage::Create.into())
+					.style(TextInputStyle::Dialog),
+				row![
+					button(text("Cancel").size(24))
+						.padding([4, 8])
+						.on_press(GroupMessage::Back.into()),
+					button(text("Create").size(24))
+						.padding([4, 8])
+						.on_press(GroupMessage::Create.into()),
+				]
+				.spacing(4),
+			]
+		}
+		.spacing(12)
+		.align_items(Alignment::Center),
+	)
+	.width(Length::Fill)
+	.height(Length::Fill)
+	.align_x(Horizontal::Center)
+	.align_y(Vertical::Center)
+	.into()
+}
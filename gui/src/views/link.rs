@@ -0,0 +1,88 @@
+use {
+	crate::{
+		messages::LinkMessage,
+		theme::{ButtonStyle, TextInputStyle, TextStyle},
+	},
+	iced::{
+		alignment::{Alignment, Horizontal, Vertical},
+		widget::{button, column, container, qr_code, row, text, text_input, QRCode},
+		Element,
+		Length,
+		Renderer,
+	},
+};
+
+pub(crate) fn view<'a, Message, Theme>(
+	code: Option<&'a str>,
+	qr: Option<&'a qr_code::State>,
+	input: &'a str,
+	status: Option<&'a Result<String, String>>,
+) -> Element<'a, Message, Renderer<Theme>>
+where
+	Message: 'a + Clone + From<LinkMessage>,
+	Theme: 'a
+		+ Default
+		+ button::StyleSheet<Style = ButtonStyle>
+		+ container::StyleSheet
+		+ text::StyleSheet<Style = TextStyle>
+		+ text_input::StyleSheet<Style = TextInputStyle>,
+{
+	let status = match status {
+		Some(Ok(status)) => text(status).size(16).style(TextStyle::Success),
+		Some(Err(status)) => text(status).size(16).style(TextStyle::Danger),
+		None => text("").size(16),
+	};
+
+	container(
+		if let Some(code) = code {
+			column![
+				text("Link this device").size(40),
+				text("On your existing device open the account, choose \"Link device\" and enter this code.")
+					.size(16),
+				button(text(code).size(16))
+					.on_press(LinkMessage::Copy.into())
+					.style(ButtonStyle::Clipboard),
+				if let Some(qr) = qr {
+					container(QRCode::new(qr).cell_size(4))
+				} else {
+					container(text("Unable to render QR code").size(16))
+				},
+				text("Waiting for the account...").size(16).style(TextStyle::Warning),
+				status,
+				button(text("Back").size(24))
+					.padding([4, 8])
+					.on_press(LinkMessage::Back.into()),
+			]
+		} else {
+			column![
+				text("Link a new device").size(40),
+				text("The new device receives this account's key and contacts.").size(16),
+				text_input("Code shown on the new device", input)
+					.size(24)
+					.width(Length::Fixed(512.0))
+					.padding(8)
+					.on_input(|new_value| LinkMessage::UpdateCode(new_value).into())
+					.on_paste(|new_value| LinkMessage::UpdateCode(new_value).into())
+					.on_submit(LinkMessage::Send.into())
+					.style(TextInputStyle::Dialog),
+				status,
+				row![
+					button(text("Back").size(24))
+						.padding([4, 8])
+						.on_press(LinkMessage::Back.into()),
+					button(text("Send").size(24))
+						.padding([4, 8])
+						.on_press(LinkMessage::Send.into()),
+				]
+				.spacing(4),
+			]
+		}
+		.spacing(12)
+		.align_items(Alignment::Center),
+	)
+	.width(Length::Fill)
+	.height(Length::Fill)
+	.align_x(Horizontal::Center)
+	.align_y(Vertical::Center)
+	.into()
+}
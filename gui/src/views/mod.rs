@@ -1,4 +1,12 @@
 pub(crate) mod account_editing;
+pub(crate) mod account_import;
 pub(crate) mod account_specify;
 pub(crate) mod chat;
 pub(crate) mod contact_creation;
+pub(crate) mod contact_details;
+pub(crate) mod group;
+pub(crate) mod link;
+pub(crate) mod profile;
+pub(crate) mod requests;
+pub(crate) mod settings;
+pub(crate) mod unlock;
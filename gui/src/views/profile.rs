@@ -0,0 +1,75 @@
+use {
+	crate::{
+		messages::ProfileMessage,
+		theme::{TextInputStyle, TextStyle},
+	},
+	iced::{
+		alignment::{Alignment, Horizontal, Vertical},
+		widget::{button, column, container, row, text, text_input},
+		Element,
+		Length,
+		Renderer,
+	},
+};
+
+pub(crate) fn view<'a, Message, Theme>(
+	path: &'a str,
+	passphrase: &'a str,
+	status: Option<&'a Result<String, String>>,
+	busy: bool,
+) -> Element<'a, Message, Renderer<Theme>>
+where
+	Message: 'a + Clone + From<ProfileMessage>,
+	Theme: 'a
+		+ Default
+		+ button::StyleSheet
+		+ container::StyleSheet
+		+ text::StyleSheet<Style = TextStyle>
+		+ text_input::StyleSheet<Style = TextInputStyle>,
+{
+	container(
+		column![
+			text("Backup").size(40),
+			text_input("Profile file", path)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| ProfileMessage::UpdatePath(new_value).into())
+				.on_paste(|new_value| ProfileMessage::UpdatePath(new_value).into())
+				.style(TextInputStyle::Dialog),
+			text_input("Profile passphrase", passphrase)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.password()
+				.on_input(|new_value| ProfileMessage::UpdatePassphrase(new_value).into())
+				.on_paste(|new_value| ProfileMessage::UpdatePassphrase(new_value).into())
+				.style(TextInputStyle::Dialog),
+			match status {
+				_ if busy => text("Working...").size(16).style(TextStyle::Warning),
+				Some(Ok(status)) => text(status).size(16).style(TextStyle::Success),
+				Some(Err(status)) => text(status).size(16).style(TextStyle::Danger),
+				None => text("").size(16),
+			},
+			row![
+				button(text("Back").size(24))
+					.padding([4, 8])
+					.on_press(ProfileMessage::Back.into()),
+				button(text("Import").size(24))
+					.padding([4, 8])
+					.on_press_maybe((!busy).then(|| ProfileMessage::Import.into())),
+				button(text("Export").size(24))
+					.padding([4, 8])
+					.on_press_maybe((!busy).then(|| ProfileMessage::Export.into())),
+			]
+			.spacing(4),
+		]
+		.spacing(12)
+		.align_items(Alignment::Center),
+	)
+	.width(Length::Fill)
+	.height(Length::Fill)
+	.align_x(Horizontal::Center)
+	.align_y(Vertical::Center)
+	.into()
+}
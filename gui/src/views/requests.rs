@@ -0,0 +1,104 @@
+use {
+	crate::{
+		messages::RequestsMessage,
+		states::Request,
+		theme::{ButtonStyle, ContainerStyle, TextStyle},
+		utils::public_key,
+	},
+	iced::{
+		alignment::{Alignment, Horizontal, Vertical},
+		widget::{button, column, container, row, scrollable, text, Column},
+		Element,
+		Length,
+		Renderer,
+	},
+};
+
+pub(crate) fn view<'a, Message, Theme>(
+	requests: &'a [Request],
+) -> Element<'a, Message, Renderer<Theme>>
+where
+	Message: 'a + Clone + From<RequestsMessage>,
+	Theme: 'a
+		+ Default
+		+ button::StyleSheet<Style = ButtonStyle>
+		+ container::StyleSheet<Style = ContainerStyle>
+		+ scrollable::StyleSheet
+		+ text::StyleSheet<Style = TextStyle>,
+{
+	column![
+		text("Message requests").size(40),
+		if !requests.is_empty() {
+			scrollable(
+				requests
+					.iter()
+					.enumerate()
+					.fold(Column::new(), |items, (index, request)| {
+						items.push(
+							container(
+								column![
+									row![
+										text(public_key(&request.address))
+											.size(18)
+											.width(Length::Fill),
+										button(text("Accept").size(22))
+											.padding([4, 8])
+											.on_press(RequestsMessage::Accept(index).into())
+											.style(ButtonStyle::Contact),
+										button(text("Ignore").size(22))
+											.padding([4, 8])
+											.on_press(RequestsMessage::Ignore(index).into())
+											.style(ButtonStyle::Contact),
+										button(text("Block").size(22))
+											.padding([4, 8])
+											.on_press(RequestsMessage::Block(index).into())
+											.style(ButtonStyle::Contact),
+									]
+									.spacing(4)
+									.align_items(Alignment::Center),
+									request
+										.messages
+										.iter()
+										.fold(Column::new(), |items, message| {
+											items.push(match &message.content {
+												Ok(content) => text(content).size(18),
+												Err(err) => {
+													text(format!("Unable to decrypt: {}", err))
+														.size(18)
+														.style(TextStyle::Danger)
+												},
+											})
+										})
+										.spacing(4),
+								]
+								.spacing(8),
+							)
+							.padding(8)
+							.width(Length::Fill)
+							.style(ContainerStyle::Contact),
+						)
+					})
+					.spacing(8)
+					.padding([0, 64]),
+			)
+		} else {
+			scrollable(
+				text("There is no message requests.")
+					.size(44)
+					.horizontal_alignment(Horizontal::Center)
+					.vertical_alignment(Vertical::Center)
+					.style(TextStyle::Disabled),
+			)
+		}
+		.height(Length::Fill),
+		button(text("Back").size(28))
+			.padding([4, 8])
+			.on_press(RequestsMessage::Back.into()),
+	]
+	.spacing(12)
+	.padding(8)
+	.width(Length::Fill)
+	.height(Length::Fill)
+	.align_items(Alignment::Center)
+	.into()
+}
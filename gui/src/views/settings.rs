@@ -0,0 +1,157 @@
+use {
+	crate::{envelope::Padding, messages::SettingsMessage, theme::TextInputStyle, utils},
+	iced::{
+		alignment::{Alignment, Horizontal, Vertical},
+		widget::{button, column, container, row, text, text_input},
+		Element,
+		Length,
+		Renderer,
+	},
+};
+
+pub(crate) fn view<'a, Message, Theme>(
+	server: &'a str,
+	ca: &'a str,
+	fingerprint: &'a str,
+	passphrase: &'a str,
+	locked: bool,
+	lock_after: Option<u32>,
+	padding: Padding,
+	sealed_sender: bool,
+	seed: bool,
+	seed_phrase: Option<&'a str>,
+) -> Element<'a, Message, Renderer<Theme>>
+where
+	Message: 'a + Clone + From<SettingsMessage>,
+	Theme: 'a
+		+ Default
+		+ button::StyleSheet
+		+ container::StyleSheet
+		+ text::StyleSheet
+		+ text_input::StyleSheet<Style = TextInputStyle>,
+{
+	container(
+		column![
+			text("Settings").size(40),
+			text_input("Default server", server)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| SettingsMessage::UpdateServer(new_value).into())
+				.on_paste(|new_value| SettingsMessage::UpdateServer(new_value).into())
+				.on_submit(SettingsMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			text_input("CA certificate file (optional)", ca)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| SettingsMessage::UpdateCa(new_value).into())
+				.on_paste(|new_value| SettingsMessage::UpdateCa(new_value).into())
+				.on_submit(SettingsMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			text_input("Pinned SHA-256 fingerprint (optional)", fingerprint)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.on_input(|new_value| SettingsMessage::UpdateFingerprint(new_value).into())
+				.on_paste(|new_value| SettingsMessage::UpdateFingerprint(new_value).into())
+				.on_submit(SettingsMessage::End(true).into())
+				.style(TextInputStyle::Dialog),
+			row![
+				text_input(
+					if locked {
+						"New passphrase"
+					} else {
+						"Passphrase (optional)"
+					},
+					passphrase
+				)
+				.size(24)
+				.width(Length::Fill)
+				.padding(8)
+				.password()
+				.on_input(|new_value| SettingsMessage::UpdatePassphrase(new_value).into())
+				.on_paste(|new_value| SettingsMessage::UpdatePassphrase(new_value).into())
+				.on_submit(SettingsMessage::SetPassphrase.into())
+				.style(TextInputStyle::Dialog),
+				button(text(if locked { "Change" } else { "Set" }).size(24))
+					.padding([4, 8])
+					.on_press(SettingsMessage::SetPassphrase.into()),
+			]
+			.spacing(4)
+			.width(Length::Fixed(512.0))
+			.align_items(Alignment::Center),
+			if locked {
+				row![
+					button(text(format!("Auto-lock: {}", utils::timer(lock_after))).size(24))
+						.padding([4, 8])
+						.on_press(SettingsMessage::CycleLockTimeout.into()),
+					button(text("Remove passphrase").size(24))
+						.padding([4, 8])
+						.on_press(SettingsMessage::RemovePassphrase.into()),
+				]
+				.spacing(4)
+			} else {
+				row![]
+			},
+			row![
+				button(
+					text(format!(
+						"Message padding: {}",
+						match padding {
+							Padding::None => "Off",
+							Padding::PowerOfTwo => "Powers of two",
+							Padding::Padme => "Padmé",
+						}
+					))
+					.size(24)
+				)
+				.padding([4, 8])
+				.on_press(SettingsMessage::CyclePadding.into()),
+				button(
+					text(
+						if sealed_sender {
+							"Sealed sender: On"
+						} else {
+							"Sealed sender: Off"
+						}
+					)
+					.size(24)
+				)
+				.padding([4, 8])
+				.on_press(SettingsMessage::ToggleSealedSender.into()),
+			]
+			.spacing(4),
+			if let Some(seed_phrase) = seed_phrase {
+				row![text(seed_phrase)
+					.size(20)
+					.width(Length::Fixed(512.0))
+					.horizontal_alignment(Horizontal::Center)]
+			} else if seed {
+				row![button(text("Show seed phrase").size(24))
+					.padding([4, 8])
+					.on_press(SettingsMessage::ShowSeed.into())]
+			} else {
+				row![button(text("Create master seed").size(24))
+					.padding([4, 8])
+					.on_press(SettingsMessage::CreateSeed.into())]
+			},
+			row![
+				button(text("Cancel").size(24))
+					.padding([4, 8])
+					.on_press(SettingsMessage::End(false).into()),
+				button(text("Save").size(24))
+					.padding([4, 8])
+					.on_press(SettingsMessage::End(true).into()),
+			]
+			.spacing(4),
+		]
+		.spacing(12)
+		.align_items(Alignment::Center),
+	)
+	.width(Length::Fill)
+	.height(Length::Fill)
+	.align_x(Horizontal::Center)
+	.align_y(Vertical::Center)
+	.into()
+}
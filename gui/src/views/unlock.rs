@@ -0,0 +1,57 @@
+use {
+	crate::{
+		messages::UnlockMessage,
+		theme::{TextInputStyle, TextStyle},
+	},
+	iced::{
+		alignment::{Alignment, Horizontal, Vertical},
+		widget::{button, column, container, text, text_input},
+		Element,
+		Length,
+		Renderer,
+	},
+};
+
+pub(crate) fn view<'a, Message, Theme>(
+	passphrase: &'a str,
+	failed: bool,
+) -> Element<'a, Message, Renderer<Theme>>
+where
+	Message: 'a + Clone + From<UnlockMessage>,
+	Theme: 'a
+		+ Default
+		+ button::StyleSheet
+		+ container::StyleSheet
+		+ text::StyleSheet<Style = TextStyle>
+		+ text_input::StyleSheet<Style = TextInputStyle>,
+{
+	container(
+		column![
+			text("Unlock").size(40),
+			text_input("Passphrase", passphrase)
+				.size(24)
+				.width(Length::Fixed(512.0))
+				.padding(8)
+				.password()
+				.on_input(|new_value| UnlockMessage::UpdatePassphrase(new_value).into())
+				.on_paste(|new_value| UnlockMessage::UpdatePassphrase(new_value).into())
+				.on_submit(UnlockMessage::Submit.into())
+				.style(TextInputStyle::Dialog),
+			if failed {
+				text("Wrong passphrase").size(16).style(TextStyle::Danger)
+			} else {
+				text("").size(16)
+			},
+			button(text("Unlock").size(24))
+				.padding([4, 8])
+				.on_press(UnlockMessage::Submit.into()),
+		]
+		.spacing(12)
+		.align_items(Alignment::Center),
+	)
+	.width(Length::Fill)
+	.height(Length::Fill)
+	.align_x(Horizontal::Center)
+	.align_y(Vertical::Center)
+	.into()
+}
@@ -1,3 +1,19 @@
 use std::time::Duration;
 
 pub const MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET: Duration = Duration::from_secs(30);
+
+pub const MAXIMUM_MESSAGE_TTL: Duration = Duration::from_secs(60 * 60 * 24 * 30);
+
+pub const MAXIMUM_ONE_TIME_PREKEYS: u32 = 1_000;
+
+pub const MAXIMUM_GROUP_MEMBERS: u32 = 256;
+
+pub const MAXIMUM_GROUP_NAME_LEN: usize = 128;
+
+/// Leading zero bits required from the proof of work that stands in for the sender signature of
+/// a sealed message.
+pub const SEALED_MESSAGE_DIFFICULTY: u32 = 20;
+
+/// Leading zero bits required from the proof of work that stands in for the signature of an
+/// anonymous prekey or device lookup.
+pub const LOOKUP_DIFFICULTY: u32 = 16;
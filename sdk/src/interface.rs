@@ -1,5 +1,5 @@
 use {
-	crate::{Salt, Sha512Data},
+	crate::{DeviceId, GroupId, Salt, Sha512Data, X25519Data},
 	borsh::{BorshDeserialize, BorshSerialize},
 	ed25519_dalek::{Signature, VerifyingKey},
 };
@@ -19,10 +19,8 @@ mod verifying_key {
 	}
 
 	pub fn deserialize<R: Read>(reader: &mut R) -> io::Result<VerifyingKey> {
-		Ok(
-			VerifyingKey::from_bytes(&<[u8; 32]>::deserialize_reader(reader)?)
-				.or_else(|err| Err(Error::new(ErrorKind::InvalidData, Box::new(err))))?,
-		)
+		VerifyingKey::from_bytes(&<[u8; 32]>::deserialize_reader(reader)?)
+			.map_err(|err| Error::new(ErrorKind::InvalidData, Box::new(err)))
 	}
 
 	pub fn serialize_option<W: Write>(
@@ -38,6 +36,20 @@ mod verifying_key {
 		}
 	}
 
+	pub fn serialize_vec<W: Write>(obj: &Vec<VerifyingKey>, writer: &mut W) -> io::Result<()> {
+		(obj.len() as u32).serialize(writer)?;
+		for inner in obj {
+			serialize(inner, writer)?;
+		}
+		Ok(())
+	}
+
+	pub fn deserialize_vec<R: Read>(reader: &mut R) -> io::Result<Vec<VerifyingKey>> {
+		(0..u32::deserialize_reader(reader)?)
+			.map(|_| deserialize(reader))
+			.collect()
+	}
+
 	pub fn deserialize_option<R: Read>(reader: &mut R) -> io::Result<Option<VerifyingKey>> {
 		match u8::deserialize_reader(reader)? {
 			0 => Ok(None),
@@ -90,6 +102,15 @@ pub struct ISigner {
 	pub signature: Signature,
 }
 
+/// Lookups made for sealed sender can't be signed without giving the sender away, so they prove
+/// some work over the request instead.
+#[allow(clippy::large_enum_variant)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub enum ICaller {
+	Signer(ISigner),
+	Anonymous { timestamp: i64, nonce: u64 },
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
 pub struct IMessage {
 	pub hash: Sha512Data,
@@ -105,6 +126,10 @@ pub struct IMessage {
 	pub receiver: VerifyingKey,
 	pub data_hash: Sha512Data,
 	pub timestamp: i64,
+	pub sealed: bool,
+	pub group: Option<GroupId>,
+	/// Set when the message is encrypted for a single device of the receiver.
+	pub device: Option<DeviceId>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
@@ -115,6 +140,22 @@ pub struct ISendMessagePayload {
 	)]
 	pub receiver: VerifyingKey,
 	pub data: Vec<u8>,
+	pub ttl: Option<u32>,
+	pub device: Option<DeviceId>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct ISendSealedMessagePayload {
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub receiver: VerifyingKey,
+	pub data: Vec<u8>,
+	pub ttl: Option<u32>,
+	pub timestamp: i64,
+	pub nonce: u64,
+	pub device: Option<DeviceId>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
@@ -127,11 +168,171 @@ pub struct IGetMessagesPayload {
 	)]
 	pub with: Option<VerifyingKey>,
 	pub mine: bool,
+	pub device: Option<DeviceId>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
 pub struct IDeleteMessagesPayload {
 	pub hashes: Vec<Sha512Data>,
+	pub device: Option<DeviceId>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IBlockPayload {
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub address: VerifyingKey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IUnblockPayload {
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub address: VerifyingKey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IListBlockedPayload {
+	pub offset: u32,
+	pub limit: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IPublishSignedPrekeyPayload {
+	pub prekey: X25519Data,
+	#[borsh(
+		serialize_with = "signature::serialize",
+		deserialize_with = "signature::deserialize"
+	)]
+	pub signature: Signature,
+	pub device: DeviceId,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IPublishOneTimePrekeysPayload {
+	pub prekeys: Vec<X25519Data>,
+	pub device: DeviceId,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct ICountOneTimePrekeysPayload {
+	pub device: DeviceId,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IListDevicesPayload {
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub address: VerifyingKey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IFetchPrekeyBundlePayload {
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub address: VerifyingKey,
+	pub device: Option<DeviceId>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IPrekeyBundle {
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub address: VerifyingKey,
+	pub signed_prekey: X25519Data,
+	#[borsh(
+		serialize_with = "signature::serialize",
+		deserialize_with = "signature::deserialize"
+	)]
+	pub signature: Signature,
+	pub one_time_prekey: Option<X25519Data>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IRegisterDevicePayload {
+	pub device: DeviceId,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct ICreateGroupPayload {
+	pub group: GroupId,
+	pub name: String,
+	#[borsh(
+		serialize_with = "verifying_key::serialize_vec",
+		deserialize_with = "verifying_key::deserialize_vec"
+	)]
+	pub members: Vec<VerifyingKey>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IInviteMemberPayload {
+	pub group: GroupId,
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub member: VerifyingKey,
+	pub admin: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IRemoveMemberPayload {
+	pub group: GroupId,
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub member: VerifyingKey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct ISetMemberRolePayload {
+	pub group: GroupId,
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub member: VerifyingKey,
+	pub admin: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct ILeaveGroupPayload {
+	pub group: GroupId,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct ISendGroupMessagePayload {
+	pub group: GroupId,
+	pub data: Vec<u8>,
+	pub ttl: Option<u32>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IGroupMember {
+	#[borsh(
+		serialize_with = "verifying_key::serialize",
+		deserialize_with = "verifying_key::deserialize"
+	)]
+	pub address: VerifyingKey,
+	pub admin: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct IGroup {
+	pub id: GroupId,
+	pub name: String,
+	pub members: Vec<IGroupMember>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
@@ -139,13 +340,57 @@ pub enum IRPCRequest {
 	SendMessage(ISigner, ISendMessagePayload),
 	GetMessages(ISigner, IGetMessagesPayload),
 	DeleteMessages(ISigner, IDeleteMessagesPayload),
+	Block(ISigner, IBlockPayload),
+	Unblock(ISigner, IUnblockPayload),
+	ListBlocked(ISigner, IListBlockedPayload),
+	PublishSignedPrekey(ISigner, IPublishSignedPrekeyPayload),
+	PublishOneTimePrekeys(ISigner, IPublishOneTimePrekeysPayload),
+	CountOneTimePrekeys(ISigner, ICountOneTimePrekeysPayload),
+	/// Unsigned when sealed sender is on, so the server doesn't learn who is about to write.
+	FetchPrekeyBundle(ICaller, IFetchPrekeyBundlePayload),
+	RegisterDevice(ISigner, IRegisterDevicePayload),
+	SendSealedMessage(ISendSealedMessagePayload),
+	/// Membership changes are signed together with a digest of their payload.
+	CreateGroup(ISigner, ICreateGroupPayload),
+	InviteMember(ISigner, IInviteMemberPayload),
+	RemoveMember(ISigner, IRemoveMemberPayload),
+	LeaveGroup(ISigner, ILeaveGroupPayload),
+	ListGroups(ISigner),
+	SendGroupMessage(ISigner, ISendGroupMessagePayload),
+	ListDevices(ICaller, IListDevicesPayload),
+	SetMemberRole(ISigner, ISetMemberRolePayload),
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
 pub enum IRPCResponse {
 	SendMessage,
 	GetMessages(Vec<IMessage>),
 	DeleteMessages(Vec<bool>),
+	Block,
+	Unblock,
+	ListBlocked(
+		#[borsh(
+			serialize_with = "verifying_key::serialize_vec",
+			deserialize_with = "verifying_key::deserialize_vec"
+		)]
+		Vec<VerifyingKey>,
+	),
+	PublishSignedPrekey,
+	PublishOneTimePrekeys(u32),
+	CountOneTimePrekeys(u32),
+	FetchPrekeyBundle(Option<IPrekeyBundle>),
+	RegisterDevice,
+	SendSealedMessage,
+	CreateGroup,
+	InviteMember,
+	RemoveMember,
+	LeaveGroup,
+	ListGroups(Vec<IGroup>),
+	SendGroupMessage,
+	/// Devices with published prekeys, `None` stands for prekeys published before devices.
+	ListDevices(Vec<Option<DeviceId>>),
+	SetMemberRole,
 }
 
 pub mod irpcerror {
@@ -157,8 +402,19 @@ pub mod irpcerror {
 	pub const DESERIALIZATION_ERROR: i32 = -10000;
 	pub const MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET_EXCCEED: i32 = -10100;
 	pub const INVALID_SIGNATURE: i32 = -10101;
+	pub const INVALID_PROOF_OF_WORK: i32 = -10102;
+	pub const MESSAGE_ALREADY_EXISTS: i32 = -10103;
+	pub const TOO_MANY_REQUESTS: i32 = -10104;
 	pub const LIMIT_DONT_FIT_RANGE: i32 = -10200;
 	pub const HASHES_LEN_DONT_FIT_RANGE: i32 = -10201;
+	pub const PREKEYS_LEN_DONT_FIT_RANGE: i32 = -10202;
+	pub const MEMBERS_LEN_DONT_FIT_RANGE: i32 = -10203;
+	pub const TTL_DONT_FIT_RANGE: i32 = -10204;
+	pub const NAME_LEN_DONT_FIT_RANGE: i32 = -10205;
+	pub const INVALID_PREKEY_SIGNATURE: i32 = -10300;
+	pub const GROUP_ALREADY_EXISTS: i32 = -10400;
+	pub const NOT_GROUP_MEMBER: i32 = -10401;
+	pub const NOT_GROUP_ADMIN: i32 = -10402;
 
 	pub fn to_string(code: i32) -> String {
 		match code {
@@ -172,8 +428,19 @@ pub mod irpcerror {
 				"Maximum confirmation timestamp offset excceed".to_string()
 			},
 			INVALID_SIGNATURE => "Invalid signature".to_string(),
+			INVALID_PROOF_OF_WORK => "Invalid proof of work".to_string(),
+			MESSAGE_ALREADY_EXISTS => "Message already exists".to_string(),
+			TOO_MANY_REQUESTS => "Too many requests".to_string(),
 			LIMIT_DONT_FIT_RANGE => "Limit don't fit range".to_string(),
 			HASHES_LEN_DONT_FIT_RANGE => "Hashes vector length don't fit range".to_string(),
+			PREKEYS_LEN_DONT_FIT_RANGE => "Prekeys vector length don't fit range".to_string(),
+			MEMBERS_LEN_DONT_FIT_RANGE => "Members vector length don't fit range".to_string(),
+			TTL_DONT_FIT_RANGE => "TTL don't fit range".to_string(),
+			NAME_LEN_DONT_FIT_RANGE => "Name length don't fit range".to_string(),
+			INVALID_PREKEY_SIGNATURE => "Invalid prekey signature".to_string(),
+			GROUP_ALREADY_EXISTS => "Group already exists".to_string(),
+			NOT_GROUP_MEMBER => "Not a group member".to_string(),
+			NOT_GROUP_ADMIN => "Not a group admin".to_string(),
 			unk => format!("Unknown error: {}", unk),
 		}
 	}
@@ -184,6 +451,7 @@ pub enum IWebSocketRequest {
 	Authorize(ISigner),
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
 pub enum IWebSocketResponse {
 	Authorized,
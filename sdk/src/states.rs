@@ -1,3 +1,9 @@
 pub type Sha512Data = [u8; 64];
 
 pub type Salt = [u8; 32];
+
+pub type X25519Data = [u8; 32];
+
+pub type DeviceId = [u8; 16];
+
+pub type GroupId = [u8; 16];
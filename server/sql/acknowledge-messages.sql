@@ -0,0 +1,6 @@
+INSERT INTO "acknowledgements" ("hash", "device")
+SELECT "hash", $3
+FROM "messages"
+WHERE ("sender"=$1 OR "receiver"=$1) AND "hash"=ANY($2)
+ON CONFLICT DO NOTHING
+RETURNING "hash";
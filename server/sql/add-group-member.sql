@@ -0,0 +1,3 @@
+INSERT INTO "group_members" ("group", "address", "admin", "joined_at")
+VALUES ($1, $2, $3, $4)
+ON CONFLICT ("group", "address") DO NOTHING;
@@ -0,0 +1,3 @@
+INSERT INTO "group_members" ("group", "address", "admin", "joined_at")
+SELECT $1, UNNEST($2::VARCHAR[]), FALSE, $3
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,3 @@
+INSERT INTO "blocks" ("receiver", "sender")
+VALUES ($1, $2)
+ON CONFLICT DO NOTHING;
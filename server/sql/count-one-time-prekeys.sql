@@ -0,0 +1,3 @@
+SELECT COUNT(*)
+FROM "one_time_prekeys"
+WHERE "address"=$1 AND "device"=$2;
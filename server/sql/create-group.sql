@@ -0,0 +1,3 @@
+INSERT INTO "groups" ("id", "name", "created_at")
+VALUES ($1, $2, $3)
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,13 @@
+DELETE FROM "messages"
+WHERE ("sender"=$1 OR "receiver"=$1) AND "hash"=ANY($2)
+AND NOT EXISTS (
+	SELECT 1 FROM "devices"
+	WHERE "devices"."address"=$1
+	AND ("messages"."device" IS NULL OR "messages"."receiver"<>$1 OR "messages"."device"="devices"."device")
+	AND NOT EXISTS (
+		SELECT 1 FROM "acknowledgements"
+		WHERE "acknowledgements"."hash"="messages"."hash"
+		AND "acknowledgements"."device"="devices"."device"
+	)
+)
+RETURNING "hash";
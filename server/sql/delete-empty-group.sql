@@ -0,0 +1,5 @@
+DELETE FROM "groups"
+WHERE "id"=$1 AND NOT EXISTS (
+	SELECT 1 FROM "group_members"
+	WHERE "group"=$1
+);
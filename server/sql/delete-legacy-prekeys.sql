@@ -0,0 +1,6 @@
+WITH "signed" AS (
+	DELETE FROM "signed_prekeys"
+	WHERE "address"=$1 AND "device"=''
+)
+DELETE FROM "one_time_prekeys"
+WHERE "address"=$1 AND "device"='';
@@ -0,0 +1,3 @@
+UPDATE "messages"
+SET "expires_at"="timestamp" + make_interval(secs => $1)
+WHERE "expires_at" IS NULL;
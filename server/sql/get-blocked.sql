@@ -0,0 +1,6 @@
+SELECT "sender"
+FROM "blocks"
+WHERE "receiver"=$1
+ORDER BY "sender" ASC
+OFFSET $2
+LIMIT $3;
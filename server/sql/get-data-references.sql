@@ -0,0 +1,4 @@
+SELECT 1
+FROM "messages"
+WHERE "data_hash"=$1
+LIMIT 1;
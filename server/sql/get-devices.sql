@@ -0,0 +1,4 @@
+SELECT "device"
+FROM "signed_prekeys"
+WHERE "address"=$1
+ORDER BY "timestamp" ASC;
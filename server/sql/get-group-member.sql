@@ -0,0 +1,3 @@
+SELECT "admin"
+FROM "group_members"
+WHERE "group"=$1 AND "address"=$2;
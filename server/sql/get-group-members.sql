@@ -0,0 +1,4 @@
+SELECT "address", "admin"
+FROM "group_members"
+WHERE "group"=$1
+ORDER BY "joined_at" ASC;
@@ -0,0 +1,6 @@
+SELECT "groups"."id", "groups"."name", "members"."address", "members"."admin"
+FROM "groups"
+JOIN "group_members" AS "own" ON "own"."group"="groups"."id"
+JOIN "group_members" AS "members" ON "members"."group"="groups"."id"
+WHERE "own"."address"=$1
+ORDER BY "groups"."created_at" ASC, "groups"."id" ASC, "members"."joined_at" ASC;
@@ -1,10 +1,16 @@
-SELECT "hash", "sender", "receiver", "data_hash", "timestamp"
+SELECT "hash", "sender", "receiver", "data_hash", "timestamp", "sealed", "group", "device"
 FROM "messages"
 WHERE
 CASE
     WHEN $1=1 THEN "sender"=$2 OR "receiver"=$2
     ELSE "receiver"=$2
 END
+AND "expires_at">$5
+AND NOT EXISTS (
+	SELECT 1 FROM "acknowledgements"
+	WHERE "acknowledgements"."hash"="messages"."hash" AND "acknowledgements"."device"=$6
+)
+AND ("receiver"<>$2 OR "device" IS NULL OR "device"=$6)
 ORDER BY "timestamp" ASC
 OFFSET $3
 LIMIT $4;
@@ -1,10 +1,19 @@
-SELECT "hash", "sender", "receiver", "data_hash", "timestamp"
+SELECT "hash", "sender", "receiver", "data_hash", "timestamp", "sealed", "group", "device"
 FROM "messages"
 WHERE
+(
 CASE
     WHEN $1=1 THEN "sender"=$2 AND "receiver"=$3 OR "sender"=$3 AND "receiver"=$2
     ELSE "sender"=$3 AND "receiver"=$2
 END
+OR "sealed" AND "receiver"=$2
+)
+AND "expires_at">$6
+AND NOT EXISTS (
+	SELECT 1 FROM "acknowledgements"
+	WHERE "acknowledgements"."hash"="messages"."hash" AND "acknowledgements"."device"=$7
+)
+AND ("receiver"<>$2 OR "device" IS NULL OR "device"=$7)
 ORDER BY "timestamp" ASC
 OFFSET $4
 LIMIT $5;
@@ -0,0 +1,3 @@
+SELECT "prekey", "signature"
+FROM "signed_prekeys"
+WHERE "address"=$1 AND "device"=$2;
@@ -0,0 +1,3 @@
+SELECT 1
+FROM "blocks"
+WHERE "receiver"=$1 AND "sender"=$2;
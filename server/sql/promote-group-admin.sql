@@ -0,0 +1,12 @@
+UPDATE "group_members"
+SET "admin"=TRUE
+WHERE "group"=$1 AND "address"=(
+	SELECT "address" FROM "group_members"
+	WHERE "group"=$1
+	ORDER BY "joined_at" ASC
+	LIMIT 1
+)
+AND NOT EXISTS (
+	SELECT 1 FROM "group_members"
+	WHERE "group"=$1 AND "admin"
+);
@@ -0,0 +1,3 @@
+INSERT INTO "one_time_prekeys" ("address", "prekey", "device")
+SELECT $1, UNNEST($2::VARCHAR[]), $3
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,4 @@
+INSERT INTO "signed_prekeys" ("address", "prekey", "signature", "timestamp", "device")
+VALUES ($1, $2, $3, $4, $5)
+ON CONFLICT ("address", "device") DO UPDATE
+SET "prekey"=$2, "signature"=$3, "timestamp"=$4;
@@ -0,0 +1,5 @@
+DELETE FROM "acknowledgements"
+WHERE NOT EXISTS (
+	SELECT 1 FROM "messages"
+	WHERE "messages"."hash"="acknowledgements"."hash"
+);
@@ -0,0 +1,3 @@
+DELETE FROM "messages"
+WHERE "expires_at"<=$1
+RETURNING "data_hash";
@@ -0,0 +1,4 @@
+INSERT INTO "devices" ("address", "device", "registered_at")
+VALUES ($1, $2, $3)
+ON CONFLICT ("address", "device") DO UPDATE
+SET "registered_at"=$3;
@@ -0,0 +1,2 @@
+DELETE FROM "group_members"
+WHERE "group"=$1 AND "address"=$2;
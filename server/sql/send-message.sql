@@ -1,2 +1,3 @@
-INSERT INTO "messages" ("hash", "sender", "receiver", "data_hash", "timestamp")
-VALUES ($1, $2, $3, $4, $5);
+INSERT INTO "messages" ("hash", "sender", "receiver", "data_hash", "timestamp", "expires_at", "device")
+VALUES ($1, $2, $3, $4, $5, $6, $7)
+ON CONFLICT ("hash") DO NOTHING;
@@ -0,0 +1,3 @@
+INSERT INTO "messages" ("hash", "sender", "receiver", "data_hash", "timestamp", "expires_at", "sealed", "device")
+VALUES ($1, NULL, $2, $3, $4, $5, TRUE, $6)
+ON CONFLICT ("hash") DO NOTHING;
@@ -3,5 +3,57 @@ CREATE TABLE IF NOT EXISTS "messages" (
 	"sender" VARCHAR(44),
 	"receiver" VARCHAR(44),
 	"data_hash" VARCHAR(88),
+	"timestamp" TIMESTAMP,
+	"expires_at" TIMESTAMP
+);
+ALTER TABLE "messages" ADD COLUMN IF NOT EXISTS "expires_at" TIMESTAMP;
+ALTER TABLE "messages" ADD COLUMN IF NOT EXISTS "sealed" BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE TABLE IF NOT EXISTS "blocks" (
+	"receiver" VARCHAR(44),
+	"sender" VARCHAR(44),
+	PRIMARY KEY ("receiver", "sender")
+);
+CREATE TABLE IF NOT EXISTS "signed_prekeys" (
+	"address" VARCHAR(44) PRIMARY KEY,
+	"prekey" VARCHAR(44),
+	"signature" VARCHAR(88),
 	"timestamp" TIMESTAMP
 );
+CREATE TABLE IF NOT EXISTS "one_time_prekeys" (
+	"address" VARCHAR(44),
+	"prekey" VARCHAR(44),
+	PRIMARY KEY ("address", "prekey")
+);
+CREATE TABLE IF NOT EXISTS "devices" (
+	"address" VARCHAR(44),
+	"device" VARCHAR(24),
+	"registered_at" TIMESTAMP,
+	PRIMARY KEY ("address", "device")
+);
+CREATE TABLE IF NOT EXISTS "acknowledgements" (
+	"hash" VARCHAR(88),
+	"device" VARCHAR(24),
+	PRIMARY KEY ("hash", "device")
+);
+CREATE TABLE IF NOT EXISTS "groups" (
+	"id" VARCHAR(24) PRIMARY KEY,
+	"name" VARCHAR(128),
+	"created_at" TIMESTAMP
+);
+CREATE TABLE IF NOT EXISTS "group_members" (
+	"group" VARCHAR(24),
+	"address" VARCHAR(44),
+	"admin" BOOLEAN NOT NULL DEFAULT FALSE,
+	"joined_at" TIMESTAMP,
+	PRIMARY KEY ("group", "address")
+);
+ALTER TABLE "messages" ADD COLUMN IF NOT EXISTS "group" VARCHAR(24);
+ALTER TABLE "messages" ADD COLUMN IF NOT EXISTS "device" VARCHAR(24);
+ALTER TABLE "signed_prekeys" ADD COLUMN IF NOT EXISTS "device" VARCHAR(24) NOT NULL DEFAULT '';
+ALTER TABLE "signed_prekeys" DROP CONSTRAINT IF EXISTS "signed_prekeys_pkey";
+CREATE UNIQUE INDEX IF NOT EXISTS "signed_prekeys_address_device" ON "signed_prekeys" ("address", "device");
+ALTER TABLE "one_time_prekeys" ADD COLUMN IF NOT EXISTS "device" VARCHAR(24) NOT NULL DEFAULT '';
+DELETE FROM "messages" AS "duplicate"
+USING "messages" AS "original"
+WHERE "duplicate"."hash"="original"."hash" AND "duplicate"."ctid">"original"."ctid";
+CREATE UNIQUE INDEX IF NOT EXISTS "messages_hash" ON "messages" ("hash");
@@ -0,0 +1,9 @@
+DELETE FROM "one_time_prekeys"
+WHERE ("address", "prekey") IN (
+	SELECT "address", "prekey"
+	FROM "one_time_prekeys"
+	WHERE "address"=$1 AND "device"=$2
+	LIMIT 1
+	FOR UPDATE SKIP LOCKED
+)
+RETURNING "prekey";
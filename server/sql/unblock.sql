@@ -0,0 +1,2 @@
+DELETE FROM "blocks"
+WHERE "receiver"=$1 AND "sender"=$2;
@@ -0,0 +1,3 @@
+UPDATE "group_members"
+SET "admin"=$3
+WHERE "group"=$1 AND "address"=$2;
@@ -1,19 +1,33 @@
 mod cdn;
+mod purge;
+mod ratelimit;
 mod rpc;
 mod states;
 mod websocket;
 
 use {
-	crate::states::{AppState, DBManager, DBPool},
+	crate::{
+		ratelimit::RateLimiter,
+		states::{AppState, DBManager, DBPool},
+	},
 	axum::{
 		extract::DefaultBodyLimit,
 		routing::{get, post},
 		Router,
 	},
-	std::{collections::HashMap, sync::Arc},
+	axum_server::tls_rustls::RustlsConfig,
+	project_toot_sdk::MAXIMUM_MESSAGE_TTL,
+	std::{
+		collections::HashMap,
+		env,
+		sync::{Arc, Mutex},
+	},
 	tokio::{net, sync::RwLock},
 };
 
+const TLS_CERT_VAR: &str = "PROJECT_TOOT_TLS_CERT";
+const TLS_KEY_VAR: &str = "PROJECT_TOOT_TLS_KEY";
+
 #[tokio::main]
 async fn main() {
 	let manager = DBManager::new(
@@ -28,10 +42,21 @@ async fn main() {
 	pool.get()
 		.await
 		.unwrap()
-		.execute(include_str!("../sql/tables.sql"), &[])
+		.batch_execute(include_str!("../sql/tables.sql"))
+		.await
+		.unwrap();
+	pool.get()
+		.await
+		.unwrap()
+		.execute(
+			include_str!("../sql/expire-legacy-messages.sql"),
+			&[&MAXIMUM_MESSAGE_TTL.as_secs_f64()],
+		)
 		.await
 		.unwrap();
 
+	tokio::spawn(purge::run(pool.clone()));
+
 	let app = Router::new()
 		.route(
 			"/cdn/:file",
@@ -45,8 +70,18 @@ async fn main() {
 		.with_state(AppState {
 			pool,
 			websockets: Arc::new(RwLock::new(HashMap::new())),
+			lookups: Arc::new(Mutex::new(RateLimiter::default())),
 		});
 	let listener = net::TcpListener::bind("localhost:8080").await.unwrap();
 
-	axum::serve(listener, app).await.unwrap();
+	if let (Ok(cert), Ok(key)) = (env::var(TLS_CERT_VAR), env::var(TLS_KEY_VAR)) {
+		let config = RustlsConfig::from_pem_file(cert, key).await.unwrap();
+
+		axum_server::from_tcp_rustls(listener.into_std().unwrap(), config)
+			.serve(app.into_make_service())
+			.await
+			.unwrap();
+	} else {
+		axum::serve(listener, app).await.unwrap();
+	}
 }
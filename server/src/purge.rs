@@ -0,0 +1,85 @@
+use {
+	crate::{cdn::CDN_PATH, states::DBPool},
+	chrono::Utc,
+	std::{path::PathBuf, time::Duration},
+	tokio::{fs, time},
+};
+
+pub(crate) const PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+async fn purge(pool: &DBPool) -> Result<(), String> {
+	let mut database = pool.get().await.map_err(|err| err.to_string())?;
+
+	let mut expired = database
+		.query(
+			include_str!("../sql/purge-messages.sql"),
+			&[&Utc::now().naive_utc()],
+		)
+		.await
+		.map_err(|err| err.to_string())?
+		.into_iter()
+		.map(|row| row.get::<_, String>(0))
+		.collect::<Vec<_>>();
+	database
+		.execute(include_str!("../sql/purge-acknowledgements.sql"), &[])
+		.await
+		.map_err(|err| err.to_string())?;
+	expired.sort();
+	expired.dedup();
+
+	// Senders hold a shared lock on the hash while they store a message, so nothing can start
+	// referring to the data between the check and the delete.
+	for data_hash in expired {
+		let tx = database
+			.transaction()
+			.await
+			.map_err(|err| err.to_string())?;
+		tx.execute(
+			include_str!("../sql/lock-data-exclusive.sql"),
+			&[&data_hash],
+		)
+		.await
+		.map_err(|err| err.to_string())?;
+
+		if tx
+			.query_opt(
+				include_str!("../sql/get-data-references.sql"),
+				&[&data_hash],
+			)
+			.await
+			.map_err(|err| err.to_string())?
+			.is_some()
+		{
+			continue;
+		}
+
+		let Ok(bytes) = bs58::decode(&data_hash).into_vec() else {
+			continue;
+		};
+		if let Err(err) = fs::remove_file(
+			PathBuf::from(CDN_PATH)
+				.join(hex::encode(bytes))
+				.with_extension("dat"),
+		)
+		.await
+		{
+			eprintln!("{:#?}", err);
+		}
+
+		tx.commit().await.map_err(|err| err.to_string())?;
+	}
+
+	Ok(())
+}
+
+pub(crate) async fn run(pool: DBPool) {
+	let mut interval = time::interval(PURGE_INTERVAL);
+
+	loop {
+		interval.tick().await;
+
+		if let Err(err) = purge(&pool).await {
+			eprintln!("{}", err);
+		}
+	}
+}
@@ -0,0 +1,52 @@
+use std::{
+	collections::HashMap,
+	time::{Duration, Instant},
+};
+
+pub(crate) const LOOKUP_WINDOW: Duration = Duration::from_secs(60);
+pub(crate) const MAXIMUM_LOOKUPS: u32 = 60;
+
+/// Fixed window counters per target, so no one can use up the one-time prekeys of an account or
+/// walk its devices faster than a real sender would.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+	windows: HashMap<Vec<u8>, (Instant, u32)>,
+}
+
+impl RateLimiter {
+	/// Counts a request against the key, false once the key used up its window.
+	pub(crate) fn check(&mut self, key: &[u8], now: Instant) -> bool {
+		if self.windows.len() >= 4_096 {
+			self.windows
+				.retain(|_, (start, _)| now.duration_since(*start) < LOOKUP_WINDOW);
+		}
+
+		let (start, count) = self.windows.entry(key.to_vec()).or_insert((now, 0));
+		if now.duration_since(*start) >= LOOKUP_WINDOW {
+			*start = now;
+			*count = 0;
+		}
+		*count += 1;
+
+		*count <= MAXIMUM_LOOKUPS
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn window() {
+		let mut limiter = RateLimiter::default();
+		let now = Instant::now();
+
+		for _ in 0..MAXIMUM_LOOKUPS {
+			assert!(limiter.check(b"a", now));
+		}
+		assert!(!limiter.check(b"a", now));
+		// Other targets have their own window.
+		assert!(limiter.check(b"b", now));
+		assert!(limiter.check(b"a", now + LOOKUP_WINDOW));
+	}
+}
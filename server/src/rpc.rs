@@ -7,26 +7,187 @@ use {
 		extract::State,
 		response::{IntoResponse, Response},
 	},
-	chrono::{TimeZone, Utc},
-	ed25519_dalek::VerifyingKey,
+	borsh::BorshSerialize,
+	chrono::{DateTime, TimeZone, Utc},
+	ed25519_dalek::{Signature, VerifyingKey},
 	project_toot_sdk::{
 		irpcerror,
+		DeviceId,
+		GroupId,
+		IBlockPayload,
+		ICaller,
+		ICountOneTimePrekeysPayload,
+		ICreateGroupPayload,
 		IDeleteMessagesPayload,
+		IFetchPrekeyBundlePayload,
 		IGetMessagesPayload,
+		IGroup,
+		IGroupMember,
+		IInviteMemberPayload,
+		ILeaveGroupPayload,
+		IListBlockedPayload,
+		IListDevicesPayload,
 		IMessage,
+		IPrekeyBundle,
+		IPublishOneTimePrekeysPayload,
+		IPublishSignedPrekeyPayload,
 		IRPCRequest,
 		IRPCResponse,
+		IRegisterDevicePayload,
+		IRemoveMemberPayload,
+		ISendGroupMessagePayload,
 		ISendMessagePayload,
+		ISendSealedMessagePayload,
+		ISetMemberRolePayload,
 		ISigner,
+		IUnblockPayload,
 		IWebSocketResponse,
 		Sha512Data,
+		LOOKUP_DIFFICULTY,
 		MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET,
+		MAXIMUM_GROUP_MEMBERS,
+		MAXIMUM_GROUP_NAME_LEN,
+		MAXIMUM_MESSAGE_TTL,
+		MAXIMUM_ONE_TIME_PREKEYS,
+		SEALED_MESSAGE_DIFFICULTY,
 	},
 	sha2::{Digest, Sha512},
-	std::path::PathBuf,
+	std::{
+		collections::HashSet,
+		path::PathBuf,
+		time::{Duration, Instant},
+	},
 	tokio::fs,
 };
 
+/// Prekeys published before devices were introduced are stored without one.
+fn encode_device(device: Option<DeviceId>) -> String {
+	device.map_or(String::new(), |x| bs58::encode(x).into_string())
+}
+
+fn verify_signer(signer: &ISigner, method: &[u8]) -> Result<(), i32> {
+	verify_signed(signer, method, None)
+}
+
+/// Like `verify_signer`, but the signature also has to cover the payload so it can't be swapped
+/// for another one while the signer is still fresh.
+fn verify_payload_signer(
+	signer: &ISigner,
+	method: &[u8],
+	payload: &impl BorshSerialize,
+) -> Result<(), i32> {
+	let digest: Sha512Data = Sha512::digest(borsh::to_vec(payload).unwrap()).into();
+	verify_signed(signer, method, Some(&digest))
+}
+
+fn verify_signed(
+	ISigner {
+		address,
+		salt,
+		timestamp,
+		signature,
+	}: &ISigner,
+	method: &[u8],
+	payload: Option<&Sha512Data>,
+) -> Result<(), i32> {
+	let now = Utc::now();
+	let datetime = Utc
+		.timestamp_micros(*timestamp)
+		.single()
+		.ok_or(irpcerror::DESERIALIZATION_ERROR)?;
+	if datetime < (now - MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET) || datetime > now {
+		return Err(irpcerror::MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET_EXCCEED)
+	}
+
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(method);
+	hasher.update(salt);
+	hasher.update(timestamp.to_le_bytes());
+	if let Some(payload) = payload {
+		hasher.update(payload);
+	}
+
+	address
+		.verify_prehashed_strict(hasher, None, signature)
+		.or(Err(irpcerror::INVALID_SIGNATURE))
+}
+
+/// The proof of work digest of an anonymous lookup.
+fn lookup_digest(
+	method: &[u8],
+	payload: &impl BorshSerialize,
+	timestamp: i64,
+	nonce: u64,
+) -> Sha512Data {
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(method);
+	hasher.update(borsh::to_vec(payload).unwrap());
+	hasher.update(timestamp.to_le_bytes());
+	hasher.update(nonce.to_le_bytes());
+	hasher.finalize().into()
+}
+
+fn verify_caller(
+	caller: &ICaller,
+	method: &[u8],
+	payload: &impl BorshSerialize,
+) -> Result<(), i32> {
+	match caller {
+		ICaller::Signer(signer) => verify_payload_signer(signer, method, payload),
+		ICaller::Anonymous { timestamp, nonce } => {
+			let now = Utc::now();
+			let datetime = Utc
+				.timestamp_micros(*timestamp)
+				.single()
+				.ok_or(irpcerror::DESERIALIZATION_ERROR)?;
+			if datetime < (now - MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET) || datetime > now {
+				return Err(irpcerror::MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET_EXCCEED)
+			}
+
+			if leading_zeros(&lookup_digest(method, payload, *timestamp, *nonce)) <
+				LOOKUP_DIFFICULTY
+			{
+				return Err(irpcerror::INVALID_PROOF_OF_WORK)
+			}
+
+			Ok(())
+		},
+	}
+}
+
+/// Writes the data under a shared lock on its hash that lasts until the transaction ends, the purge
+/// job takes the same lock exclusively before it deletes data nothing refers to anymore.
+async fn store_data(
+	tx: &tokio_postgres::Transaction<'_>,
+	data_hash: &[u8],
+	data: Vec<u8>,
+) -> Result<(), i32> {
+	tx.execute(
+		include_str!("../sql/lock-data.sql"),
+		&[&bs58::encode(data_hash).into_string()],
+	)
+	.await
+	.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	fs::write(
+		PathBuf::from(CDN_PATH)
+			.join(hex::encode(data_hash))
+			.with_extension("dat"),
+		data,
+	)
+	.await
+	.or(Err(irpcerror::FILE_SYSTEM_WRITE_ERROR))
+}
+
+/// Senders may shorten how long the server keeps a message, never extend it past the maximum.
+fn expires_at(now: DateTime<Utc>, ttl: Option<u32>) -> Result<DateTime<Utc>, i32> {
+	match ttl {
+		Some(0) => Err(irpcerror::TTL_DONT_FIT_RANGE),
+		Some(ttl) => Ok(now + Duration::from_secs(ttl.into()).min(MAXIMUM_MESSAGE_TTL)),
+		None => Ok(now + MAXIMUM_MESSAGE_TTL),
+	}
+}
+
 pub(crate) async fn send_message(
 	state: AppState,
 	ISigner {
@@ -35,7 +196,12 @@ pub(crate) async fn send_message(
 		timestamp,
 		signature,
 	}: ISigner,
-	ISendMessagePayload { receiver, data }: ISendMessagePayload,
+	ISendMessagePayload {
+		receiver,
+		data,
+		ttl,
+		device,
+	}: ISendMessagePayload,
 ) -> Result<IRPCResponse, i32> {
 	let now = Utc::now();
 	let datetime = Utc
@@ -45,39 +211,58 @@ pub(crate) async fn send_message(
 	if datetime < (now - MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET) || datetime > now {
 		return Err(irpcerror::MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET_EXCCEED)
 	}
+	let expires_at = expires_at(now, ttl)?;
 
 	let hash = {
 		let mut hasher: Sha512 = Digest::new();
 		hasher.update(b"send-message");
-		hasher.update(&salt);
+		hasher.update(salt);
 		hasher.update(timestamp.to_le_bytes());
 
 		sender
 			.verify_prehashed_strict(hasher.clone(), None, &signature)
-			.and_then(|_| Ok(hasher.finalize()))
+			.map(|_| hasher.finalize())
 			.or(Err(irpcerror::INVALID_SIGNATURE))?
 	};
 
+	// Blocked senders aren't told, the same as with sealed messages, which the receiver drops.
+	if state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.query_opt(
+			include_str!("../sql/is-blocked.sql"),
+			&[
+				&bs58::encode(receiver).into_string(),
+				&bs58::encode(sender).into_string(),
+			],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_READ_ERROR))?
+		.is_some()
+	{
+		return Ok(IRPCResponse::SendMessage)
+	}
+
 	let data_hash = {
 		let mut hasher: Sha512 = Digest::new();
 		hasher.update(&data);
 		hasher.finalize()
 	};
 
-	fs::write(
-		PathBuf::from(CDN_PATH)
-			.join(hex::encode(data_hash.clone()))
-			.with_extension("dat"),
-		data,
-	)
-	.await
-	.or(Err(irpcerror::FILE_SYSTEM_WRITE_ERROR))?;
-
-	state
+	let mut database = state
 		.pool
 		.get()
 		.await
-		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?;
+	let tx = database
+		.transaction()
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+	store_data(&tx, &data_hash, data).await?;
+
+	if tx
 		.execute(
 			include_str!("../sql/send-message.sql"),
 			&[
@@ -85,25 +270,166 @@ pub(crate) async fn send_message(
 				&bs58::encode(sender).into_string(),
 				&bs58::encode(receiver).into_string(),
 				&bs58::encode(data_hash).into_string(),
-				&now.naive_utc(),
+				&datetime.naive_utc(),
+				&expires_at.naive_utc(),
+				&device.map(|x| bs58::encode(x).into_string()),
 			],
 		)
 		.await
-		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))? ==
+		0
+	{
+		return Err(irpcerror::MESSAGE_ALREADY_EXISTS)
+	}
+	tx.commit().await.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
 
-	if let Some(tx) = state.websockets.read().await.get(receiver.as_bytes()) {
-		let _ = tx.send(IWebSocketResponse::NewMessage(IMessage {
+	if let Some(senders) = state.websockets.write().await.get_mut(receiver.as_bytes()) {
+		let message = IMessage {
 			hash: hash.into(),
 			sender,
 			receiver,
 			data_hash: data_hash.into(),
 			timestamp,
-		}));
+			sealed: false,
+			group: None,
+			device,
+		};
+		senders.retain(|tx| {
+			tx.send(IWebSocketResponse::NewMessage(message.clone()))
+				.is_ok()
+		});
 	}
 
 	Ok(IRPCResponse::SendMessage)
 }
 
+fn leading_zeros(digest: &[u8]) -> u32 {
+	let mut ret = 0;
+	for byte in digest {
+		ret += byte.leading_zeros();
+		if *byte != 0 {
+			break;
+		}
+	}
+	ret
+}
+
+/// Checks the timestamp and the proof of work of a sealed message and returns its hash. The hash
+/// depends on nothing but the request, so a replay collides with the message it copies.
+fn verify_sealed(
+	now: DateTime<Utc>,
+	receiver: &VerifyingKey,
+	data_hash: &Sha512Data,
+	timestamp: i64,
+	nonce: u64,
+	device: Option<DeviceId>,
+) -> Result<(DateTime<Utc>, Sha512Data), i32> {
+	let datetime = Utc
+		.timestamp_micros(timestamp)
+		.single()
+		.ok_or(irpcerror::DESERIALIZATION_ERROR)?;
+	if datetime < (now - MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET) || datetime > now {
+		return Err(irpcerror::MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET_EXCCEED)
+	}
+
+	let hash = sealed_hash(receiver, data_hash, timestamp, nonce, device);
+	if leading_zeros(&hash) < SEALED_MESSAGE_DIFFICULTY {
+		return Err(irpcerror::INVALID_PROOF_OF_WORK)
+	}
+
+	Ok((datetime, hash))
+}
+
+fn sealed_hash(
+	receiver: &VerifyingKey,
+	data_hash: &Sha512Data,
+	timestamp: i64,
+	nonce: u64,
+	device: Option<DeviceId>,
+) -> Sha512Data {
+	let mut hasher: Sha512 = Digest::new();
+	hasher.update(b"sealed-message");
+	hasher.update(receiver.as_bytes());
+	hasher.update(data_hash);
+	hasher.update(timestamp.to_le_bytes());
+	hasher.update(nonce.to_le_bytes());
+	if let Some(device) = device {
+		hasher.update(device);
+	}
+	hasher.finalize().into()
+}
+
+/// Sealed messages carry no sender, so a proof of work over the delivery stands in for the
+/// signature. Messages to blocked senders can't be filtered here and are left to the receiver.
+pub(crate) async fn send_sealed_message(
+	state: AppState,
+	ISendSealedMessagePayload {
+		receiver,
+		data,
+		ttl,
+		timestamp,
+		nonce,
+		device,
+	}: ISendSealedMessagePayload,
+) -> Result<IRPCResponse, i32> {
+	let now = Utc::now();
+	let expires_at = expires_at(now, ttl)?;
+
+	let data_hash: Sha512Data = Sha512::digest(&data).into();
+	let (datetime, hash) = verify_sealed(now, &receiver, &data_hash, timestamp, nonce, device)?;
+
+	let mut database = state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?;
+	let tx = database
+		.transaction()
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+	store_data(&tx, &data_hash, data).await?;
+
+	// The proof of work is the only thing binding the request, so the hash rejects replays.
+	if tx
+		.execute(
+			include_str!("../sql/send-sealed-message.sql"),
+			&[
+				&bs58::encode(hash).into_string(),
+				&bs58::encode(receiver).into_string(),
+				&bs58::encode(data_hash).into_string(),
+				&datetime.naive_utc(),
+				&expires_at.naive_utc(),
+				&device.map(|x| bs58::encode(x).into_string()),
+			],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))? ==
+		0
+	{
+		return Err(irpcerror::MESSAGE_ALREADY_EXISTS)
+	}
+	tx.commit().await.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	if let Some(senders) = state.websockets.write().await.get_mut(receiver.as_bytes()) {
+		let message = IMessage {
+			hash,
+			sender: receiver,
+			receiver,
+			data_hash,
+			timestamp,
+			sealed: true,
+			group: None,
+			device,
+		};
+		senders.retain(|tx| {
+			tx.send(IWebSocketResponse::NewMessage(message.clone()))
+				.is_ok()
+		});
+	}
+
+	Ok(IRPCResponse::SendSealedMessage)
+}
+
 pub(crate) async fn get_messages(
 	state: AppState,
 	ISigner {
@@ -117,6 +443,7 @@ pub(crate) async fn get_messages(
 		limit,
 		with,
 		mine,
+		device,
 	}: IGetMessagesPayload,
 ) -> Result<IRPCResponse, i32> {
 	let now = Utc::now();
@@ -135,7 +462,7 @@ pub(crate) async fn get_messages(
 	{
 		let mut hasher: Sha512 = Digest::new();
 		hasher.update(b"get-messages");
-		hasher.update(&salt);
+		hasher.update(salt);
 		hasher.update(timestamp.to_le_bytes());
 
 		sender
@@ -143,6 +470,8 @@ pub(crate) async fn get_messages(
 			.or(Err(irpcerror::INVALID_SIGNATURE))?
 	}
 
+	let device = device.map(|x| bs58::encode(x).into_string());
+
 	Ok(IRPCResponse::GetMessages({
 		let database = state
 			.pool
@@ -159,6 +488,8 @@ pub(crate) async fn get_messages(
 						&bs58::encode(second).into_string(),
 						&(offset as i64),
 						&(limit as i64),
+						&now.naive_utc(),
+						&device,
 					],
 				)
 				.await
@@ -171,6 +502,8 @@ pub(crate) async fn get_messages(
 						&bs58::encode(sender).into_string(),
 						&(offset as i64),
 						&(limit as i64),
+						&now.naive_utc(),
+						&device,
 					],
 				)
 				.await
@@ -183,8 +516,9 @@ pub(crate) async fn get_messages(
 				.unwrap()
 				.try_into()
 				.unwrap(),
+			// Sealed messages don't have a sender, the receiver stands in like on the websocket.
 			sender: VerifyingKey::try_from(
-				bs58::decode(x.get::<_, &str>(1))
+				bs58::decode(x.get::<_, Option<&str>>(1).unwrap_or(x.get(2)))
 					.into_vec()
 					.unwrap()
 					.as_slice(),
@@ -203,6 +537,13 @@ pub(crate) async fn get_messages(
 				.try_into()
 				.unwrap(),
 			timestamp: x.get::<_, chrono::NaiveDateTime>(4).timestamp_micros(),
+			sealed: x.get(5),
+			group: x
+				.get::<_, Option<&str>>(6)
+				.map(|group| bs58::decode(group).into_vec().unwrap().try_into().unwrap()),
+			device: x
+				.get::<_, Option<&str>>(7)
+				.map(|device| bs58::decode(device).into_vec().unwrap().try_into().unwrap()),
 		})
 		.collect::<Vec<_>>()
 	}))
@@ -216,7 +557,7 @@ pub(crate) async fn delete_messages(
 		timestamp,
 		signature,
 	}: ISigner,
-	IDeleteMessagesPayload { hashes }: IDeleteMessagesPayload,
+	IDeleteMessagesPayload { hashes, device }: IDeleteMessagesPayload,
 ) -> Result<IRPCResponse, i32> {
 	let now = Utc::now();
 	let datetime = Utc
@@ -234,7 +575,7 @@ pub(crate) async fn delete_messages(
 	{
 		let mut hasher: Sha512 = Digest::new();
 		hasher.update(b"delete-messages");
-		hasher.update(&salt);
+		hasher.update(salt);
 		hasher.update(timestamp.to_le_bytes());
 
 		sender
@@ -242,52 +583,1045 @@ pub(crate) async fn delete_messages(
 			.or(Err(irpcerror::INVALID_SIGNATURE))?
 	}
 
-	let deleted = state
+	let database = state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?;
+	let address = bs58::encode(sender).into_string();
+	let encoded = hashes
+		.iter()
+		.map(|x| bs58::encode(x).into_string())
+		.collect::<Vec<_>>();
+
+	// Registered devices only acknowledge messages, which are dropped once every
+	// device of the address has seen them.
+	let deleted = if let Some(device) = device {
+		let mut rows = database
+			.query(
+				include_str!("../sql/acknowledge-messages.sql"),
+				&[&address, &encoded, &bs58::encode(device).into_string()],
+			)
+			.await
+			.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+		rows.extend(
+			database
+				.query(
+					include_str!("../sql/delete-acknowledged-messages.sql"),
+					&[&address, &encoded],
+				)
+				.await
+				.or(Err(irpcerror::DATABASE_DELETE_ERROR))?,
+		);
+		rows
+	} else {
+		database
+			.query(
+				include_str!("../sql/delete-messages.sql"),
+				&[&address, &encoded],
+			)
+			.await
+			.or(Err(irpcerror::DATABASE_DELETE_ERROR))?
+	}
+	.into_iter()
+	.map(|row| {
+		bs58::decode(row.get::<_, String>(0))
+			.into_vec()
+			.unwrap()
+			.try_into()
+			.unwrap()
+	})
+	.collect::<Vec<Sha512Data>>();
+
+	Ok(IRPCResponse::DeleteMessages(
+		hashes
+			.into_iter()
+			.map(|x| deleted.contains(&x))
+			.collect::<Vec<_>>(),
+	))
+}
+
+pub(crate) async fn block(
+	state: AppState,
+	signer: ISigner,
+	IBlockPayload { address }: IBlockPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_signer(&signer, b"block")?;
+
+	state
 		.pool
 		.get()
 		.await
 		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
-		.query(
-			include_str!("../sql/delete-messages.sql"),
+		.execute(
+			include_str!("../sql/block.sql"),
 			&[
-				&bs58::encode(sender).into_string(),
-				&hashes
-					.iter()
-					.map(|x| bs58::encode(x).into_string())
-					.collect::<Vec<_>>(),
+				&bs58::encode(signer.address).into_string(),
+				&bs58::encode(address).into_string(),
 			],
 		)
 		.await
-		.or(Err(irpcerror::DATABASE_DELETE_ERROR))?
-		.into_iter()
-		.map(|row| {
-			bs58::decode(row.get::<_, String>(0))
-				.into_vec()
-				.unwrap()
-				.try_into()
-				.unwrap()
-		})
-		.collect::<Vec<Sha512Data>>();
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
 
-	Ok(IRPCResponse::DeleteMessages(
-		hashes
+	Ok(IRPCResponse::Block)
+}
+
+pub(crate) async fn unblock(
+	state: AppState,
+	signer: ISigner,
+	IUnblockPayload { address }: IUnblockPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_signer(&signer, b"unblock")?;
+
+	state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.execute(
+			include_str!("../sql/unblock.sql"),
+			&[
+				&bs58::encode(signer.address).into_string(),
+				&bs58::encode(address).into_string(),
+			],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_DELETE_ERROR))?;
+
+	Ok(IRPCResponse::Unblock)
+}
+
+pub(crate) async fn list_blocked(
+	state: AppState,
+	signer: ISigner,
+	IListBlockedPayload { offset, limit }: IListBlockedPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_signer(&signer, b"list-blocked")?;
+
+	if !(1..=10_000).contains(&limit) {
+		return Err(irpcerror::LIMIT_DONT_FIT_RANGE)
+	}
+
+	Ok(IRPCResponse::ListBlocked(
+		state
+			.pool
+			.get()
+			.await
+			.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+			.query(
+				include_str!("../sql/get-blocked.sql"),
+				&[
+					&bs58::encode(signer.address).into_string(),
+					&(offset as i64),
+					&(limit as i64),
+				],
+			)
+			.await
+			.or(Err(irpcerror::DATABASE_READ_ERROR))?
 			.into_iter()
-			.map(|x| deleted.contains(&x))
+			.map(|row| {
+				VerifyingKey::try_from(
+					bs58::decode(row.get::<_, &str>(0))
+						.into_vec()
+						.unwrap()
+						.as_slice(),
+				)
+				.unwrap()
+			})
 			.collect::<Vec<_>>(),
 	))
 }
 
-pub(crate) async fn handler(
-	State(state): State<AppState>,
-	Borsh((id, data)): Borsh<(u128, IRPCRequest)>,
-) -> Response {
-	let res: Result<IRPCResponse, i32> = match data {
-		IRPCRequest::SendMessage(signer, payload) => send_message(state, signer, payload).await,
-		IRPCRequest::GetMessages(signer, payload) => get_messages(state, signer, payload).await,
-		IRPCRequest::DeleteMessages(signer, payload) => {
-			delete_messages(state, signer, payload).await
-		},
-	};
+pub(crate) async fn publish_signed_prekey(
+	state: AppState,
+	signer: ISigner,
+	IPublishSignedPrekeyPayload {
+		prekey,
+		signature,
+		device,
+	}: IPublishSignedPrekeyPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_signer(&signer, b"publish-signed-prekey")?;
 
-	Borsh((id, res)).into_response()
+	{
+		let mut hasher: Sha512 = Digest::new();
+		hasher.update(b"signed-prekey");
+		hasher.update(prekey);
+
+		signer
+			.address
+			.verify_prehashed_strict(hasher, None, &signature)
+			.or(Err(irpcerror::INVALID_PREKEY_SIGNATURE))?
+	}
+
+	let database = state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?;
+	let address = bs58::encode(signer.address).into_string();
+
+	database
+		.execute(
+			include_str!("../sql/publish-signed-prekey.sql"),
+			&[
+				&address,
+				&bs58::encode(prekey).into_string(),
+				&bs58::encode(signature.to_bytes()).into_string(),
+				&Utc::now().naive_utc(),
+				&encode_device(Some(device)),
+			],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	// Once a device publishes its own prekeys the ones shared by every device are dropped, so
+	// nobody starts a session that only one of the devices could answer.
+	database
+		.execute(
+			include_str!("../sql/delete-legacy-prekeys.sql"),
+			&[&address],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_DELETE_ERROR))?;
+
+	Ok(IRPCResponse::PublishSignedPrekey)
+}
+
+async fn count_prekeys(
+	state: &AppState,
+	address: &VerifyingKey,
+	device: &DeviceId,
+) -> Result<u32, i32> {
+	Ok(state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.query_one(
+			include_str!("../sql/count-one-time-prekeys.sql"),
+			&[
+				&bs58::encode(address).into_string(),
+				&encode_device(Some(*device)),
+			],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_READ_ERROR))?
+		.get::<_, i64>(0) as u32)
+}
+
+pub(crate) async fn publish_one_time_prekeys(
+	state: AppState,
+	signer: ISigner,
+	IPublishOneTimePrekeysPayload { prekeys, device }: IPublishOneTimePrekeysPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_signer(&signer, b"publish-one-time-prekeys")?;
+
+	let count = count_prekeys(&state, &signer.address, &device).await?;
+	if !(1..=MAXIMUM_ONE_TIME_PREKEYS.saturating_sub(count) as usize).contains(&prekeys.len()) {
+		return Err(irpcerror::PREKEYS_LEN_DONT_FIT_RANGE)
+	}
+
+	state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.execute(
+			include_str!("../sql/publish-one-time-prekeys.sql"),
+			&[
+				&bs58::encode(signer.address).into_string(),
+				&prekeys
+					.iter()
+					.map(|x| bs58::encode(x).into_string())
+					.collect::<Vec<_>>(),
+				&encode_device(Some(device)),
+			],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	Ok(IRPCResponse::PublishOneTimePrekeys(
+		count_prekeys(&state, &signer.address, &device).await?,
+	))
+}
+
+pub(crate) async fn count_one_time_prekeys(
+	state: AppState,
+	signer: ISigner,
+	ICountOneTimePrekeysPayload { device }: ICountOneTimePrekeysPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_signer(&signer, b"count-one-time-prekeys")?;
+
+	Ok(IRPCResponse::CountOneTimePrekeys(
+		count_prekeys(&state, &signer.address, &device).await?,
+	))
+}
+
+pub(crate) async fn list_devices(
+	state: AppState,
+	caller: ICaller,
+	payload: IListDevicesPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_caller(&caller, b"list-devices", &payload)?;
+	let IListDevicesPayload { address } = payload;
+
+	if !state.lookups.lock().unwrap().check(
+		&[b"list-devices".as_slice(), address.as_bytes()].concat(),
+		Instant::now(),
+	) {
+		return Err(irpcerror::TOO_MANY_REQUESTS)
+	}
+
+	Ok(IRPCResponse::ListDevices(
+		state
+			.pool
+			.get()
+			.await
+			.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+			.query(
+				include_str!("../sql/get-devices.sql"),
+				&[&bs58::encode(address).into_string()],
+			)
+			.await
+			.or(Err(irpcerror::DATABASE_READ_ERROR))?
+			.into_iter()
+			.map(|row| {
+				Some(row.get::<_, &str>(0))
+					.filter(|x| !x.is_empty())
+					.map(|x| bs58::decode(x).into_vec().unwrap().try_into().unwrap())
+			})
+			.collect::<Vec<_>>(),
+	))
+}
+
+pub(crate) async fn fetch_prekey_bundle(
+	state: AppState,
+	caller: ICaller,
+	payload: IFetchPrekeyBundlePayload,
+) -> Result<IRPCResponse, i32> {
+	verify_caller(&caller, b"fetch-prekey-bundle", &payload)?;
+	let IFetchPrekeyBundlePayload { address, device } = payload;
+
+	if !state.lookups.lock().unwrap().check(
+		&[
+			b"fetch-prekey-bundle".as_slice(),
+			address.as_bytes(),
+			&device.unwrap_or_default(),
+		]
+		.concat(),
+		Instant::now(),
+	) {
+		return Err(irpcerror::TOO_MANY_REQUESTS)
+	}
+
+	let database = state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?;
+	let address_str = bs58::encode(address).into_string();
+	let device = encode_device(device);
+
+	let Some(row) = database
+		.query_opt(
+			include_str!("../sql/get-signed-prekey.sql"),
+			&[&address_str, &device],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_READ_ERROR))?
+	else {
+		return Ok(IRPCResponse::FetchPrekeyBundle(None))
+	};
+
+	let one_time_prekey = database
+		.query_opt(
+			include_str!("../sql/take-one-time-prekey.sql"),
+			&[&address_str, &device],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_DELETE_ERROR))?
+		.map(|row| {
+			bs58::decode(row.get::<_, &str>(0))
+				.into_vec()
+				.unwrap()
+				.try_into()
+				.unwrap()
+		});
+
+	Ok(IRPCResponse::FetchPrekeyBundle(Some(IPrekeyBundle {
+		address,
+		signed_prekey: bs58::decode(row.get::<_, &str>(0))
+			.into_vec()
+			.unwrap()
+			.try_into()
+			.unwrap(),
+		signature: Signature::from_bytes(
+			&bs58::decode(row.get::<_, &str>(1))
+				.into_vec()
+				.unwrap()
+				.try_into()
+				.unwrap(),
+		),
+		one_time_prekey,
+	})))
+}
+
+pub(crate) async fn register_device(
+	state: AppState,
+	signer: ISigner,
+	IRegisterDevicePayload { device }: IRegisterDevicePayload,
+) -> Result<IRPCResponse, i32> {
+	verify_signer(&signer, b"register-device")?;
+
+	state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.execute(
+			include_str!("../sql/register-device.sql"),
+			&[
+				&bs58::encode(signer.address).into_string(),
+				&bs58::encode(device).into_string(),
+				&Utc::now().naive_utc(),
+			],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	Ok(IRPCResponse::RegisterDevice)
+}
+
+/// Returns whether the address administers the group, failing for non-members.
+async fn group_admin(
+	state: &AppState,
+	group: &GroupId,
+	address: &VerifyingKey,
+) -> Result<bool, i32> {
+	Ok(state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.query_opt(
+			include_str!("../sql/get-group-member.sql"),
+			&[
+				&bs58::encode(group).into_string(),
+				&bs58::encode(address).into_string(),
+			],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_READ_ERROR))?
+		.ok_or(irpcerror::NOT_GROUP_MEMBER)?
+		.get(0))
+}
+
+async fn group_members(state: &AppState, group: &GroupId) -> Result<Vec<IGroupMember>, i32> {
+	Ok(state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.query(
+			include_str!("../sql/get-group-members.sql"),
+			&[&bs58::encode(group).into_string()],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_READ_ERROR))?
+		.into_iter()
+		.map(|row| IGroupMember {
+			address: VerifyingKey::try_from(
+				bs58::decode(row.get::<_, &str>(0))
+					.into_vec()
+					.unwrap()
+					.as_slice(),
+			)
+			.unwrap(),
+			admin: row.get(1),
+		})
+		.collect::<Vec<_>>())
+}
+
+/// Drops a member, handing the group to its oldest member once no admin is left.
+async fn drop_group_member(
+	state: &AppState,
+	group: &GroupId,
+	address: &VerifyingKey,
+) -> Result<(), i32> {
+	let database = state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?;
+	let group = bs58::encode(group).into_string();
+
+	database
+		.execute(
+			include_str!("../sql/remove-group-member.sql"),
+			&[&group, &bs58::encode(address).into_string()],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_DELETE_ERROR))?;
+	database
+		.execute(include_str!("../sql/promote-group-admin.sql"), &[&group])
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+	database
+		.execute(include_str!("../sql/delete-empty-group.sql"), &[&group])
+		.await
+		.or(Err(irpcerror::DATABASE_DELETE_ERROR))?;
+
+	Ok(())
+}
+
+/// Counts the creator along with everyone else listed once, the same way invites count the
+/// members already in the group.
+fn group_size(creator: &VerifyingKey, members: &[VerifyingKey]) -> usize {
+	members
+		.iter()
+		.filter(|x| *x != creator)
+		.collect::<HashSet<_>>()
+		.len() + 1
+}
+
+pub(crate) async fn create_group(
+	state: AppState,
+	signer: ISigner,
+	payload: ICreateGroupPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_payload_signer(&signer, b"create-group", &payload)?;
+	let ICreateGroupPayload {
+		group,
+		name,
+		members,
+	} = payload;
+
+	if group_size(&signer.address, &members) > MAXIMUM_GROUP_MEMBERS as usize {
+		return Err(irpcerror::MEMBERS_LEN_DONT_FIT_RANGE)
+	}
+	if name.chars().count() > MAXIMUM_GROUP_NAME_LEN {
+		return Err(irpcerror::NAME_LEN_DONT_FIT_RANGE)
+	}
+
+	let mut database = state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?;
+	let id = bs58::encode(group).into_string();
+	let now = Utc::now().naive_utc();
+
+	let tx = database
+		.transaction()
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	if tx
+		.execute(include_str!("../sql/create-group.sql"), &[&id, &name, &now])
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))? ==
+		0
+	{
+		return Err(irpcerror::GROUP_ALREADY_EXISTS)
+	}
+
+	tx.execute(
+		include_str!("../sql/add-group-member.sql"),
+		&[
+			&id,
+			&bs58::encode(signer.address).into_string(),
+			&true,
+			&now,
+		],
+	)
+	.await
+	.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+	tx.execute(
+		include_str!("../sql/add-group-members.sql"),
+		&[
+			&id,
+			&members
+				.iter()
+				.filter(|x| **x != signer.address)
+				.map(|x| bs58::encode(x).into_string())
+				.collect::<Vec<_>>(),
+			&now,
+		],
+	)
+	.await
+	.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	tx.commit().await.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	Ok(IRPCResponse::CreateGroup)
+}
+
+pub(crate) async fn invite_member(
+	state: AppState,
+	signer: ISigner,
+	payload: IInviteMemberPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_payload_signer(&signer, b"invite-member", &payload)?;
+	let IInviteMemberPayload {
+		group,
+		member,
+		admin,
+	} = payload;
+
+	if !group_admin(&state, &group, &signer.address).await? {
+		return Err(irpcerror::NOT_GROUP_ADMIN)
+	}
+
+	if group_members(&state, &group).await?.len() >= MAXIMUM_GROUP_MEMBERS as usize {
+		return Err(irpcerror::MEMBERS_LEN_DONT_FIT_RANGE)
+	}
+
+	state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.execute(
+			include_str!("../sql/add-group-member.sql"),
+			&[
+				&bs58::encode(group).into_string(),
+				&bs58::encode(member).into_string(),
+				&admin,
+				&Utc::now().naive_utc(),
+			],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	Ok(IRPCResponse::InviteMember)
+}
+
+/// Promotes or demotes an existing member, the group keeps an admin either way.
+pub(crate) async fn set_member_role(
+	state: AppState,
+	signer: ISigner,
+	payload: ISetMemberRolePayload,
+) -> Result<IRPCResponse, i32> {
+	verify_payload_signer(&signer, b"set-member-role", &payload)?;
+	let ISetMemberRolePayload {
+		group,
+		member,
+		admin,
+	} = payload;
+
+	if !group_admin(&state, &group, &signer.address).await? {
+		return Err(irpcerror::NOT_GROUP_ADMIN)
+	}
+
+	let database = state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?;
+	let group = bs58::encode(group).into_string();
+
+	if database
+		.execute(
+			include_str!("../sql/update-group-member.sql"),
+			&[&group, &bs58::encode(member).into_string(), &admin],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))? ==
+		0
+	{
+		return Err(irpcerror::NOT_GROUP_MEMBER)
+	}
+	database
+		.execute(include_str!("../sql/promote-group-admin.sql"), &[&group])
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	Ok(IRPCResponse::SetMemberRole)
+}
+
+pub(crate) async fn remove_member(
+	state: AppState,
+	signer: ISigner,
+	payload: IRemoveMemberPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_payload_signer(&signer, b"remove-member", &payload)?;
+	let IRemoveMemberPayload { group, member } = payload;
+
+	if !group_admin(&state, &group, &signer.address).await? {
+		return Err(irpcerror::NOT_GROUP_ADMIN)
+	}
+
+	drop_group_member(&state, &group, &member).await?;
+
+	Ok(IRPCResponse::RemoveMember)
+}
+
+pub(crate) async fn leave_group(
+	state: AppState,
+	signer: ISigner,
+	payload: ILeaveGroupPayload,
+) -> Result<IRPCResponse, i32> {
+	verify_payload_signer(&signer, b"leave-group", &payload)?;
+	let ILeaveGroupPayload { group } = payload;
+
+	group_admin(&state, &group, &signer.address).await?;
+	drop_group_member(&state, &group, &signer.address).await?;
+
+	Ok(IRPCResponse::LeaveGroup)
+}
+
+pub(crate) async fn list_groups(state: AppState, signer: ISigner) -> Result<IRPCResponse, i32> {
+	verify_signer(&signer, b"list-groups")?;
+
+	let rows = state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?
+		.query(
+			include_str!("../sql/get-groups.sql"),
+			&[&bs58::encode(signer.address).into_string()],
+		)
+		.await
+		.or(Err(irpcerror::DATABASE_READ_ERROR))?;
+
+	// One row per member, the rows of a group follow each other.
+	let mut groups = Vec::<IGroup>::new();
+	for row in rows {
+		let id: GroupId = bs58::decode(row.get::<_, &str>(0))
+			.into_vec()
+			.unwrap()
+			.try_into()
+			.unwrap();
+		let member = IGroupMember {
+			address: VerifyingKey::try_from(
+				bs58::decode(row.get::<_, &str>(2))
+					.into_vec()
+					.unwrap()
+					.as_slice(),
+			)
+			.unwrap(),
+			admin: row.get(3),
+		};
+
+		match groups.last_mut() {
+			Some(group) if group.id == id => group.members.push(member),
+			_ => groups.push(IGroup {
+				id,
+				name: row.get(1),
+				members: vec![member],
+			}),
+		}
+	}
+
+	Ok(IRPCResponse::ListGroups(groups))
+}
+
+/// Stores one copy of the data and fans it out to the inbox of every other member. Each copy gets
+/// its own hash so members acknowledge and delete them independently.
+pub(crate) async fn send_group_message(
+	state: AppState,
+	signer: ISigner,
+	payload: ISendGroupMessagePayload,
+) -> Result<IRPCResponse, i32> {
+	verify_payload_signer(&signer, b"send-group-message", &payload)?;
+	let ISendGroupMessagePayload { group, data, ttl } = payload;
+	group_admin(&state, &group, &signer.address).await?;
+
+	let sender = signer.address;
+	let now = Utc::now();
+	let datetime = Utc
+		.timestamp_micros(signer.timestamp)
+		.single()
+		.ok_or(irpcerror::DESERIALIZATION_ERROR)?;
+	let expires_at = expires_at(now, ttl)?;
+
+	let hash = {
+		let mut hasher: Sha512 = Digest::new();
+		hasher.update(b"send-group-message");
+		hasher.update(signer.salt);
+		hasher.update(signer.timestamp.to_le_bytes());
+		hasher.finalize()
+	};
+
+	let data_hash = {
+		let mut hasher: Sha512 = Digest::new();
+		hasher.update(&data);
+		hasher.finalize()
+	};
+
+	let members = group_members(&state, &group).await?;
+	let mut database = state
+		.pool
+		.get()
+		.await
+		.or(Err(irpcerror::DATABASE_POOL_ERROR))?;
+	let tx = database
+		.transaction()
+		.await
+		.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+	store_data(&tx, &data_hash, data).await?;
+
+	let mut delivered = Vec::<IMessage>::new();
+	for IGroupMember {
+		address: receiver, ..
+	} in members
+	{
+		if receiver == sender ||
+			tx.query_opt(
+				include_str!("../sql/is-blocked.sql"),
+				&[
+					&bs58::encode(receiver).into_string(),
+					&bs58::encode(sender).into_string(),
+				],
+			)
+			.await
+			.or(Err(irpcerror::DATABASE_READ_ERROR))?
+			.is_some()
+		{
+			continue;
+		}
+
+		let hash = {
+			let mut hasher: Sha512 = Digest::new();
+			hasher.update(hash);
+			hasher.update(receiver.as_bytes());
+			hasher.finalize()
+		};
+
+		if tx
+			.execute(
+				include_str!("../sql/send-group-message.sql"),
+				&[
+					&bs58::encode(hash).into_string(),
+					&bs58::encode(sender).into_string(),
+					&bs58::encode(receiver).into_string(),
+					&bs58::encode(data_hash).into_string(),
+					&datetime.naive_utc(),
+					&expires_at.naive_utc(),
+					&bs58::encode(group).into_string(),
+				],
+			)
+			.await
+			.or(Err(irpcerror::DATABASE_WRITE_ERROR))? ==
+			0
+		{
+			continue;
+		}
+
+		delivered.push(IMessage {
+			hash: hash.into(),
+			sender,
+			receiver,
+			data_hash: data_hash.into(),
+			timestamp: signer.timestamp,
+			sealed: false,
+			group: Some(group),
+			device: None,
+		});
+	}
+	tx.commit().await.or(Err(irpcerror::DATABASE_WRITE_ERROR))?;
+
+	let mut websockets = state.websockets.write().await;
+	for message in delivered {
+		if let Some(senders) = websockets.get_mut(message.receiver.as_bytes()) {
+			senders.retain(|tx| {
+				tx.send(IWebSocketResponse::NewMessage(message.clone()))
+					.is_ok()
+			});
+		}
+	}
+
+	Ok(IRPCResponse::SendGroupMessage)
+}
+
+pub(crate) async fn handler(
+	State(state): State<AppState>,
+	Borsh((id, data)): Borsh<(u128, IRPCRequest)>,
+) -> Response {
+	let res: Result<IRPCResponse, i32> = match data {
+		IRPCRequest::SendMessage(signer, payload) => send_message(state, signer, payload).await,
+		IRPCRequest::GetMessages(signer, payload) => get_messages(state, signer, payload).await,
+		IRPCRequest::DeleteMessages(signer, payload) => {
+			delete_messages(state, signer, payload).await
+		},
+		IRPCRequest::Block(signer, payload) => block(state, signer, payload).await,
+		IRPCRequest::Unblock(signer, payload) => unblock(state, signer, payload).await,
+		IRPCRequest::ListBlocked(signer, payload) => list_blocked(state, signer, payload).await,
+		IRPCRequest::PublishSignedPrekey(signer, payload) => {
+			publish_signed_prekey(state, signer, payload).await
+		},
+		IRPCRequest::PublishOneTimePrekeys(signer, payload) => {
+			publish_one_time_prekeys(state, signer, payload).await
+		},
+		IRPCRequest::CountOneTimePrekeys(signer, payload) => {
+			count_one_time_prekeys(state, signer, payload).await
+		},
+		IRPCRequest::FetchPrekeyBundle(caller, payload) => {
+			fetch_prekey_bundle(state, caller, payload).await
+		},
+		IRPCRequest::RegisterDevice(signer, payload) => {
+			register_device(state, signer, payload).await
+		},
+		IRPCRequest::SendSealedMessage(payload) => send_sealed_message(state, payload).await,
+		IRPCRequest::CreateGroup(signer, payload) => create_group(state, signer, payload).await,
+		IRPCRequest::InviteMember(signer, payload) => invite_member(state, signer, payload).await,
+		IRPCRequest::RemoveMember(signer, payload) => remove_member(state, signer, payload).await,
+		IRPCRequest::LeaveGroup(signer, payload) => leave_group(state, signer, payload).await,
+		IRPCRequest::ListGroups(signer) => list_groups(state, signer).await,
+		IRPCRequest::SendGroupMessage(signer, payload) => {
+			send_group_message(state, signer, payload).await
+		},
+		IRPCRequest::ListDevices(caller, payload) => list_devices(state, caller, payload).await,
+		IRPCRequest::SetMemberRole(signer, payload) => {
+			set_member_role(state, signer, payload).await
+		},
+	};
+
+	Borsh((id, res)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, ed25519_dalek::SigningKey};
+
+	#[test]
+	fn anonymous_lookup() {
+		let address = SigningKey::from_bytes(&[1; 32]).verifying_key();
+		let other = SigningKey::from_bytes(&[2; 32]).verifying_key();
+		let payload = IListDevicesPayload { address };
+		let timestamp = Utc::now().timestamp_micros();
+
+		// A proof for one target doesn't carry over to another.
+		let nonce = (0..)
+			.find(|nonce| {
+				leading_zeros(&lookup_digest(b"list-devices", &payload, timestamp, *nonce)) >=
+					LOOKUP_DIFFICULTY &&
+					leading_zeros(&lookup_digest(
+						b"list-devices",
+						&IListDevicesPayload { address: other },
+						timestamp,
+						*nonce,
+					)) < LOOKUP_DIFFICULTY
+			})
+			.unwrap();
+		let caller = ICaller::Anonymous { timestamp, nonce };
+
+		assert_eq!(verify_caller(&caller, b"list-devices", &payload), Ok(()));
+		assert_eq!(
+			verify_caller(
+				&caller,
+				b"list-devices",
+				&IListDevicesPayload { address: other }
+			),
+			Err(irpcerror::INVALID_PROOF_OF_WORK)
+		);
+
+		let stale = timestamp - 60_000_000;
+		let nonce = (0..)
+			.find(|nonce| {
+				leading_zeros(&lookup_digest(b"list-devices", &payload, stale, *nonce)) >=
+					LOOKUP_DIFFICULTY
+			})
+			.unwrap();
+		assert_eq!(
+			verify_caller(
+				&ICaller::Anonymous {
+					timestamp: stale,
+					nonce
+				},
+				b"list-devices",
+				&payload
+			),
+			Err(irpcerror::MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET_EXCCEED)
+		);
+	}
+
+	#[test]
+	fn message_ttl() {
+		let now = Utc::now();
+
+		assert_eq!(expires_at(now, None), Ok(now + MAXIMUM_MESSAGE_TTL));
+		assert_eq!(expires_at(now, Some(60)), Ok(now + Duration::from_secs(60)));
+		assert_eq!(
+			expires_at(now, Some(u32::MAX)),
+			Ok(now + MAXIMUM_MESSAGE_TTL)
+		);
+		assert_eq!(expires_at(now, Some(0)), Err(irpcerror::TTL_DONT_FIT_RANGE));
+	}
+
+	#[test]
+	fn sealed_proof() {
+		let receiver = SigningKey::from_bytes(&[1; 32]).verifying_key();
+		let data_hash: Sha512Data = Sha512::digest(b"data").into();
+		let device = Some([2; 16]);
+		let now = Utc::now();
+		let timestamp = now.timestamp_micros();
+
+		// The proof covers the device. A lower difficulty keeps the search short in debug builds.
+		let difficulty = 12;
+		assert!((0..).any(|nonce| {
+			leading_zeros(&sealed_hash(
+				&receiver, &data_hash, timestamp, nonce, device,
+			)) >= difficulty &&
+				leading_zeros(&sealed_hash(&receiver, &data_hash, timestamp, nonce, None)) <
+					difficulty
+		}));
+
+		let weak = (0..)
+			.find(|nonce| {
+				leading_zeros(&sealed_hash(
+					&receiver, &data_hash, timestamp, *nonce, device,
+				)) < SEALED_MESSAGE_DIFFICULTY
+			})
+			.unwrap();
+		assert_eq!(
+			verify_sealed(now, &receiver, &data_hash, timestamp, weak, device),
+			Err(irpcerror::INVALID_PROOF_OF_WORK)
+		);
+		// Replays within the window hit the unique message hash, later ones are too old.
+		assert_eq!(
+			verify_sealed(
+				now + MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET * 2,
+				&receiver,
+				&data_hash,
+				timestamp,
+				weak,
+				device
+			),
+			Err(irpcerror::MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET_EXCCEED)
+		);
+	}
+
+	#[test]
+	fn group_members() {
+		let creator = SigningKey::from_bytes(&[1; 32]).verifying_key();
+		let members = (2..=MAXIMUM_GROUP_MEMBERS)
+			.map(|x| {
+				SigningKey::from_bytes(&Sha512::digest(x.to_le_bytes())[..32].try_into().unwrap())
+					.verifying_key()
+			})
+			.collect::<Vec<_>>();
+
+		// The creator fills the last seat, and listing anyone twice doesn't take another.
+		assert_eq!(
+			group_size(&creator, &members),
+			MAXIMUM_GROUP_MEMBERS as usize
+		);
+		let repeated = [members.as_slice(), &members[..1], &[creator]].concat();
+		assert_eq!(
+			group_size(&creator, &repeated),
+			MAXIMUM_GROUP_MEMBERS as usize
+		);
+		let extra = [
+			members.as_slice(),
+			&[SigningKey::from_bytes(&[0; 32]).verifying_key()],
+		]
+		.concat();
+		assert_eq!(
+			group_size(&creator, &extra),
+			MAXIMUM_GROUP_MEMBERS as usize + 1
+		);
+	}
 }
@@ -1,4 +1,5 @@
 use {
+	crate::ratelimit::RateLimiter,
 	axum::{
 		async_trait,
 		body::Bytes,
@@ -8,7 +9,10 @@ use {
 	},
 	borsh::{BorshDeserialize, BorshSerialize},
 	project_toot_sdk::IWebSocketResponse,
-	std::{collections::HashMap, sync::Arc},
+	std::{
+		collections::HashMap,
+		sync::{Arc, Mutex},
+	},
 	tokio::sync::{mpsc::UnboundedSender, RwLock},
 };
 
@@ -74,23 +78,18 @@ impl<T: BorshSerialize + BorshDeserialize> IntoResponse for Borsh<T> {
 }
 
 fn borsh_content_type(headers: &HeaderMap) -> bool {
-	if let Some(content_type) = headers.get(CONTENT_TYPE) {
-		if let Ok("application/borsh") = content_type.to_str() {
-			true
-		} else {
-			false
-		}
-	} else {
-		false
-	}
+	headers
+		.get(CONTENT_TYPE)
+		.is_some_and(|x| matches!(x.to_str(), Ok("application/borsh")))
 }
 
 pub(crate) type DBManager = bb8_postgres::PostgresConnectionManager<tokio_postgres::NoTls>;
 pub(crate) type DBPool = bb8::Pool<DBManager>;
-pub(crate) type WebSockets = HashMap<[u8; 32], UnboundedSender<IWebSocketResponse>>;
+pub(crate) type WebSockets = HashMap<[u8; 32], Vec<UnboundedSender<IWebSocketResponse>>>;
 
 #[derive(Clone, Debug)]
 pub(crate) struct AppState {
 	pub pool: DBPool,
 	pub websockets: Arc<RwLock<WebSockets>>,
+	pub lookups: Arc<Mutex<RateLimiter>>,
 }
@@ -33,62 +33,63 @@ async fn handle_socket(state: AppState, socket: ws::WebSocket) {
 	});
 
 	while let Some(Ok(msg)) = ws_rx.next().await {
-		match msg {
-			ws::Message::Binary(bytes) => {
-				if let Ok(data) = borsh::from_slice::<IWebSocketRequest>(&bytes) {
-					match data {
-						IWebSocketRequest::Authorize(ISigner {
-							address,
-							salt,
-							timestamp,
-							signature,
-						}) => {
-							let now = Utc::now();
-							let datetime =
-								if let Some(ret) = Utc.timestamp_micros(timestamp).single() {
-									ret
-								} else {
-									continue;
-								};
-							if datetime < (now - MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET) ||
-								datetime > now
-							{
-								continue;
-							}
+		if let ws::Message::Binary(bytes) = msg {
+			if let Ok(data) = borsh::from_slice::<IWebSocketRequest>(&bytes) {
+				match data {
+					IWebSocketRequest::Authorize(ISigner {
+						address,
+						salt,
+						timestamp,
+						signature,
+					}) => {
+						let now = Utc::now();
+						let datetime = if let Some(ret) = Utc.timestamp_micros(timestamp).single() {
+							ret
+						} else {
+							continue;
+						};
+						if datetime < (now - MAXIMUM_CONFIRMATION_TIMESTAMP_OFFSET) ||
+							datetime > now
+						{
+							continue;
+						}
 
-							if address
-								.verify_prehashed_strict(
-									{
-										let mut hasher: Sha512 = Digest::new();
-										hasher.update(b"authorize");
-										hasher.update(&salt);
-										hasher.update(timestamp.to_le_bytes());
-										hasher
-									},
-									None,
-									&signature,
-								)
-								.is_err()
-							{
-								continue;
-							}
+						if address
+							.verify_prehashed_strict(
+								{
+									let mut hasher: Sha512 = Digest::new();
+									hasher.update(b"authorize");
+									hasher.update(salt);
+									hasher.update(timestamp.to_le_bytes());
+									hasher
+								},
+								None,
+								&signature,
+							)
+							.is_err()
+						{
+							continue;
+						}
 
-							state
-								.websockets
-								.write()
-								.await
-								.entry(address.to_bytes())
-								.or_insert(tx.clone());
+						let mut websockets = state.websockets.write().await;
+						let senders = websockets.entry(address.to_bytes()).or_default();
+						if !senders.iter().any(|x| x.same_channel(&tx)) {
+							senders.push(tx.clone());
+						}
+						drop(websockets);
 
-							let _ = tx.send(IWebSocketResponse::Authorized);
-						},
-					}
+						let _ = tx.send(IWebSocketResponse::Authorized);
+					},
 				}
-			},
-			_ => (),
+			}
 		}
 	}
 
+	state.websockets.write().await.retain(|_, senders| {
+		senders.retain(|x| !x.same_channel(&tx));
+		!senders.is_empty()
+	});
+
 	if !resp_handle.is_finished() {
 		resp_handle.abort();
 	}